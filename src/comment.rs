@@ -0,0 +1,989 @@
+use crate::parser::{Comment, Example, NoteKind};
+
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// How many times aliases can expand to other aliases before giving up on a recursive one
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// Expand the `@name` or `\name` commands of `text` that are `aliases`, replacing `\1` in their
+/// markdown by the argument of `@name{argument}`, until no aliases are left. Aliases still
+/// expanding to aliases after `MAX_ALIAS_DEPTH` levels are an error.
+pub fn expand_aliases(text: &str, aliases: &BTreeMap<String, String>) -> Result<String, String> {
+    static COMMAND: OnceLock<Regex> = OnceLock::new();
+
+    let command = COMMAND.get_or_init(|| Regex::new(r"[@\\]([A-Za-z_]\w*)(\{[^{}]*\})?").unwrap());
+    let mut text = text.to_string();
+
+    for depth in 0..=MAX_ALIAS_DEPTH {
+        let found = command
+            .captures_iter(&text)
+            .filter(|c| aliases.contains_key(&c[1]))
+            // The `@` of email addresses and the like
+            .filter(|c| !text[..c.get(0).unwrap().start()].ends_with(|c: char| c.is_alphanumeric()))
+            .collect::<Vec<_>>();
+
+        let Some(first) = found.first() else {
+            return Ok(text);
+        };
+
+        if depth == MAX_ALIAS_DEPTH {
+            return Err(format!(
+                "the alias “{}” still expands to aliases after {MAX_ALIAS_DEPTH} levels, it is \
+                 probably recursive",
+                &first[1]
+            ));
+        }
+
+        let mut out = String::new();
+        let mut last = 0;
+
+        for captures in &found {
+            let whole = captures.get(0).unwrap();
+            let markdown = &aliases[&captures[1]];
+
+            out.push_str(&text[last..whole.start()]);
+            last = whole.end();
+
+            match captures.get(2) {
+                // Aliases without an argument leave the braces after them alone
+                Some(braces) if !markdown.contains("\\1") => {
+                    out.push_str(markdown);
+                    out.push_str(braces.as_str());
+                }
+                braces => {
+                    let argument = braces.map_or("", |b| &b.as_str()[1..b.len() - 1]);
+                    out.push_str(&markdown.replace("\\1", argument));
+                }
+            }
+        }
+
+        out.push_str(&text[last..]);
+        text = out;
+    }
+
+    unreachable!()
+}
+
+/// Remove the comment markers (`///`, `//!`, `/**`, ` * `, `*/`...) from a raw comment,
+/// keeping the indentation of the text that follows them. The `<` of trailing member comments
+/// (`///<`, `/**<`...) goes with the marker.
+fn strip_markers(raw: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+
+    for line in raw.lines() {
+        let mut line = line.trim_start();
+
+        for marker in ["///", "//!", "//", "/**", "/*!", "/*"] {
+            if let Some(rest) = line.strip_prefix(marker) {
+                line = match marker {
+                    "///" | "//!" | "/**" | "/*!" => rest.strip_prefix('<').unwrap_or(rest),
+                    _ => rest,
+                };
+                break;
+            }
+        }
+
+        let line = line.trim_end();
+        let line = line.strip_suffix("*/").unwrap_or(line).trim_end();
+
+        // Continuation lines of block comments
+        let line = match line.trim_start().strip_prefix('*') {
+            Some(rest) if !rest.starts_with('*') => rest,
+            _ => line,
+        };
+
+        lines.push(line.strip_prefix(' ').unwrap_or(line));
+    }
+
+    lines
+}
+
+/// If `line` starts with the command `name` (`@name` or `\name`), return its argument
+pub fn command<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let rest = line
+        .strip_prefix('@')
+        .or_else(|| line.strip_prefix('\\'))?
+        .strip_prefix(name)?;
+
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// If `line` is a `@param name text` command, possibly with a direction (`@param[in,out]`),
+/// return the direction and the argument
+fn param_command(line: &str) -> Option<(Option<String>, &str)> {
+    let rest = line
+        .strip_prefix('@')
+        .or_else(|| line.strip_prefix('\\'))?
+        .strip_prefix("param")?;
+
+    let (direction, rest) = match rest.strip_prefix('[') {
+        Some(rest) => {
+            let (direction, rest) = rest.split_once(']')?;
+            let direction = direction.split(',').map(str::trim).collect::<Vec<_>>().join(",");
+
+            (Some(direction), rest)
+        }
+        None => (None, rest),
+    };
+
+    rest.starts_with(char::is_whitespace)
+        .then(|| (direction, rest.trim()))
+}
+
+/// If `line` starts a `@code` block, return its language: `cpp` for `@code{.cpp}`, empty
+/// without one
+fn code_command(line: &str) -> Option<&str> {
+    let rest = line
+        .strip_prefix('@')
+        .or_else(|| line.strip_prefix('\\'))?
+        .strip_prefix("code")?;
+
+    match rest.strip_prefix('{') {
+        Some(language) => Some(language.split_once('}')?.0.trim().trim_start_matches('.')),
+        None => (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(""),
+    }
+}
+
+/// If `line` is an `@example file` command, possibly with options (`@example{doctest}`),
+/// return the file and whether it is a doctest
+fn example_command(line: &str) -> Option<(&str, bool)> {
+    let rest = line
+        .strip_prefix('@')
+        .or_else(|| line.strip_prefix('\\'))?
+        .strip_prefix("example")?;
+
+    let (options, rest) = match rest.strip_prefix('{') {
+        Some(rest) => rest.split_once('}')?,
+        None => ("", rest),
+    };
+
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let file = rest.split_whitespace().next()?;
+
+    Some((file, options.split(',').any(|option| option.trim() == "doctest")))
+}
+
+/// The command whose description the following lines continue
+#[derive(Clone, Copy)]
+enum Continued {
+    Param,
+    Returns,
+    Retval,
+    Throws,
+    TParam,
+    Deprecated,
+    Precondition,
+    Postcondition,
+    Note,
+}
+
+/// Parse the raw comment `raw`, expanding the `aliases` of the configuration
+/// (`[comments.aliases]`) first
+pub fn parse_comment(raw: String, aliases: &BTreeMap<String, String>) -> Comment {
+    let mut ret = Comment {
+        description: String::new(),
+        brief: String::new(),
+        impl_: None,
+        since: None,
+        params: Vec::new(),
+        returns: None,
+        retvals: Vec::new(),
+        throws: Vec::new(),
+        tparams: Vec::new(),
+        see_also: Vec::new(),
+        notes: Vec::new(),
+        deprecated: None,
+        groups: Vec::new(),
+        copydoc: None,
+        auto_brief: false,
+        internal: false,
+        preconditions: Vec::new(),
+        postconditions: Vec::new(),
+        examples: Vec::new(),
+    };
+
+    // The lines after a `@param`, `@tparam`, `@return`, `@retval`, `@throws`, `@deprecated`,
+    // `@pre`, `@post` or admonition continue its description, up to a blank line or a command
+    let mut continued = None;
+
+    // `@code` and `@verbatim` blocks become fenced code blocks, their lines kept as they are
+    let mut block_end = None;
+
+    // `@internal` sections, up to `@endinternal` or the end of the comment, are kept apart
+    let mut in_internal = false;
+    let mut seen_text = false;
+
+    // Aliases are expanded first, so they can stand for any command. Recursive ones are
+    // reported when the configuration is read, the text is kept as it is if one gets through.
+    let text = strip_markers(&raw).join("\n");
+    let text = expand_aliases(&text, aliases).unwrap_or(text);
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+
+        let first_text = !seen_text && !trimmed.is_empty();
+        seen_text |= !trimmed.is_empty();
+
+        if in_internal {
+            if command(trimmed, "endinternal").is_some() {
+                in_internal = false;
+            } else {
+                let text = &mut ret.notes.last_mut().unwrap().1;
+
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+
+                text.push_str(line);
+            }
+
+            continue;
+        }
+
+        if let Some(end) = block_end {
+            if command(trimmed, end).is_some() {
+                ret.description.push_str("```\n");
+                block_end = None;
+            } else {
+                ret.description.push_str(line);
+                ret.description.push('\n');
+            }
+
+            continue;
+        }
+
+        if let Some(text) = command(trimmed, "internal") {
+            ret.notes.push((NoteKind::Internal, text.to_string()));
+            ret.internal |= first_text;
+            in_internal = true;
+            continued = None;
+            continue;
+        }
+
+        let block = match code_command(trimmed) {
+            Some(language) => Some(("endcode", language)),
+            None => command(trimmed, "verbatim").map(|_| ("endverbatim", "text")),
+        };
+
+        if let Some((end, language)) = block {
+            ret.description.push_str(&format!("```{language}\n"));
+            block_end = Some(end);
+            continued = None;
+            continue;
+        }
+
+        if let Some((direction, argument)) = param_command(trimmed) {
+            let (name, description) = argument
+                .split_once(char::is_whitespace)
+                .unwrap_or((argument, ""));
+
+            let description = match direction {
+                Some(direction) => format!("[{direction}] {}", description.trim()),
+                None => description.trim().to_string(),
+            };
+
+            ret.params.push((name.to_string(), description.trim_end().to_string()));
+            continued = Some(Continued::Param);
+            continue;
+        }
+
+        if let Some(argument) = command(trimmed, "tparam") {
+            let (name, description) = argument
+                .split_once(char::is_whitespace)
+                .unwrap_or((argument, ""));
+
+            ret.tparams.push((name.to_string(), description.trim().to_string()));
+            continued = Some(Continued::TParam);
+            continue;
+        }
+
+        let returns = command(trimmed, "return").or_else(|| command(trimmed, "returns"));
+
+        if let Some(description) = returns {
+            ret.returns = Some(description.to_string());
+            continued = Some(Continued::Returns);
+            continue;
+        }
+
+        if let Some(argument) = command(trimmed, "retval") {
+            let (value, description) = argument
+                .split_once(char::is_whitespace)
+                .unwrap_or((argument, ""));
+
+            ret.retvals.push((value.to_string(), description.trim().to_string()));
+            continued = Some(Continued::Retval);
+            continue;
+        }
+
+        let throws = ["throws", "throw", "exception"]
+            .iter()
+            .find_map(|name| command(trimmed, name));
+
+        if let Some(argument) = throws {
+            let (type_, description) = argument
+                .split_once(char::is_whitespace)
+                .unwrap_or((argument, ""));
+
+            add_throws(&mut ret.throws, type_.to_string(), description.trim().to_string());
+            continued = Some(Continued::Throws);
+            continue;
+        }
+
+        if let Some(message) = command(trimmed, "deprecated") {
+            ret.deprecated = Some(message.to_string());
+            continued = Some(Continued::Deprecated);
+            continue;
+        }
+
+        if let Some(condition) = command(trimmed, "pre") {
+            ret.preconditions.push(condition.to_string());
+            continued = Some(Continued::Precondition);
+            continue;
+        }
+
+        if let Some(condition) = command(trimmed, "post") {
+            ret.postconditions.push(condition.to_string());
+            continued = Some(Continued::Postcondition);
+            continue;
+        }
+
+        let note = [
+            ("note", NoteKind::Note),
+            ("warning", NoteKind::Warning),
+            ("attention", NoteKind::Attention),
+            ("bug", NoteKind::Bug),
+        ]
+        .into_iter()
+        .find_map(|(name, kind)| command(trimmed, name).map(|text| (kind, text)));
+
+        if let Some((kind, text)) = note {
+            ret.notes.push((kind, text.to_string()));
+            continued = Some(Continued::Note);
+            continue;
+        }
+
+        let is_text = !trimmed.is_empty() && !trimmed.starts_with(['@', '\\']);
+
+        // Admonitions are markdown, their lines are kept
+        if let Some(Continued::Note) = continued.filter(|_| is_text) {
+            let text = &mut ret.notes.last_mut().unwrap().1;
+
+            if !text.is_empty() {
+                text.push('\n');
+            }
+
+            text.push_str(trimmed);
+            continue;
+        }
+
+        if let Some(block) = continued.filter(|_| is_text) {
+            let description = match block {
+                Continued::Param => &mut ret.params.last_mut().unwrap().1,
+                Continued::Returns => ret.returns.as_mut().unwrap(),
+                Continued::Retval => &mut ret.retvals.last_mut().unwrap().1,
+                Continued::Throws => &mut ret.throws.last_mut().unwrap().1,
+                Continued::TParam => &mut ret.tparams.last_mut().unwrap().1,
+                Continued::Deprecated => ret.deprecated.as_mut().unwrap(),
+                Continued::Precondition => ret.preconditions.last_mut().unwrap(),
+                Continued::Postcondition => ret.postconditions.last_mut().unwrap(),
+                Continued::Note => unreachable!(),
+            };
+
+            if !description.is_empty() {
+                description.push(' ');
+            }
+
+            description.push_str(trimmed);
+            continue;
+        }
+
+        continued = None;
+
+        if let Some(brief) = command(trimmed, "brief") {
+            ret.brief = brief.to_string();
+        } else if let Some(name) = command(trimmed, "impl") {
+            ret.impl_.get_or_insert_with(Vec::new).push(name.to_string());
+        } else if let Some(version) = command(trimmed, "since") {
+            ret.since = Some(version.to_string());
+        } else if let Some(entry) = command(trimmed, "see").or_else(|| command(trimmed, "sa")) {
+            ret.see_also.push(entry.to_string());
+        } else if let Some((file, doctest)) = example_command(trimmed) {
+            ret.examples.push(Example {
+                file: file.to_string(),
+                doctest,
+                code: None,
+            });
+        } else if let Some(target) = command(trimmed, "copydoc") {
+            ret.copydoc = Some(target.to_string());
+        } else if let Some(names) = command(trimmed, "ingroup") {
+            ret.groups.extend(names.split_whitespace().map(str::to_string));
+        } else if command(trimmed, "defgroup").is_some() {
+            // Read by `defined_groups`, whatever the comment is attached to
+        } else {
+            ret.description.push_str(line);
+            ret.description.push('\n');
+        }
+    }
+
+    ret.notes
+        .retain(|(kind, text)| *kind != NoteKind::Internal || !text.trim().is_empty());
+
+    // An unterminated block ends with the comment
+    if block_end.is_some() {
+        ret.description.truncate(ret.description.trim_end().len());
+        ret.description.push_str("\n```\n");
+    }
+
+    ret.description = ret.description.trim().to_string();
+
+    if ret.brief.is_empty() {
+        ret.brief = first_sentence(&ret.description);
+        ret.auto_brief = !ret.brief.is_empty();
+    }
+
+    ret
+}
+
+/// Abbreviations whose period doesn't end a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "e.g.", "i.e.", "etc.", "vs.", "cf.", "approx.", "incl.", "resp.", "viz.", "al.", "no.",
+];
+
+/// The first sentence of `description`, up to a period followed by whitespace or to the end of
+/// its first paragraph, on one line. Periods in inline code, ellipses and the usual
+/// abbreviations don't end it, and a description starting with a code block or a heading has
+/// none.
+fn first_sentence(description: &str) -> String {
+    let paragraph = description.split("\n\n").next().unwrap_or_default().trim();
+
+    if paragraph.starts_with("```") || paragraph.starts_with('#') {
+        return String::new();
+    }
+
+    let mut in_code = false;
+    let mut end = paragraph.len();
+
+    for (i, c) in paragraph.char_indices() {
+        match c {
+            '`' => in_code = !in_code,
+            '.' if !in_code => {
+                let (sentence, rest) = paragraph.split_at(i + 1);
+
+                let word = sentence
+                    .rsplit(char::is_whitespace)
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase();
+
+                if (rest.is_empty() || rest.starts_with(char::is_whitespace))
+                    && !sentence.ends_with("..")
+                    && !ABBREVIATIONS.contains(&word.as_str())
+                {
+                    end = i + 1;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    paragraph[..end].split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The `//!` lines or the `/*! */` block that `body`, the text following the `{` of a
+/// namespace, starts with, and its offset in `body`
+pub fn header_comment(body: &str) -> Option<(usize, &str)> {
+    let is_header_line = |line: &str| line.starts_with("//!") && !line.starts_with("//!<");
+
+    let offset = body.len() - body.trim_start().len();
+    let rest = &body[offset..];
+
+    let len = if rest.starts_with("/*!") && !rest.starts_with("/*!<") {
+        rest.find("*/")? + 2
+    } else if is_header_line(rest) {
+        rest.split_inclusive('\n')
+            .take_while(|line| is_header_line(line.trim_start()))
+            .map(str::len)
+            .sum()
+    } else {
+        return None;
+    };
+
+    Some((offset, rest[..len].trim_end()))
+}
+
+/// The groups defined in a source file by `@defgroup name Title`, with the rest of the comment
+/// defining them. These comments are usually attached to nothing, clang doesn't give them.
+pub fn defined_groups(
+    source: &str,
+    aliases: &BTreeMap<String, String>,
+) -> Vec<(String, String, Comment)> {
+    let mut comments = Vec::new();
+    let mut current = String::new();
+    let mut in_block = false;
+
+    // Runs of `///` or `//!` lines, and `/** */` or `/*! */` blocks
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+
+        if in_block {
+            current.push_str(line);
+            current.push('\n');
+
+            if trimmed.contains("*/") {
+                comments.push(std::mem::take(&mut current));
+                in_block = false;
+            }
+        } else if trimmed.starts_with("///") || trimmed.starts_with("//!") {
+            current.push_str(line);
+            current.push('\n');
+        } else {
+            if !current.is_empty() {
+                comments.push(std::mem::take(&mut current));
+            }
+
+            if trimmed.starts_with("/**") || trimmed.starts_with("/*!") {
+                current.push_str(line);
+                current.push('\n');
+
+                if trimmed[3..].contains("*/") {
+                    comments.push(std::mem::take(&mut current));
+                } else {
+                    in_block = true;
+                }
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        comments.push(current);
+    }
+
+    comments
+        .into_iter()
+        .filter_map(|raw| {
+            let argument = strip_markers(&raw)
+                .into_iter()
+                .find_map(|line| command(line.trim_start(), "defgroup"))?
+                .to_string();
+
+            let (name, title) = argument
+                .split_once(char::is_whitespace)
+                .unwrap_or((&argument, ""));
+
+            Some((name.to_string(), title.trim().to_string(), parse_comment(raw, aliases)))
+        })
+        .collect()
+}
+
+/// Add an exception of `@throws`, to the description of the same type when it's already there
+fn add_throws(throws: &mut Vec<(String, String)>, type_: String, description: String) {
+    match throws.iter_mut().find(|(t, _)| *t == type_) {
+        Some((_, existing)) if existing.is_empty() => *existing = description,
+        Some((_, existing)) if !description.is_empty() && *existing != description => {
+            existing.push(' ');
+            existing.push_str(&description);
+        }
+        Some(_) => {}
+        None => throws.push((type_, description)),
+    }
+}
+
+/// Merge the comment of another declaration of the same entity (the definition in a source file
+/// of a function declared in a header, say) into `into`. The brief and `@since` of `into` are
+/// kept when it has them, unless its brief is a first sentence and `from` has an explicit one,
+/// descriptions are joined when they differ.
+pub fn merge_comments(into: &mut Comment, from: Comment) {
+    // An explicit `@brief` wins over a first sentence
+    if into.brief.is_empty() || (into.auto_brief && !from.auto_brief && !from.brief.is_empty()) {
+        into.brief = from.brief;
+        into.auto_brief = from.auto_brief;
+    }
+
+    if into.description.is_empty() {
+        into.description = from.description;
+    } else if !from.description.is_empty() && from.description != into.description {
+        into.description = format!("{}\n\n{}", into.description, from.description);
+    }
+
+    for name in from.impl_.into_iter().flatten() {
+        let impls = into.impl_.get_or_insert_with(Vec::new);
+
+        if !impls.contains(&name) {
+            impls.push(name);
+        }
+    }
+
+    if into.since.is_none() {
+        into.since = from.since;
+    }
+
+    for (name, description) in from.params {
+        if !into.params.iter().any(|(n, _)| *n == name) {
+            into.params.push((name, description));
+        }
+    }
+
+    if into.returns.is_none() {
+        into.returns = from.returns;
+    }
+
+    for (value, description) in from.retvals {
+        if !into.retvals.iter().any(|(v, _)| *v == value) {
+            into.retvals.push((value, description));
+        }
+    }
+
+    for (type_, description) in from.throws {
+        add_throws(&mut into.throws, type_, description);
+    }
+
+    for (name, description) in from.tparams {
+        if !into.tparams.iter().any(|(n, _)| *n == name) {
+            into.tparams.push((name, description));
+        }
+    }
+
+    for entry in from.see_also {
+        if !into.see_also.contains(&entry) {
+            into.see_also.push(entry);
+        }
+    }
+
+    for note in from.notes {
+        if !into.notes.contains(&note) {
+            into.notes.push(note);
+        }
+    }
+
+    if into.deprecated.as_deref().unwrap_or_default().is_empty() && from.deprecated.is_some() {
+        into.deprecated = from.deprecated;
+    }
+
+    for group in from.groups {
+        if !into.groups.contains(&group) {
+            into.groups.push(group);
+        }
+    }
+
+    if into.copydoc.is_none() {
+        into.copydoc = from.copydoc;
+    }
+
+    for condition in from.preconditions {
+        if !into.preconditions.contains(&condition) {
+            into.preconditions.push(condition);
+        }
+    }
+
+    for condition in from.postconditions {
+        if !into.postconditions.contains(&condition) {
+            into.postconditions.push(condition);
+        }
+    }
+
+    // Documented when any of the declarations is documented publicly
+    into.internal &= from.internal;
+
+    for example in from.examples {
+        if !into.examples.iter().any(|e| e.file == example.file) {
+            into.examples.push(example);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_comment(raw: String) -> Comment {
+        super::parse_comment(raw, &BTreeMap::new())
+    }
+
+    #[test]
+    fn params_are_parsed_with_their_direction() {
+        let comment = parse_comment(
+            "/// Read from the socket\n\
+             /// @param[in, out] buffer Where the bytes go,\n\
+             ///   grown as needed\n\
+             /// \\param timeout_ms Timeout in milliseconds\n\
+             ///\n\
+             /// Blocks until done"
+                .to_string(),
+        );
+
+        assert_eq!(comment.description, "Read from the socket\n\nBlocks until done");
+        assert_eq!(
+            comment.params,
+            [
+                ("buffer".to_string(), "[in,out] Where the bytes go, grown as needed".to_string()),
+                ("timeout_ms".to_string(), "Timeout in milliseconds".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_and_retvals_are_parsed() {
+        let comment = parse_comment(
+            "/// Close the file
+             /// @returns Whether the file was
+             ///   closed
+             /// @retval Status::Ok Closed
+             /// @retval Status::Busy Still in use"
+                .to_string(),
+        );
+
+        assert_eq!(comment.description, "Close the file");
+        assert_eq!(comment.returns.as_deref(), Some("Whether the file was closed"));
+        assert_eq!(comment.retvals[0], ("Status::Ok".to_string(), "Closed".to_string()));
+        assert_eq!(comment.retvals[1], ("Status::Busy".to_string(), "Still in use".to_string()));
+    }
+
+    #[test]
+    fn throws_of_the_same_type_are_merged() {
+        let comment = parse_comment(
+            "/// Connect
+             /// @throws mylib::TimeoutError When the server doesn't answer
+             /// @exception std::bad_alloc
+             /// @throw mylib::TimeoutError or when the handshake is too slow"
+                .to_string(),
+        );
+
+        assert_eq!(
+            comment.throws,
+            [
+                (
+                    "mylib::TimeoutError".to_string(),
+                    "When the server doesn't answer or when the handshake is too slow".to_string()
+                ),
+                ("std::bad_alloc".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn admonitions_keep_their_lines_together() {
+        let comment = parse_comment(
+            "/// Flush the buffer
+             /// @warning Not thread-safe:
+             ///   - lock the buffer first
+             ///   - or use `flush_sync`
+             ///
+             /// Returns immediately
+             /// \\note Cheap when empty
+             /// @bug Loses the last byte"
+                .to_string(),
+        );
+
+        assert_eq!(comment.description, "Flush the buffer\n\nReturns immediately");
+        assert_eq!(
+            comment.notes,
+            [
+                (
+                    NoteKind::Warning,
+                    "Not thread-safe:\n- lock the buffer first\n- or use `flush_sync`".to_string()
+                ),
+                (NoteKind::Note, "Cheap when empty".to_string()),
+                (NoteKind::Bug, "Loses the last byte".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_are_defined_by_any_comment() {
+        let groups = defined_groups(
+            "#pragma once\n\
+             \n\
+             /// @defgroup net Networking\n\
+             /// Sockets and connections\n\
+             \n\
+             /** \\defgroup serialization */\n\
+             \n\
+             /// A socket\n\
+             /// @ingroup net serialization\n\
+             class Socket;\n",
+            &BTreeMap::new(),
+        );
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!((groups[0].0.as_str(), groups[0].1.as_str()), ("net", "Networking"));
+        assert_eq!(groups[0].2.description, "Sockets and connections");
+        assert_eq!((groups[1].0.as_str(), groups[1].1.as_str()), ("serialization", ""));
+
+        let comment = parse_comment("/// A socket\n/// @ingroup net serialization".to_string());
+        assert_eq!(comment.description, "A socket");
+        assert_eq!(comment.groups, ["net", "serialization"]);
+    }
+
+    #[test]
+    fn code_blocks_become_fenced_blocks() {
+        let comment = parse_comment(
+            "/**
+              * Parse a value
+              *
+              * @code{.cpp}
+              * int x = parse(\"1\");
+              *
+              *     // @param inside code
+              * @endcode
+              * \\verbatim
+              *   raw   text
+              * \\endverbatim
+              * @code
+              * run();
+              */"
+                .to_string(),
+        );
+
+        assert_eq!(
+            comment.description,
+            "Parse a value\n\n\
+             ```cpp\nint x = parse(\"1\");\n\n    // @param inside code\n```\n\
+             ```text\n  raw   text\n```\n\
+             ```\nrun();\n```"
+        );
+        assert!(comment.params.is_empty());
+    }
+
+    #[test]
+    fn trailing_markers_are_stripped() {
+        for raw in ["///< TCP port", "//!< TCP port", "/**< TCP port */", "/*!< TCP port */"] {
+            assert_eq!(parse_comment(raw.to_string()).description, "TCP port", "{raw}");
+        }
+
+        assert_eq!(parse_comment("/// <b>bold</b>".to_string()).description, "<b>bold</b>");
+    }
+
+    #[test]
+    fn namespace_headers_are_found() {
+        assert_eq!(
+            header_comment(" //! Networking\n  //! primitives\n\nclass Socket;"),
+            Some((1, "//! Networking\n  //! primitives"))
+        );
+        assert_eq!(
+            header_comment("\n/*! Storage\n */\nvoid open();"),
+            Some((1, "/*! Storage\n */"))
+        );
+        assert_eq!(header_comment(" //!< Trailing"), None);
+        assert_eq!(header_comment("\n/// Socket\nclass Socket;"), None);
+    }
+
+    #[test]
+    fn first_sentence_is_the_brief() {
+        let comment =
+            parse_comment("/** Opens the connection. Retries up to three times. */".to_string());
+
+        assert_eq!(comment.brief, "Opens the connection.");
+        assert_eq!(comment.description, "Opens the connection. Retries up to three times.");
+        assert!(comment.auto_brief);
+
+        let brief = |raw: &str| parse_comment(raw.to_string()).brief;
+
+        assert_eq!(
+            brief("/// Reads a value, e.g. a number, i.e. digits. Then returns."),
+            "Reads a value, e.g. a number, i.e. digits."
+        );
+        assert_eq!(brief("/// Calls `values.size()` first. Then."), "Calls `values.size()` first.");
+        assert_eq!(brief("/// Forwards args... to it. Then."), "Forwards args... to it.");
+        assert_eq!(brief("/// Reads the\n/// whole file\n///\n/// Fast."), "Reads the whole file");
+        assert_eq!(brief("/// Version 1.2 of the protocol."), "Version 1.2 of the protocol.");
+        assert_eq!(brief("/// ```\n/// run();\n/// ```"), "");
+
+        let comment = parse_comment("/// Opens it.\n/// @brief Opens the file".to_string());
+        assert_eq!(comment.brief, "Opens the file");
+        assert_eq!(comment.description, "Opens it.");
+        assert!(!comment.auto_brief);
+
+        let mut declaration = parse_comment("/// Opens it. Quickly.".to_string());
+        merge_comments(&mut declaration, parse_comment("/// @brief Opens the file".to_string()));
+        assert_eq!(declaration.brief, "Opens the file");
+        assert!(!declaration.auto_brief);
+    }
+
+    #[test]
+    fn conditions_are_parsed() {
+        let comment = parse_comment(
+            "/// Clear the buffer
+             /// @pre buffer != nullptr
+             /// @pre size < capacity,
+             ///   checked in debug builds
+             /// @post size() == 0
+             ///
+             /// Keeps the capacity"
+                .to_string(),
+        );
+
+        assert_eq!(
+            comment.preconditions,
+            ["buffer != nullptr", "size < capacity, checked in debug builds"]
+        );
+        assert_eq!(comment.postconditions, ["size() == 0"]);
+        assert_eq!(comment.description, "Clear the buffer\n\nKeeps the capacity");
+    }
+
+    #[test]
+    fn internal_sections_are_kept_apart() {
+        let comment = parse_comment(
+            "/// Flush the buffer
+             /// @internal Called by the destructor,
+             ///   @param ignored inside
+             /// @endinternal
+             /// @returns The bytes written
+             /// @internal
+             /// Unterminated"
+                .to_string(),
+        );
+
+        assert_eq!(comment.description, "Flush the buffer");
+        assert_eq!(comment.returns.as_deref(), Some("The bytes written"));
+        assert!(comment.params.is_empty());
+        assert_eq!(
+            comment.notes,
+            [
+                (NoteKind::Internal, "Called by the destructor,\n  @param ignored inside".into()),
+                (NoteKind::Internal, "Unterminated".into()),
+            ]
+        );
+        assert!(!comment.internal);
+
+        let comment = parse_comment("///\n/// @internal Helper for `flush`".to_string());
+        assert!(comment.internal);
+        assert!(comment.description.is_empty());
+    }
+
+    #[test]
+    fn aliases_are_expanded_with_their_argument() {
+        let aliases = BTreeMap::from([
+            ("threadsafe".to_string(), "@warning Not thread-safe.".to_string()),
+            ("since_v".to_string(), "@since \\1".to_string()),
+            ("stable".to_string(), "@since_v{2.0} \\threadsafe".to_string()),
+        ]);
+
+        assert_eq!(
+            expand_aliases("Sends it\n@stable", &aliases).unwrap(),
+            "Sends it\n@since 2.0 @warning Not thread-safe."
+        );
+        assert_eq!(
+            expand_aliases("@threadsafe{x}", &aliases).unwrap(),
+            "@warning Not thread-safe.{x}"
+        );
+        assert_eq!(expand_aliases("me@threadsafe", &aliases).unwrap(), "me@threadsafe");
+        assert_eq!(expand_aliases("@threadsafety", &aliases).unwrap(), "@threadsafety");
+
+        let aliases = BTreeMap::from([
+            ("ping".to_string(), "@pong".to_string()),
+            ("pong".to_string(), "@ping".to_string()),
+        ]);
+        assert!(expand_aliases("@ping", &aliases).unwrap_err().contains("recursive"));
+    }
+}
@@ -0,0 +1,391 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    pub version: String,
+
+    /// Copyright line shown in the page footer, `{year}` expands to the build year
+    pub copyright: Option<String>,
+    pub license: Option<License>,
+
+    /// File rendered as `license.html` and linked from the footer
+    pub license_file: Option<String>,
+
+    /// How `@since` versions compare: "semver" (default) or "lexical"
+    pub version_scheme: Option<String>,
+
+    /// Version being released, whose `@since` badges are styled as new when
+    /// `output.highlight_since` isn't set. Also written to the JSON dump.
+    pub current_version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct License {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Input {
+    /// Single glob of the files to document, same as a one-element `globs`
+    pub glob: Option<String>,
+
+    /// Globs of the files to document
+    pub globs: Option<Vec<String>>,
+
+    /// Globs of the matched files to leave out, e.g. `**/third_party/**`
+    pub exclude: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub compiler_arguments: Vec<String>,
+
+    /// Groups of files parsed with their own arguments (`[[input.sets]]`), in addition to the
+    /// files of `glob`/`globs`
+    pub sets: Option<Vec<InputSet>>,
+
+    /// Directory that displayed source paths are made relative to
+    pub source_root: Option<String>,
+
+    /// Namespaces holding implementation details, matched by name or qualified name
+    /// (default: `["detail", "impl"]`)
+    pub exclude_namespaces: Option<Vec<String>>,
+
+    /// Document the content of anonymous namespaces, skipped by default
+    pub include_anonymous_namespaces: Option<bool>,
+
+    /// Language of the sources, "c++" (default) or "c"
+    pub language: Option<String>,
+
+    /// Compilation database (`compile_commands.json`) giving the arguments of each file,
+    /// `compiler_arguments` are used for the files it doesn't list
+    pub compile_commands: Option<String>,
+
+    /// Fail the build when clang reports errors while parsing, e.g. a missing include
+    pub fail_on_error: Option<bool>,
+
+    /// Directories whose headers are documented when a parsed file includes them, so that an
+    /// umbrella header can be the only input
+    pub include_paths_to_document: Option<Vec<String>>,
+
+    /// Language standard, e.g. "c++17" or "c99", passed as `-std`
+    pub std: Option<String>,
+
+    /// Target triple for cross-compiled code, e.g. "armv7em-none-eabi"
+    pub target: Option<String>,
+
+    /// Root of the target's headers and libraries, e.g. the one of a cross toolchain
+    pub sysroot: Option<String>,
+
+    /// System include directories, whose headers get no warnings
+    pub isystem: Option<Vec<String>>,
+
+    /// Configurations every file is parsed in, e.g. with and without an optional feature. What
+    /// only some of them declare is documented as only available in these.
+    pub configurations: Option<Vec<Configuration>>,
+}
+
+/// `[[input.configurations]]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Configuration {
+    pub name: String,
+
+    /// Macros defined in this configuration, `NAME` or `NAME=value`
+    #[serde(default)]
+    pub defines: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputSet {
+    pub glob: Option<String>,
+    pub globs: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub compiler_arguments: Vec<String>,
+
+    /// Language of the set, the one of `input` by default
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pages {
+    pub index: Option<String>,
+    pub extra: Option<Vec<String>>,
+
+    /// Glob matching `README.md`/`index.md` files that document their source directory
+    pub dir_docs: Option<String>,
+
+    /// Extra pages larger than this many bytes are skipped
+    pub max_size: Option<u64>,
+
+    /// Extensions of the extra pages to render, `md` and `markdown` by default
+    pub extensions: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Output {
+    pub static_dir: String,
+    pub path: String,
+    pub base_url: String,
+    pub root_namespace: Option<String>,
+
+    /// Rhai script whose functions are exposed to templates as filters and functions
+    pub template_script: Option<String>,
+
+    /// Show the value of every enum constant, not only the explicitly assigned ones
+    pub show_all_enum_values: Option<bool>,
+
+    /// Also document protected members of records, for classes meant to be derived from
+    pub include_protected: Option<bool>,
+
+    /// Document the content of inline namespaces as part of their parent (default: true)
+    pub collapse_inline_namespaces: Option<bool>,
+
+    /// Initializers of fields longer than this many characters are cut with an ellipsis
+    /// (default: 40)
+    pub max_initializer_length: Option<usize>,
+    /// Leave out what module interface units don't export
+    pub exported_only: Option<bool>,
+
+    /// Regular expressions of the qualified names left out, e.g. `^mylib::internal::`
+    pub exclude_symbols: Option<Vec<String>>,
+
+    /// Leave out the functions, records, enums and aliases without a documentation comment
+    pub require_comment: Option<bool>,
+
+    /// Write a highlighted page of every parsed file, linked from the "Defined in" lines
+    pub emit_source: Option<bool>,
+
+    /// Files larger than this many bytes get no source page (default: 1 MiB)
+    pub max_source_size: Option<u64>,
+
+    /// Leading part of the source locations left out of the `#include` lines, e.g. `include/`
+    pub include_prefix_strip: Option<String>,
+
+    /// Order of the members of namespaces: "alphabetical" (default) or "source"
+    pub member_order: Option<String>,
+
+    /// Still give a page to the namespaces with nothing documented (default: false)
+    pub keep_empty_namespaces: Option<bool>,
+
+    /// Warn about the `@see` entries naming no documented symbol, shown as plain code
+    pub strict_see_also: Option<bool>,
+
+    /// `@since` badges of versions newer than this one are styled as new
+    pub highlight_since: Option<String>,
+
+    /// Link the documented qualified names and calls written in comments and pages, like
+    /// `mylib::Socket` or `Socket::connect()`, to their page
+    pub autolink: Option<bool>,
+
+    /// Show the `@internal` sections of the comments, in an "Internal" callout (default: false)
+    pub show_internal: Option<bool>,
+
+    /// What becomes of the raw HTML of the comments: "escape" (default) shows it as text, "allow"
+    /// keeps it and "sanitize" keeps the tags of `render::SANITIZED_TAGS` only. Extra pages keep
+    /// their HTML.
+    pub comment_html: Option<String>,
+
+    /// Directories the images of the comments (`@image html file`, `![alt](file)`) are looked up
+    /// in, to be copied to the `assets/` directory of the output
+    pub image_dirs: Option<Vec<String>>,
+
+    /// How the TeX of the markdown (`$…$`, `$$…$$` and ```` ```math ```` blocks) is rendered:
+    /// "katex" is the only renderer, off by default
+    pub math: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Doctests {
+    pub enable: bool,
+    pub run: Option<bool>,
+    pub compiler_invocation: Option<Vec<String>>,
+
+    /// Directories the files of `@example file` are looked up in
+    pub example_dirs: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Comments {
+    /// Commands expanded to markdown before the comments are parsed, like Doxygen's `ALIASES`.
+    /// `\1` in the markdown stands for the argument in braces, `@command{argument}`.
+    pub aliases: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Config {
+    pub project: Project,
+    pub input: Input,
+    pub pages: Pages,
+    pub output: Output,
+    pub doctests: Option<Doctests>,
+    pub comments: Option<Comments>,
+}
+
+impl Input {
+    /// `glob` followed by `globs`
+    pub fn globs(&self) -> Vec<&str> {
+        self.glob
+            .iter()
+            .chain(self.globs.iter().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl Config {
+    /// One configuration per group of input files: the top-level input when it has globs, then
+    /// each of `input.sets` with its globs, arguments and language in place of the top-level ones
+    pub fn input_sets(&self) -> Vec<Config> {
+        let mut sets = Vec::new();
+
+        if !self.input.globs().is_empty() {
+            sets.push(self.clone());
+        }
+
+        for set in self.input.sets.iter().flatten() {
+            let mut config = self.clone();
+
+            config.input.glob = set.glob.clone();
+            config.input.globs = set.globs.clone();
+            config.input.compiler_arguments = set.compiler_arguments.clone();
+            config.input.language = set.language.clone().or(config.input.language);
+            config.input.sets = None;
+
+            sets.push(config);
+        }
+
+        sets
+    }
+
+    /// The `[comments.aliases]` table, empty when there is none
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        static EMPTY: BTreeMap<String, String> = BTreeMap::new();
+
+        self.comments.as_ref().and_then(|c| c.aliases.as_ref()).unwrap_or(&EMPTY)
+    }
+
+    pub fn new(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let source = std::fs::read_to_string(path)?;
+        let mut config: Config = toml::from_str(&source)?;
+
+        if config.input_sets().is_empty() {
+            return Err("one of input.glob, input.globs or input.sets must be set".into());
+        }
+
+        let configurations = config.input.configurations.iter().flatten().collect::<Vec<_>>();
+
+        for (i, configuration) in configurations.iter().enumerate() {
+            if configurations[..i].iter().any(|c| c.name == configuration.name) {
+                return Err(format!(
+                    "input.configurations has several configurations named “{}”",
+                    configuration.name
+                )
+                .into());
+            }
+        }
+
+        if let Some(ref mode) = config.output.comment_html {
+            if !["escape", "allow", "sanitize"].contains(&mode.as_str()) {
+                return Err(format!(
+                    "output.comment_html is “{mode}”, it must be “escape”, “allow” or “sanitize”"
+                )
+                .into());
+            }
+        }
+
+        if let Some(ref math) = config.output.math {
+            if math != "katex" {
+                return Err(format!("output.math is “{math}”, the only renderer is “katex”").into());
+            }
+        }
+
+        if let Some(ref script) = config.output.template_script {
+            if !cfg!(feature = "scripting") {
+                return Err(format!(
+                    "output.template_script is “{script}”, but cppdoc was built without the \
+                     “scripting” feature"
+                )
+                .into());
+            }
+        }
+
+        for name in config.aliases().keys() {
+            crate::comment::expand_aliases(&format!("@{name}"), config.aliases())?;
+        }
+
+        if let Some(ref mut copyright) = config.project.copyright {
+            *copyright = copyright.replace("{year}", &build_year().to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+/// A minimal configuration for the unit tests, `output` being added to its `[output]` table
+#[cfg(test)]
+pub fn test_config(output: &str) -> Config {
+    toml::from_str(&format!(
+        r#"
+        [project]
+        name = "Test"
+        version = "1.0.0"
+
+        [input]
+        glob = "*.hpp"
+        compiler_arguments = []
+
+        [pages]
+
+        [output]
+        static_dir = "static"
+        path = "docs"
+        base_url = ""
+        {output}
+        "#
+    ))
+    .unwrap()
+}
+
+/// Year of the build, taken from `SOURCE_DATE_EPOCH` when set for reproducible builds
+fn build_year() -> i64 {
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<i64>().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or_default()
+        });
+
+    year(timestamp)
+}
+
+/// Year of the UNIX timestamp `timestamp`, in UTC
+fn year(timestamp: i64) -> i64 {
+    // Civil-from-days conversion, see http://howardhinnant.github.io/date_algorithms.html
+    let days = timestamp.div_euclid(86400) + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+
+    year_of_era + era * 400 + if month >= 10 { 1 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn years_change_at_midnight_utc() {
+        assert_eq!(year(0), 1970);
+        assert_eq!(year(-1), 1969);
+        assert_eq!(year(951_868_800), 2000); // 2000-03-01
+        assert_eq!(year(1_704_067_199), 2023);
+        assert_eq!(year(1_704_067_200), 2024);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::comment;
+use crate::parser::{qualified_name, Comment, Function, Namespace, NestedField, Output, Record};
+use crate::report::report_warning;
+
+use std::collections::{HashMap, HashSet};
+
+/// Call `f` with the qualified name and the comment of a function and of its overloads
+fn functions(functions: &mut [Function], scope: &str, f: &mut dyn FnMut(&str, &mut Comment)) {
+    for function in functions {
+        let name = qualified_name(scope, &function.name);
+
+        if let Some(ref mut comment) = function.comment {
+            f(&name, comment);
+        }
+
+        for overload in function.overloads.iter_mut().flatten() {
+            if let Some(ref mut comment) = overload.comment {
+                f(&name, comment);
+            }
+        }
+    }
+}
+
+fn record(record: &mut Record, scope: &str, f: &mut dyn FnMut(&str, &mut Comment)) {
+    let name = qualified_name(scope, &record.name);
+
+    if let Some(ref mut comment) = record.comment {
+        f(&name, comment);
+    }
+
+    functions(&mut record.ctor, &name, f);
+    functions(&mut record.methods, &name, f);
+    functions(&mut record.conversions, &name, f);
+    functions(record.dtor.as_mut_slice(), &name, f);
+
+    for field in &mut record.fields {
+        if let Some(ref mut comment) = field.comment {
+            f(&qualified_name(name.as_str(), &field.name), comment);
+        }
+    }
+
+    for variable in &mut record.static_fields {
+        if let Some(ref mut comment) = variable.comment {
+            f(&qualified_name(name.as_str(), &variable.name), comment);
+        }
+    }
+
+    for nested in record.nested.iter_mut().flatten() {
+        match nested {
+            NestedField::Record(nested) => self::record(nested, &name, f),
+            NestedField::Enum(enm) => {
+                if let Some(ref mut comment) = enm.comment {
+                    f(&qualified_name(name.as_str(), &enm.name), comment);
+                }
+            }
+        }
+    }
+}
+
+/// Call `f` with the qualified name and the comment of every documented entity of `ns`, whose
+/// qualified name is `scope`, members included
+pub fn for_each_comment(ns: &mut Namespace, scope: &str, f: &mut dyn FnMut(&str, &mut Comment)) {
+    functions(&mut ns.functions, scope, f);
+
+    for r in &mut ns.records {
+        record(r, scope, f);
+    }
+
+    let comments = ns
+        .enums
+        .iter_mut()
+        .map(|e| (&e.name, &mut e.comment))
+        .chain(ns.aliases.iter_mut().map(|a| (&a.name, &mut a.comment)))
+        .chain(ns.variables.iter_mut().map(|v| (&v.name, &mut v.comment)))
+        .chain(ns.concepts.iter_mut().map(|c| (&c.name, &mut c.comment)));
+
+    for (name, comment) in comments {
+        if let Some(comment) = comment {
+            f(&qualified_name(scope, name), comment);
+        }
+    }
+
+    for child in &mut ns.namespaces {
+        let name = qualified_name(scope, &child.name);
+
+        if let Some(ref mut comment) = child.comment {
+            f(&name, comment);
+        }
+
+        for_each_comment(child, &name, f);
+    }
+}
+
+/// The entity `target` names in `scope`, looking in the enclosing scopes like the compiler.
+/// A signature (`open(int)`) is ignored, overloads share their first declaration's comment.
+fn find<'a>(
+    target: &str,
+    scope: &str,
+    comments: &'a HashMap<String, Comment>,
+) -> Option<(String, &'a Comment)> {
+    let target = target.split('(').next().unwrap_or_default().trim();
+
+    let mut parts = match target.strip_prefix("::") {
+        Some(_) => Vec::new(),
+        None => scope.split("::").filter(|p| !p.is_empty()).collect::<Vec<_>>(),
+    };
+    let target = target.trim_start_matches("::");
+
+    loop {
+        let candidate = qualified_name(parts.join("::").as_str(), target);
+
+        if let Some(comment) = comments.get(&candidate) {
+            return Some((candidate, comment));
+        }
+
+        parts.pop()?;
+    }
+}
+
+/// Copy the documentation `target` into `comment`: its brief when `comment` has none, its
+/// description before the one of `comment`, and the parameters, return values, exceptions
+/// and template parameters that `comment` doesn't document itself
+fn copy(comment: &mut Comment, target: &Comment) {
+    let own_description = std::mem::take(&mut comment.description);
+
+    // The description starts with the one of `target`, and so does the first sentence
+    if comment.auto_brief {
+        comment.brief.clear();
+    }
+
+    comment::merge_comments(
+        comment,
+        Comment {
+            brief: target.brief.clone(),
+            auto_brief: target.auto_brief,
+            description: target.description.clone(),
+            params: target.params.clone(),
+            returns: target.returns.clone(),
+            retvals: target.retvals.clone(),
+            throws: target.throws.clone(),
+            tparams: target.tparams.clone(),
+            ..Default::default()
+        },
+    );
+
+    if comment.description.is_empty() {
+        comment.description = own_description;
+    } else if !own_description.is_empty() {
+        comment.description = format!("{}\n\n{}", comment.description, own_description);
+    }
+}
+
+/// Resolve the `@copydoc` of `comment`, the one of `name`, following the `@copydoc`s of its
+/// target in turn. `chain` holds the entities whose `@copydoc` is being resolved, a target
+/// among them closes a cycle, which is broken there.
+fn resolve(
+    name: &str,
+    comment: &mut Comment,
+    comments: &HashMap<String, Comment>,
+    chain: &mut Vec<String>,
+    warned: &mut HashSet<String>,
+) {
+    let Some(target) = comment.copydoc.take() else {
+        return;
+    };
+
+    let scope = name.rsplit_once("::").map_or("", |(scope, _)| scope);
+
+    let Some((target_name, target_comment)) = find(&target, scope, comments) else {
+        report_warning(&format!(
+            "“{name}” copies the documentation of “{target}” with @copydoc, which isn't found"
+        ));
+        return;
+    };
+
+    if target_name == name || chain.contains(&target_name) {
+        let message = format!(
+            "“{name}” copies the documentation of “{target_name}”, which copies it back \
+             with @copydoc, ignored"
+        );
+
+        if warned.insert(message.clone()) {
+            report_warning(&message);
+        }
+
+        return;
+    }
+
+    let mut target_comment = target_comment.clone();
+
+    chain.push(name.to_string());
+    resolve(&target_name, &mut target_comment, comments, chain, warned);
+    chain.pop();
+
+    copy(comment, &target_comment);
+}
+
+/// Replace the `@copydoc`s of the output by the documentation they name, looked up among the
+/// documented entities and the implementation details
+pub fn resolve_copydocs(output: &mut Output) {
+    let mut comments = output
+        .hidden_comments
+        .iter()
+        .map(|(name, comment)| (name.clone(), comment.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut any = false;
+
+    for_each_comment(&mut output.root, "", &mut |name, comment| {
+        any |= comment.copydoc.is_some();
+        comments
+            .entry(name.to_string())
+            .or_insert_with(|| comment.clone());
+    });
+
+    if !any {
+        return;
+    }
+
+    let mut warned = HashSet::new();
+
+    for_each_comment(&mut output.root, "", &mut |name, comment| {
+        resolve(name, comment, &comments, &mut Vec::new(), &mut warned);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_comment(raw: String) -> Comment {
+        comment::parse_comment(raw, &Default::default())
+    }
+
+    #[test]
+    fn copydoc_follows_chains_and_breaks_cycles() {
+        let mut comments = HashMap::new();
+        comments.insert(
+            "mylib::detail::open_impl".to_string(),
+            parse_comment("/// Open `path`\n/// @param path Where\n/// @returns The file".into()),
+        );
+        comments.insert(
+            "mylib::open".to_string(),
+            parse_comment("/// @copydoc detail::open_impl(const char *)".into()),
+        );
+        comments.insert("a".to_string(), parse_comment("/// @copydoc b".into()));
+        comments.insert("b".to_string(), parse_comment("/// B\n/// @copydoc a".into()));
+
+        let mut warned = HashSet::new();
+
+        let mut open = parse_comment("/// @copydoc open\n///\n/// Also follows links".into());
+        resolve("mylib::open_link", &mut open, &comments, &mut Vec::new(), &mut warned);
+
+        assert_eq!(open.description, "Open `path`\n\nAlso follows links");
+        assert_eq!(open.params, [("path".to_string(), "Where".to_string())]);
+        assert_eq!(open.returns.as_deref(), Some("The file"));
+        assert!(open.copydoc.is_none());
+
+        let mut a = comments["a"].clone();
+        resolve("a", &mut a, &comments, &mut Vec::new(), &mut warned);
+
+        assert_eq!(a.description, "B");
+        assert_eq!(warned.len(), 1);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::comment;
+use crate::config::Config;
+use crate::doctest;
+use crate::index::Index;
+use crate::render;
+use crate::report::report_warning;
+
+use glob::glob;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A documented source directory
+#[derive(Debug, Default)]
+pub struct Directory {
+    /// Path relative to `input.source_root`
+    pub path: String,
+    pub description: String,
+    pub headers: Vec<String>,
+    pub entities: Vec<String>,
+}
+
+fn relative_path(path: &Path, config: &Config) -> String {
+    let path = match config.input.source_root {
+        Some(ref root) => path.strip_prefix(root).unwrap_or(path),
+        None => path,
+    };
+
+    path.to_string_lossy().trim_end_matches('/').to_string()
+}
+
+/// Title of the page of the directory `path`, `src/net/`
+fn title(path: &str) -> String {
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        format!("{}/", path)
+    }
+}
+
+pub fn slug(path: &str) -> String {
+    if path.is_empty() {
+        "root".to_string()
+    } else {
+        path.replace(['/', '\\'], "-")
+    }
+}
+
+/// Find `@dir` blocks in the comments of `source`, returning (directory argument, text) pairs.
+/// The argument is empty when the command doesn't name a directory.
+fn find_dir_comments(source: &str) -> Vec<(String, String)> {
+    let mut ret = Vec::new();
+    let mut block = Vec::new();
+    let mut in_block_comment = false;
+
+    let mut flush = |block: &mut Vec<String>| {
+        if let Some((pos, arg)) = block
+            .iter()
+            .enumerate()
+            .find_map(|(pos, l)| Some((pos, comment::command(l, "dir")?.to_string())))
+        {
+            let text = block[pos + 1..].join("\n").trim().to_string();
+
+            ret.push((arg, text));
+        }
+
+        block.clear();
+    };
+
+    for line in source.lines() {
+        let line = line.trim();
+
+        if in_block_comment {
+            let (content, end) = match line.find("*/") {
+                Some(pos) => (&line[..pos], true),
+                None => (line, false),
+            };
+
+            block.push(content.trim_start_matches('*').trim().to_string());
+
+            if end {
+                in_block_comment = false;
+                flush(&mut block);
+            }
+        } else if let Some(rest) = line
+            .strip_prefix("///")
+            .or_else(|| line.strip_prefix("//!"))
+        {
+            block.push(rest.trim().to_string());
+        } else if let Some(rest) = line
+            .strip_prefix("/**")
+            .or_else(|| line.strip_prefix("/*!"))
+        {
+            match rest.find("*/") {
+                Some(pos) => {
+                    block.push(rest[..pos].trim().to_string());
+                    flush(&mut block);
+                }
+                None => {
+                    block.push(rest.trim().to_string());
+                    in_block_comment = true;
+                }
+            }
+        } else {
+            flush(&mut block);
+        }
+    }
+
+    flush(&mut block);
+
+    ret
+}
+
+/// Collect the documented directories from `@dir` comments in the parsed files and from the
+/// sidecar pages matched by `pages.dir_docs`.
+pub fn collect(files: &BTreeMap<String, Vec<String>>, config: &Config) -> Vec<Directory> {
+    let mut descriptions = BTreeMap::new();
+
+    for file in files.keys() {
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(_) => continue,
+        };
+
+        let parent = Path::new(file).parent().unwrap_or(Path::new(""));
+
+        for (arg, text) in find_dir_comments(&source) {
+            let dir = if arg.is_empty() {
+                relative_path(parent, config)
+            } else {
+                relative_path(Path::new(&arg), config)
+            };
+
+            descriptions.insert(dir, text);
+        }
+    }
+
+    if let Some(ref pattern) = config.pages.dir_docs {
+        for file in glob(pattern).expect("Failed to read glob pattern") {
+            match file {
+                Ok(path) => match std::fs::read_to_string(&path) {
+                    Ok(source) => {
+                        let parent = path.parent().unwrap_or(Path::new(""));
+                        descriptions.insert(relative_path(parent, config), source);
+                    }
+                    Err(e) => {
+                        report_warning(&format!("Error reading directory page “{path:?}”: {e}"));
+                    }
+                },
+                Err(e) => {
+                    report_warning(&format!("Error reading directory page “{pattern}”: {e}"));
+                }
+            }
+        }
+    }
+
+    descriptions
+        .into_iter()
+        .map(|(path, description)| {
+            let mut dir = Directory {
+                path,
+                description,
+                ..Default::default()
+            };
+
+            for (file, entities) in files {
+                let file_dir = relative_path(Path::new(file).parent().unwrap_or(Path::new("")), config);
+
+                let is_inside = dir.path.is_empty()
+                    || file_dir == dir.path
+                    || file_dir.starts_with(&format!("{}/", dir.path));
+
+                if is_inside {
+                    dir.headers.push(relative_path(Path::new(file), config));
+                    dir.entities.extend(entities.iter().cloned());
+                }
+            }
+
+            dir.entities.sort();
+            dir
+        })
+        .collect()
+}
+
+pub fn output_dir_page(
+    dir: &Directory,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) -> render::Page {
+    let mut page = render::process_markdown(&dir.description, index, doctests, config);
+
+    let mut content = page.content;
+
+    content.push_str("<h2>Headers</h2>\n<ul>\n");
+    for header in &dir.headers {
+        content.push_str(&format!("<li><code>{}</code></li>\n", render::escape_html(header)));
+    }
+    content.push_str("</ul>\n");
+
+    if !dir.entities.is_empty() {
+        content.push_str("<h2>Entities</h2>\n<ul>\n");
+        for entity in &dir.entities {
+            let name = render::escape_html(entity);
+
+            match render::get_path_for_name(entity, index) {
+                Some(path) => content.push_str(&format!(
+                    "<li><a href=\"{}/{}.html\"><code>{}</code></a></li>\n",
+                    config.output.base_url, path, name
+                )),
+                None => content.push_str(&format!("<li><code>{}</code></li>\n", name)),
+            }
+        }
+        content.push_str("</ul>\n");
+    }
+
+    page.content = content;
+
+    if page.title.is_empty() {
+        page.title = title(&dir.path);
+    }
+
+    page.path = PathBuf::from(format!("dir.{}", slug(&dir.path)));
+    page
+}
+
+/// The innermost documented directory of every entity, by qualified name: the title of its page
+/// and the link to it
+pub fn dir_pages(dirs: &[Directory], config: &Config) -> HashMap<String, (String, String)> {
+    let mut pages: HashMap<String, (&str, String)> = HashMap::new();
+
+    for dir in dirs {
+        for entity in &dir.entities {
+            let replace = match pages.get(entity) {
+                Some((path, _)) => dir.path.len() > path.len(),
+                None => true,
+            };
+
+            if replace {
+                pages.insert(
+                    entity.clone(),
+                    (
+                        &dir.path,
+                        format!("{}/dir.{}.html", config.output.base_url, slug(&dir.path)),
+                    ),
+                );
+            }
+        }
+    }
+
+    pages
+        .into_iter()
+        .map(|(entity, (path, link))| (entity, (title(path), link)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+
+    fn config() -> Config {
+        let mut config = test_config("");
+        config.output.base_url = "/api".to_string();
+        config
+    }
+
+    fn directory(path: &str, entities: &[&str]) -> Directory {
+        Directory {
+            path: path.to_string(),
+            entities: entities.iter().map(|e| e.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dir_commands_are_whole_words() {
+        let comments = find_dir_comments(
+            "/// @dir src/net\n\
+             /// Sockets\n\
+             int port;\n\
+             /// @directory of the users\n\
+             void lookup();\n\
+             /** \\dir\n * Everything */\n",
+        );
+
+        assert_eq!(
+            comments,
+            [
+                ("src/net".to_string(), "Sockets".to_string()),
+                (String::new(), "Everything".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn dir_pages_escape_headers_and_entities() {
+        let mut dir = directory("src", &["net::operator<<"]);
+        dir.headers.push("src/a&b.hpp".to_string());
+
+        let page = output_dir_page(&dir, &Index::default(), &mut Vec::new(), &config());
+
+        assert!(page.content.contains("<li><code>src/a&amp;b.hpp</code></li>"));
+        assert!(page.content.contains("<li><code>net::operator&lt;&lt;</code></li>"));
+        assert_eq!(page.title, "src/");
+    }
+
+    #[test]
+    fn entities_link_to_their_innermost_directory() {
+        let dirs = [
+            directory("", &["net::Socket", "main"]),
+            directory("src/net", &["net::Socket"]),
+        ];
+
+        let pages = dir_pages(&dirs, &config());
+
+        assert_eq!(
+            pages["net::Socket"],
+            ("src/net/".to_string(), "/api/dir.src-net.html".to_string())
+        );
+        assert_eq!(pages["main"], ("/".to_string(), "/api/dir.root.html".to_string()));
+    }
+}
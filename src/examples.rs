@@ -0,0 +1,55 @@
+use crate::config::Config;
+use crate::copydoc::for_each_comment;
+use crate::parser::{ExampleFile, Output};
+use crate::report::report_warning;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Read the files that `@example` commands show from `doctests.example_dirs`, warning about the
+/// ones that aren't found, and list them with the entities showing them in `output.examples`
+pub fn load_examples(output: &mut Output, config: &Config) {
+    let dirs = config
+        .doctests
+        .as_ref()
+        .and_then(|doctests| doctests.example_dirs.clone())
+        .unwrap_or_default();
+
+    let mut files = BTreeMap::<String, ExampleFile>::new();
+
+    for_each_comment(&mut output.root, "", &mut |name, comment| {
+        for example in &mut comment.examples {
+            if !files.contains_key(&example.file) {
+                let code = dirs.iter().find_map(|dir| {
+                    std::fs::read_to_string(Path::new(dir).join(&example.file)).ok()
+                });
+
+                let Some(code) = code else {
+                    report_warning(&format!(
+                        "“{name}” shows the example “{}”, which isn't in doctests.example_dirs",
+                        example.file
+                    ));
+                    continue;
+                };
+
+                files.insert(
+                    example.file.clone(),
+                    ExampleFile {
+                        file: example.file.clone(),
+                        code,
+                        used_by: Vec::new(),
+                    },
+                );
+            }
+
+            let file = files.get_mut(&example.file).unwrap();
+            example.code = Some(file.code.clone());
+
+            if !file.used_by.iter().any(|n| n == name) {
+                file.used_by.push(name.to_string());
+            }
+        }
+    });
+
+    output.examples = files.into_values().collect();
+}
@@ -0,0 +1,419 @@
+use crate::parser::{qualified_name, Comment, Function, Namespace, NestedField, Output, Record};
+
+use std::cmp::Ordering;
+
+/// Compare two versions. The "semver" scheme compares dot separated components numerically
+/// when both are numbers (so 1.10 > 1.9) and puts prereleases before their release
+/// (1.4.0-rc1 < 1.4.0), ignoring build metadata. "lexical" compares plain strings.
+pub fn compare_versions(a: &str, b: &str, scheme: &str) -> Ordering {
+    if scheme == "lexical" {
+        return a.cmp(b);
+    }
+
+    // `1.4.0-rc.1+build.5` is the release `1.4.0` and the prerelease `rc.1`
+    let split = |v: &str| {
+        let v = v.trim_start_matches(['v', 'V']);
+        let v = v.split_once('+').map_or(v, |(v, _)| v);
+
+        match v.split_once('-') {
+            Some((release, prerelease)) => (release.to_string(), Some(prerelease.to_string())),
+            None => (v.to_string(), None),
+        }
+    };
+
+    let compare_component = |x: &str, y: &str| match (x.parse::<u64>(), y.parse::<u64>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y),
+        // Numeric identifiers come before alphanumeric ones
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        _ => x.cmp(y),
+    };
+
+    let ((a, a_pre), (b, b_pre)) = (split(a), split(b));
+    let (a, b) = (a.split('.').collect::<Vec<_>>(), b.split('.').collect::<Vec<_>>());
+
+    for i in 0..a.len().max(b.len()) {
+        let ordering = compare_component(
+            a.get(i).copied().unwrap_or("0"),
+            b.get(i).copied().unwrap_or("0"),
+        );
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let (a, b) = (a.split('.').collect::<Vec<_>>(), b.split('.').collect::<Vec<_>>());
+
+            // A larger set of identifiers comes after when the common ones are equal
+            a.iter()
+                .zip(&b)
+                .map(|(x, y)| compare_component(x, y))
+                .find(|o| *o != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len()))
+        }
+    }
+}
+
+/// What a pass of `NamespaceFilter` keeps
+trait Keep {
+    /// Whether to keep an entity documented with `comment`
+    fn comment(&self, comment: &Option<Comment>) -> bool;
+
+    /// Whether to keep a record, before its members are filtered
+    fn record(&self, record: &Record) -> bool {
+        self.comment(&record.comment)
+    }
+
+    /// Whether to keep a nested namespace, before its members are filtered
+    fn namespace(&self, ns: &Namespace) -> bool {
+        self.comment(&ns.comment)
+    }
+}
+
+/// A pass over the namespace tree dropping the entities `keep` doesn't keep, with the qualified
+/// names of the dropped ones, to remove them from the index, and their number
+struct NamespaceFilter<K> {
+    keep: K,
+    removed: Vec<String>,
+    hidden: usize,
+}
+
+impl<K: Keep> NamespaceFilter<K> {
+    fn new(keep: K) -> Self {
+        NamespaceFilter {
+            keep,
+            removed: Vec::new(),
+            hidden: 0,
+        }
+    }
+
+    /// Filter a function and its overloads, returning `None` when none of them remain
+    fn function(&mut self, mut function: Function) -> Option<Function> {
+        let overloads = function.overloads.take().unwrap_or_default();
+        let count = overloads.len() + 1;
+
+        let remaining = std::iter::once(function)
+            .chain(overloads)
+            .filter(|f| self.keep.comment(&f.comment))
+            .collect::<Vec<_>>();
+
+        self.hidden += count - remaining.len();
+
+        let mut remaining = remaining.into_iter();
+
+        let mut primary = remaining.next()?;
+
+        let overloads = remaining.collect::<Vec<_>>();
+        primary.overloads = if overloads.is_empty() {
+            None
+        } else {
+            Some(overloads)
+        };
+
+        Some(primary)
+    }
+
+    fn functions(&mut self, functions: &mut Vec<Function>) {
+        *functions = std::mem::take(functions)
+            .into_iter()
+            .filter_map(|f| {
+                let name = qualified_name(f.namespace.as_deref(), &f.name);
+                let ret = self.function(f);
+
+                if ret.is_none() {
+                    self.removed.push(name);
+                }
+
+                ret
+            })
+            .collect();
+    }
+
+    /// Drop the `entities` that `kept` rejects, `name` giving their qualified names
+    fn retain<T>(
+        &mut self,
+        entities: &mut Vec<T>,
+        kept: impl Fn(&K, &T) -> bool,
+        name: impl Fn(&T) -> String,
+    ) {
+        let count = entities.len();
+        let (keep, removed) = (&self.keep, &mut self.removed);
+
+        entities.retain(|e| {
+            if kept(keep, e) {
+                true
+            } else {
+                removed.push(name(e));
+                false
+            }
+        });
+
+        self.hidden += count - entities.len();
+    }
+
+    fn record(&mut self, record: &mut Record) {
+        let scope = qualified_name(record.namespace.as_deref(), &record.name);
+        let member = |name: &str| qualified_name(scope.as_str(), name);
+
+        self.retain(&mut record.fields, |k, f| k.comment(&f.comment), |f| member(&f.name));
+        self.retain(&mut record.static_fields, |k, v| k.comment(&v.comment), |v| member(&v.name));
+        self.functions(&mut record.methods);
+        self.functions(&mut record.ctor);
+        self.functions(&mut record.conversions);
+        self.functions(&mut record.friends);
+
+        if record.dtor.as_ref().is_some_and(|d| !self.keep.comment(&d.comment)) {
+            record.dtor = None;
+            self.hidden += 1;
+        }
+
+        if let Some(ref mut nested) = record.nested {
+            self.retain(
+                nested,
+                |k, n| match n {
+                    NestedField::Record(r) => k.record(r),
+                    NestedField::Enum(e) => k.comment(&e.comment),
+                },
+                |n| match n {
+                    NestedField::Record(r) => member(&r.name),
+                    NestedField::Enum(e) => member(&e.name),
+                },
+            );
+
+            for nested in nested.iter_mut() {
+                if let NestedField::Record(r) = nested {
+                    self.record(r);
+                }
+            }
+        }
+    }
+
+    fn namespace(&mut self, ns: &mut Namespace) {
+        let name = |scope: &Option<String>, name: &str| qualified_name(scope.as_deref(), name);
+
+        self.functions(&mut ns.functions);
+        self.retain(&mut ns.records, |k, r| k.record(r), |r| name(&r.namespace, &r.name));
+
+        for record in &mut ns.records {
+            self.record(record);
+        }
+
+        self.retain(&mut ns.enums, |k, e| k.comment(&e.comment), |e| name(&e.namespace, &e.name));
+        self.retain(
+            &mut ns.aliases,
+            |k, a| k.comment(&a.comment),
+            |a| name(&a.namespace, &a.name),
+        );
+        self.retain(
+            &mut ns.concepts,
+            |k, c| k.comment(&c.comment),
+            |c| name(&c.namespace, &c.name),
+        );
+        self.retain(
+            &mut ns.variables,
+            |k, v| k.comment(&v.comment),
+            |v| name(&v.namespace, &v.name),
+        );
+        self.retain(
+            &mut ns.namespace_aliases,
+            |k, a| k.comment(&a.comment),
+            |a| name(&a.namespace, &a.name),
+        );
+        self.retain(
+            &mut ns.namespaces,
+            |k, n| k.namespace(n),
+            |n| name(&n.namespace, &n.name),
+        );
+
+        for child in &mut ns.namespaces {
+            self.namespace(child);
+        }
+    }
+
+    /// Filter the namespace tree of `output`, and drop what was removed from the index along
+    /// with the nested types of the removed records
+    fn run(mut self, output: &mut Output) -> usize {
+        self.namespace(&mut output.root);
+
+        for name in &self.removed {
+            let prefix = format!("{}::", name);
+            output
+                .index
+                .retain(|key, _| key != name && !key.starts_with(&prefix));
+        }
+
+        self.hidden
+    }
+}
+
+/// The entities without an `@since` newer than `version`
+struct Since<'a> {
+    version: &'a str,
+    scheme: &'a str,
+}
+
+impl Keep for Since<'_> {
+    fn comment(&self, comment: &Option<Comment>) -> bool {
+        match comment.as_ref().and_then(|c| c.since.as_deref()) {
+            Some(since) => compare_versions(since, self.version, self.scheme) != Ordering::Greater,
+            None => true,
+        }
+    }
+}
+
+/// Drop every entity documented with an `@since` newer than `version` from the namespace tree
+/// and the index, so that the docs of an older release can be built from the current sources.
+/// Entities without `@since` are always kept.
+pub fn filter_since(output: &mut Output, version: &str, scheme: &str) {
+    NamespaceFilter::new(Since { version, scheme }).run(output);
+}
+
+/// Whether an entity has a comment for `output.require_comment`, one starting with `@internal`
+/// not counting
+fn is_documented(comment: &Option<Comment>) -> bool {
+    comment.as_ref().is_some_and(|c| !c.internal)
+}
+
+/// A record without a comment is still worth a page when some of its members are documented
+fn has_documented_members(record: &Record) -> bool {
+    record.fields.iter().any(|f| is_documented(&f.comment))
+        || record.static_fields.iter().any(|v| is_documented(&v.comment))
+        || record
+            .methods
+            .iter()
+            .chain(&record.ctor)
+            .chain(&record.conversions)
+            .flat_map(|f| std::iter::once(f).chain(f.overloads.iter().flatten()))
+            .any(|f| is_documented(&f.comment))
+        || record.nested.iter().flatten().any(|n| match n {
+            NestedField::Record(r) => Documented.record(r),
+            NestedField::Enum(e) => is_documented(&e.comment),
+        })
+}
+
+/// The documented entities, and the records with documented members
+struct Documented;
+
+impl Keep for Documented {
+    fn comment(&self, comment: &Option<Comment>) -> bool {
+        is_documented(comment)
+    }
+
+    fn record(&self, record: &Record) -> bool {
+        is_documented(&record.comment) || has_documented_members(record)
+    }
+
+    /// Namespaces are rarely documented, the empty ones are pruned later on
+    fn namespace(&self, _: &Namespace) -> bool {
+        true
+    }
+}
+
+/// Drop the entities without a comment (or with an `@internal` one) from the namespace tree and
+/// the index (`output.require_comment`). Records are kept when some of their members are
+/// documented. Returns the number of hidden entities.
+pub fn filter_undocumented(output: &mut Output) -> usize {
+    NamespaceFilter::new(Documented).run(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Variable;
+    use serde_json::json;
+
+    #[test]
+    fn versions_follow_semver_ordering() {
+        let semver = |a, b| compare_versions(a, b, "semver");
+
+        assert_eq!(semver("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(semver("v2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(semver("1.4.0-rc1", "1.4.0"), Ordering::Less);
+        assert_eq!(semver("1.4.0", "1.4.0-rc1"), Ordering::Greater);
+        assert_eq!(semver("1.4.0-rc1", "1.3.9"), Ordering::Greater);
+        assert_eq!(semver("1.0.0-alpha", "1.0.0-alpha.1"), Ordering::Less);
+        assert_eq!(semver("1.0.0-alpha.2", "1.0.0-alpha.10"), Ordering::Less);
+        assert_eq!(semver("1.0.0-alpha.1", "1.0.0-alpha.beta"), Ordering::Less);
+        assert_eq!(semver("1.0.0-beta", "1.0.0-alpha"), Ordering::Greater);
+        assert_eq!(semver("1.0.0+build.5", "1.0.0"), Ordering::Equal);
+
+        assert_eq!(compare_versions("1.10", "1.9", "lexical"), Ordering::Less);
+    }
+
+    #[test]
+    fn every_member_list_and_nested_namespace_is_filtered() {
+        let mut output: Output =
+            serde_json::from_str(include_str!("../tests/fixtures/basic/expected/output.json"))
+                .unwrap();
+
+        let documented = output.root.records[0].fields[0].comment.clone().unwrap();
+        let since = |version: &str| {
+            let mut comment = documented.clone();
+            comment.since = Some(version.to_string());
+            Some(comment)
+        };
+        let variable = |scope: &str, name: &str, comment: Option<Comment>| -> Variable {
+            serde_json::from_value(json!({
+                "name": name, "type": "int", "constexpr": true, "static": false,
+                "inline": false, "comment": comment, "namespace": scope,
+            }))
+            .unwrap()
+        };
+
+        let root = &mut output.root;
+        root.variables.push(variable("", "kOld", since("1.0")));
+        root.variables.push(variable("", "kNew", since("3.0")));
+        root.variables.push(variable("", "kBare", None));
+        root.namespace_aliases.push(
+            serde_json::from_value(json!({
+                "name": "fs", "namespace": "", "target": "std::filesystem",
+                "comment": since("3.0"),
+            }))
+            .unwrap(),
+        );
+
+        for (name, comment) in [("future", since("3.0")), ("plain", None)] {
+            root.namespaces.push(Namespace {
+                name: name.to_string(),
+                comment,
+                namespace: Some(String::new()),
+                variables: vec![variable(name, "kInside", Some(documented.clone()))],
+                ..Default::default()
+            });
+        }
+
+        for name in ["kOld", "kNew", "kBare", "fs", "future", "future::kInside", "plain"] {
+            output.index.insert(name.to_string(), "variable", None);
+        }
+
+        filter_since(&mut output, "2.0", "semver");
+
+        let names = |output: &Output| {
+            let root = &output.root;
+            (
+                root.variables.iter().map(|v| v.name.clone()).collect::<Vec<_>>(),
+                root.namespace_aliases.len(),
+                root.namespaces.iter().map(|n| n.name.clone()).collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(names(&output), (vec!["kOld".into(), "kBare".into()], 0, vec!["plain".into()]));
+        assert!(output.index.contains_key("kOld"));
+
+        for name in ["kNew", "fs", "future", "future::kInside"] {
+            assert!(!output.index.contains_key(name), "{name} is still indexed");
+        }
+
+        // Undocumented namespaces are kept, they are pruned when nothing in them is documented
+        filter_undocumented(&mut output);
+
+        assert_eq!(names(&output), (vec!["kOld".into()], 0, vec!["plain".into()]));
+        assert!(!output.index.contains_key("kBare"));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::config::Config;
+use crate::copydoc::for_each_comment;
+use crate::manifest;
+use crate::parser::{Comment, Namespace};
+use crate::render;
+use crate::report::report_warning;
+
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The image files shown by the comments, by their path in the `assets/` directory of the output
+pub type Images = BTreeMap<String, PathBuf>;
+
+/// `@image format file ["caption"]` commands, on a line of their own
+fn image_command() -> &'static Regex {
+    static COMMAND: OnceLock<Regex> = OnceLock::new();
+
+    COMMAND.get_or_init(|| {
+        Regex::new(
+            r#"(?m)^[ \t]*[@\\]image[ \t]+(\w+)[ \t]+("[^"\n]*"|\S+)(?:[ \t]+"([^"\n]*)")?[ \t]*$"#,
+        )
+        .unwrap()
+    })
+}
+
+/// Markdown images, `![alt](file "title")`
+fn markdown_image() -> &'static Regex {
+    static IMAGE: OnceLock<Regex> = OnceLock::new();
+
+    IMAGE.get_or_init(|| Regex::new(r#"(!\[[^\]\n]*\]\()([^)\s]+)((?:\s+"[^"\n]*")?\))"#).unwrap())
+}
+
+/// Find `file` in `output.image_dirs`, adding it to `images` under its path in `assets/`, and
+/// return the URL of its copy. Files outside of the directories, like `../secret.png`, aren't
+/// looked up.
+fn resolve(file: &str, images: &mut Images, config: &Config) -> Option<String> {
+    let mut parts = Vec::new();
+
+    for component in Path::new(file).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    let relative = parts.join("/");
+
+    let source = config
+        .output
+        .image_dirs
+        .iter()
+        .flatten()
+        .map(|dir| Path::new(dir).join(&relative))
+        .find(|path| path.is_file())?;
+
+    let url = format!("{}/assets/{}", config.output.base_url, relative);
+    images.insert(relative, source);
+
+    Some(url)
+}
+
+/// Turn the `@image html` commands of `text` into figures and point its markdown images with a
+/// relative URL to their copy, adding the files to `images` and the ones not found to `missing`
+fn process_images(
+    text: &str,
+    images: &mut Images,
+    missing: &mut Vec<String>,
+    config: &Config,
+) -> String {
+    let mut url_of = |file: &str| {
+        let url = resolve(file, images, config);
+
+        if url.is_none() && !missing.iter().any(|m| m == file) {
+            missing.push(file.to_string());
+        }
+
+        url.unwrap_or_else(|| file.to_string())
+    };
+
+    render::map_prose(text, &mut |prose| {
+        let prose = image_command().replace_all(prose, |captures: &regex::Captures| {
+            // The images of the LaTeX, RTF... outputs
+            if &captures[1] != "html" {
+                return String::new();
+            }
+
+            let file = url_of(captures[2].trim_matches('"'));
+            let img = format!("<img src=\"{}\" alt=\"", render::escape_html(&file));
+
+            // On lines of their own, so that the text around them is still markdown
+            match captures.get(3) {
+                Some(caption) => {
+                    let caption = render::escape_html(caption.as_str());
+                    format!(
+                        "\n<figure class=\"image\">{img}{caption}\">\
+                         <figcaption>{caption}</figcaption></figure>\n"
+                    )
+                }
+                None => format!("\n<figure class=\"image\">{img}\"></figure>\n"),
+            }
+        });
+
+        markdown_image()
+            .replace_all(&prose, |captures: &regex::Captures| {
+                let url = &captures[2];
+
+                if url.contains("://") || url.starts_with(['/', '#']) || url.starts_with("data:") {
+                    return captures[0].to_string();
+                }
+
+                format!("{}{}{}", &captures[1], url_of(url), &captures[3])
+            })
+            .into_owned()
+    })
+}
+
+/// Process the images of the comments of `root`, whose qualified name is `scope`: `@image html`
+/// commands and markdown images, whose files are looked up in `output.image_dirs`. Returns the
+/// files to copy to the output with `copy_images`, after warning about the missing ones.
+pub fn resolve_images(root: &mut Namespace, scope: &str, config: &Config) -> Images {
+    let mut images = Images::new();
+
+    let mut process = |name: &str, comment: &mut Comment| {
+        let mut missing = Vec::new();
+
+        comment.brief = process_images(&comment.brief, &mut images, &mut missing, config);
+        comment.description =
+            process_images(&comment.description, &mut images, &mut missing, config);
+
+        for (_, text) in &mut comment.notes {
+            *text = process_images(text, &mut images, &mut missing, config);
+        }
+
+        for file in missing {
+            report_warning(&format!(
+                "“{name}” shows the image “{file}”, which isn't in output.image_dirs"
+            ));
+        }
+    };
+
+    if let Some(ref mut comment) = root.comment {
+        process(scope, comment);
+    }
+
+    for_each_comment(root, scope, &mut process);
+
+    images
+}
+
+/// Copy the images of the comments to the `assets/` directory of the output
+pub fn copy_images(images: &Images, config: &Config, recorder: &manifest::Recorder) {
+    for (relative, source) in images {
+        let dest = Path::new(&config.output.path).join("assets").join(relative);
+
+        let copied = std::fs::create_dir_all(dest.parent().unwrap())
+            .and_then(|_| manifest::copy(recorder, source, &dest, manifest::Category::Asset));
+
+        if let Err(e) = copied {
+            report_warning(&format!("Error copying the image “{}”: {e}", source.display()));
+        }
+    }
+}
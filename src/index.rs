@@ -0,0 +1,224 @@
+use crate::parser::SourceLocation;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A documented entity, one of those a qualified name refers to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub kind: String,
+
+    /// Qualified name of the enclosing namespace or record, empty at global scope
+    pub namespace: String,
+
+    pub location: Option<SourceLocation>,
+}
+
+/// Qualified names of the documented entities, with what they are. A name can refer to several
+/// entities of different kinds, like the `stat` struct and function of C: lookups then prefer
+/// namespaces, then types, then variables and functions.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(transparent)]
+pub struct Index {
+    entities: HashMap<String, Vec<IndexEntry>>,
+
+    /// Aliased namespace of every namespace alias, by qualified name of the alias
+    #[serde(skip)]
+    namespace_aliases: HashMap<String, String>,
+
+    /// Anchor of every method and constructor on its record page, by qualified name (the first
+    /// overload's for overloaded ones), for links naming members
+    #[serde(skip)]
+    method_anchors: HashMap<String, String>,
+}
+
+/// Lookup priority of a kind of entity, lowest first
+fn rank(kind: &str) -> u8 {
+    match kind {
+        "namespace" | "inline namespace" | "namespace alias" => 0,
+        "record" => 1,
+        "enum" => 2,
+        "alias" => 3,
+        "concept" => 4,
+        "variable" => 5,
+        "function" => 6,
+        _ => 7,
+    }
+}
+
+impl Index {
+    /// Add an entity. Another entity of the same name and kind is a redeclaration, the first
+    /// one is kept.
+    pub fn insert(&mut self, name: String, kind: &str, location: Option<SourceLocation>) {
+        let namespace = name
+            .rsplit_once("::")
+            .map(|(namespace, _)| namespace.to_string())
+            .unwrap_or_default();
+
+        self.insert_entry(
+            name,
+            IndexEntry {
+                kind: kind.to_string(),
+                namespace,
+                location,
+            },
+        );
+    }
+
+    pub fn insert_entry(&mut self, name: String, entry: IndexEntry) {
+        let entries = self.entities.entry(name).or_default();
+
+        if !entries.iter().any(|e| e.kind == entry.kind) {
+            entries.push(entry);
+        }
+    }
+
+    /// The entity `name` refers to, the preferred one when there are several
+    pub fn entry(&self, name: &str) -> Option<&IndexEntry> {
+        self.entities.get(name)?.iter().min_by_key(|e| rank(&e.kind))
+    }
+
+    /// Kind of the entity `name` refers to
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.entry(name).map(|e| &e.kind)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.entities.contains_key(name)
+    }
+
+    /// Every entity, with its qualified name
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &IndexEntry)> {
+        self.entities
+            .iter()
+            .flat_map(|(name, entries)| entries.iter().map(move |e| (name, e)))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.entities.keys()
+    }
+
+    pub fn extend(&mut self, other: Index) {
+        for (name, entries) in other.entities {
+            for entry in entries {
+                self.insert_entry(name.clone(), entry);
+            }
+        }
+    }
+
+    /// Keep the entities for which `keep` returns true
+    pub fn retain(&mut self, mut keep: impl FnMut(&String, &IndexEntry) -> bool) {
+        self.entities.retain(|name, entries| {
+            entries.retain(|e| keep(name, e));
+            !entries.is_empty()
+        });
+    }
+
+    /// Set the targets of the namespace aliases, they are found in the parsed namespaces rather
+    /// than saved with the entities
+    pub fn set_namespace_aliases(&mut self, aliases: HashMap<String, String>) {
+        self.namespace_aliases = aliases;
+    }
+
+    /// The namespace aliased by the namespace alias `name`
+    pub fn namespace_alias_target(&self, name: &str) -> Option<&str> {
+        self.namespace_aliases.get(name).map(String::as_str)
+    }
+
+    /// Set the anchors of the methods, they are found in the parsed records like the namespace
+    /// aliases
+    pub fn set_method_anchors(&mut self, anchors: HashMap<String, String>) {
+        self.method_anchors = anchors;
+    }
+
+    /// Anchor of the method `name` on the page of its record
+    pub fn method_anchor(&self, name: &str) -> Option<&str> {
+        self.method_anchors.get(name).map(String::as_str)
+    }
+
+    /// Resolve `name` as written in `scope` to the qualified name of an entity, looking in
+    /// `scope` first, then in the enclosing scopes up to the global one, like the compiler.
+    /// `::name` is only looked up at global scope.
+    pub fn resolve(&self, name: &str, scope: &str) -> Option<String> {
+        self.resolve_with(name, scope, |candidate| self.contains_key(candidate))
+    }
+
+    /// `resolve`, to the first qualified name `found` accepts, like the ones of records only
+    pub fn resolve_with(
+        &self,
+        name: &str,
+        scope: &str,
+        found: impl Fn(&str) -> bool,
+    ) -> Option<String> {
+        let (name, scope) = match name.strip_prefix("::") {
+            Some(name) => (name, ""),
+            None => (name, scope),
+        };
+
+        let mut parts = scope
+            .split("::")
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>();
+
+        loop {
+            let candidate = if parts.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}::{}", parts.join("::"), name)
+            };
+
+            if found(&candidate) {
+                return Some(candidate);
+            }
+
+            parts.pop()?;
+        }
+    }
+
+    /// The names referring to entities of several kinds, sorted, with these entities
+    pub fn collisions(&self) -> Vec<(&String, &[IndexEntry])> {
+        let mut collisions = self
+            .entities
+            .iter()
+            .filter(|(_, entries)| entries.len() > 1)
+            .map(|(name, entries)| (name, entries.as_slice()))
+            .collect::<Vec<_>>();
+
+        collisions.sort_by_key(|(name, _)| *name);
+        collisions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colliding_names_prefer_types_and_inner_scopes() {
+        let mut index = Index::default();
+        index.insert("stat".to_string(), "function", None);
+        index.insert("stat".to_string(), "record", None);
+        index.insert("stat".to_string(), "function", None);
+        index.insert("io".to_string(), "namespace", None);
+        index.insert("io::stat".to_string(), "function", None);
+
+        assert_eq!(index.get("stat").unwrap(), "record");
+        assert_eq!(index.entry("io::stat").unwrap().namespace, "io");
+        assert_eq!(index.collisions().len(), 1);
+        assert_eq!(index.collisions()[0].1.len(), 2);
+
+        assert_eq!(index.resolve("stat", "io").unwrap(), "io::stat");
+        assert_eq!(index.resolve("stat", "net").unwrap(), "stat");
+        assert_eq!(index.resolve("io", "io").unwrap(), "io");
+        assert!(index.resolve("fstat", "io").is_none());
+
+        // `::stat` skips the enclosing scopes
+        assert_eq!(index.resolve("::stat", "io").unwrap(), "stat");
+        assert_eq!(
+            index
+                .resolve_with("stat", "io", |name| index.get(name).is_some_and(|k| k == "record"))
+                .unwrap(),
+            "stat"
+        );
+    }
+}
@@ -1,16 +1,33 @@
 use clap::{Parser, Subcommand};
 use glob::glob;
 use indicatif::{ProgressBar, ProgressStyle};
+use parser::qualified_name;
 use render::get_path_for_name;
-use serde::Serialize;
-use std::{path::Path, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    time::Duration,
+};
 
 mod comment;
 mod config;
+mod copydoc;
+mod dirs;
 mod doctest;
+mod examples;
+mod filter;
+mod images;
+mod index;
+mod manifest;
 mod parser;
+mod refs;
 mod render;
 mod report;
+#[cfg(feature = "scripting")]
+mod script;
+mod source;
 mod templates;
 
 use report::{report_error, report_warning};
@@ -33,6 +50,197 @@ struct SearchIndex {
     name: String,
     link: String,
     kind: String,
+    anchor: Option<String>,
+    deprecated: bool,
+}
+
+/// Qualified names of every entity marked `[[deprecated]]`
+fn collect_deprecated(namespace: &parser::Namespace, names: &mut HashSet<String>) {
+    fn walk_record(record: &parser::Record, names: &mut HashSet<String>) {
+        if record.deprecated.is_some() {
+            names.insert(qualified_name(record.namespace.as_deref(), &record.name));
+        }
+
+        for nested in record.nested.iter().flatten() {
+            match nested {
+                parser::NestedField::Record(r) => walk_record(r, names),
+                parser::NestedField::Enum(e) => {
+                    if e.deprecated.is_some() {
+                        names.insert(qualified_name(e.namespace.as_deref(), &e.name));
+                    }
+                }
+            }
+        }
+    }
+
+    for function in &namespace.functions {
+        if function.deprecated.is_some() {
+            names.insert(qualified_name(function.namespace.as_deref(), &function.name));
+        }
+    }
+
+    for record in &namespace.records {
+        walk_record(record, names);
+    }
+
+    for enm in namespace.enums.iter().filter(|e| e.deprecated.is_some()) {
+        names.insert(qualified_name(enm.namespace.as_deref(), &enm.name));
+    }
+
+    for alias in namespace.aliases.iter().filter(|a| a.deprecated.is_some()) {
+        names.insert(qualified_name(alias.namespace.as_deref(), &alias.name));
+    }
+
+    for variable in namespace.variables.iter().filter(|v| v.deprecated.is_some()) {
+        names.insert(qualified_name(variable.namespace.as_deref(), &variable.name));
+    }
+
+    for concept in namespace.concepts.iter().filter(|c| c.deprecated.is_some()) {
+        names.insert(qualified_name(concept.namespace.as_deref(), &concept.name));
+    }
+
+    for ns in &namespace.namespaces {
+        collect_deprecated(ns, names);
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// The `output.root_namespace` namespace, or an error naming the namespaces there are and the
+/// closest one to the configured name
+fn find_root_namespace<'a>(
+    root: &'a mut parser::Namespace,
+    name: &str,
+) -> Result<&'a mut parser::Namespace, String> {
+    if let Some(index) = root.namespaces.iter().position(|ns| ns.name == name) {
+        return Ok(&mut root.namespaces[index]);
+    }
+
+    let names = root
+        .namespaces
+        .iter()
+        .map(|ns| ns.name.as_str())
+        .filter(|ns| !ns.is_empty())
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        return Err(format!(
+            "Root namespace “{name}” (output.root_namespace) not found, no namespace was parsed"
+        ));
+    }
+
+    let mut message = format!(
+        "Root namespace “{name}” (output.root_namespace) not found, the top-level namespaces are: {}",
+        names.join(", ")
+    );
+
+    let closest = names
+        .iter()
+        .map(|ns| (edit_distance(name, ns), ns))
+        .min()
+        .filter(|(distance, _)| *distance <= name.chars().count() / 3 + 1);
+
+    if let Some((_, ns)) = closest {
+        message.push_str(&format!(". Did you mean “{ns}”?"));
+    }
+
+    Err(message)
+}
+
+/// Add a search entry for every function overload, pointing at its anchor on the shared page,
+/// for every method and constructor, pointing at its anchor on the record page, and for every
+/// friend function
+fn push_overload_entries(
+    namespace: &parser::Namespace,
+    index: &index::Index,
+    entries: &mut Vec<SearchIndex>,
+    id: &mut i32,
+) {
+    for function in &namespace.functions {
+        let name = qualified_name(function.namespace.as_deref(), &function.name);
+
+        // A single function has no anchor, its page is the entry of its name
+        let overloads = function.overloads.iter().flatten();
+
+        for overload in std::iter::once(function).chain(overloads).filter(|f| f.anchor.is_some()) {
+            entries.push(SearchIndex {
+                id: *id,
+                name: render::escape_html(&name),
+                link: get_path_for_name(&name, index).unwrap_or_default(),
+                kind: "function".to_string(),
+                anchor: overload.anchor.clone(),
+                deprecated: overload.deprecated.is_some(),
+            });
+
+            *id += 1;
+        }
+    }
+
+    for record in &namespace.records {
+        let record_name = qualified_name(record.namespace.as_deref(), &record.name);
+
+        let link = get_path_for_name(&record_name, index).unwrap_or_default();
+
+        for method in record.methods.iter().chain(&record.ctor) {
+            let name = format!("{}::{}", record_name, method.name);
+
+            for overload in std::iter::once(method).chain(method.overloads.iter().flatten()) {
+                entries.push(SearchIndex {
+                    id: *id,
+                    name: render::escape_html(&name),
+                    link: link.clone(),
+                    kind: "function".to_string(),
+                    anchor: overload.anchor.clone(),
+                    deprecated: overload.deprecated.is_some(),
+                });
+
+                *id += 1;
+            }
+        }
+
+        // Friend functions are named in the enclosing namespace, they aren't in the index but
+        // have their page next to the nested types of the record
+        for friend in &record.friends {
+            let name = qualified_name(record.namespace.as_deref(), &friend.name);
+
+            let page = format!("{}::{}", record_name, friend.name);
+            let link = render::get_path_for_entity(&page, "function").unwrap_or_default();
+
+            for overload in std::iter::once(friend).chain(friend.overloads.iter().flatten()) {
+                entries.push(SearchIndex {
+                    id: *id,
+                    name: render::escape_html(&name),
+                    link: link.clone(),
+                    kind: "function".to_string(),
+                    anchor: overload.anchor.clone(),
+                    deprecated: overload.deprecated.is_some(),
+                });
+
+                *id += 1;
+            }
+        }
+    }
+
+    for ns in &namespace.namespaces {
+        push_overload_entries(ns, index, entries, id);
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -46,9 +254,537 @@ enum Commands {
         /// Configuration file to use
         #[arg(short, long, default_value = "cppdoc.toml", value_name = "FILE")]
         config_file: Option<String>,
+
+        /// Skip parsing and render from a previous --dump-json output
+        #[arg(long, value_name = "FILE")]
+        from_json: Option<String>,
+
+        /// Print the template context of the entity with this qualified name
+        #[arg(long, value_name = "QUALIFIED_NAME")]
+        debug_context: Option<String>,
+
+        /// Write the --debug-context dump to this file instead of stdout
+        #[arg(long, value_name = "FILE", requires = "debug_context")]
+        debug_context_output: Option<String>,
+
+        /// Only document entities available in this version, according to their @since
+        #[arg(long, value_name = "VERSION")]
+        api_version: Option<String>,
+
+        /// Delete files in the output directory that this build didn't produce
+        #[clap(long)]
+        prune: bool,
+
+        /// With --prune, only print the files that would be removed
+        #[clap(long, requires = "prune")]
+        dry_run: bool,
+
+        /// Number of files parsed in parallel (default: number of cores). The clang crate allows
+        /// a single libclang instance per process, so each job is a `parse-worker` process
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<usize>,
+
+        /// Parse every file, without reading or writing the parse cache
+        #[clap(long)]
+        no_cache: bool,
+
+        /// Clear the parse cache first, e.g. after changing a header that other files include
+        #[clap(long, conflicts_with = "no_cache")]
+        fresh: bool,
+
+        /// Fail when clang reports errors while parsing, like `input.fail_on_error`
+        #[clap(long)]
+        strict: bool,
+
+        /// Print the clang arguments of every parsed file
+        #[clap(long)]
+        print_clang_args: bool,
+    },
+
+    /// Parse a share of the input files for `build --jobs`, printing their outputs as JSON
+    #[clap(name = "parse-worker", hide = true)]
+    ParseWorker {
+        #[arg(long, value_name = "FILE")]
+        config_file: String,
+
+        #[arg(long)]
+        worker: usize,
+
+        #[arg(long)]
+        workers: usize,
+
+        #[clap(long)]
+        no_cache: bool,
+
+        #[clap(long)]
+        print_clang_args: bool,
+    },
+}
+
+/// Default `pages.max_size`, in bytes
+const DEFAULT_MAX_PAGE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Read an extra page, refusing files that are too large, have an unexpected extension or
+/// aren't valid UTF-8 (a docs/**/* glob easily matches PDFs or generated files)
+fn read_extra_page(path: &Path, config: &config::Config) -> Result<String, String> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let allowed = match config.pages.extensions {
+        Some(ref extensions) => extensions.iter().any(|e| e.to_lowercase() == extension),
+        None => extension == "md" || extension == "markdown",
+    };
+
+    if !allowed {
+        return Err(format!("extension “{extension}” is not in pages.extensions"));
+    }
+
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    let max_size = config.pages.max_size.unwrap_or(DEFAULT_MAX_PAGE_SIZE);
+
+    if size > max_size {
+        return Err(format!("file is {size} bytes, over the {max_size} bytes limit of pages.max_size"));
+    }
+
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+
+    String::from_utf8(bytes).map_err(|e| {
+        format!(
+            "not valid UTF-8 (invalid byte at offset {}), is it a binary file?",
+            e.utf8_error().valid_up_to()
+        )
+    })
+}
+
+/// The files matched by the input globs, in order and without duplicates, minus the excluded ones
+fn input_files(config: &config::Config) -> Vec<String> {
+    let exclude = config
+        .input
+        .exclude
+        .iter()
+        .flatten()
+        .filter_map(|pattern| match glob::Pattern::new(pattern) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => {
+                report_warning(&format!("Invalid input.exclude pattern “{pattern}”: {e}"));
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+
+    for pattern in config.input.globs() {
+        for file in glob(pattern).expect("Failed to read glob pattern") {
+            match file {
+                Ok(file) => {
+                    if exclude.iter().any(|p| p.matches_path(&file)) {
+                        continue;
+                    }
+
+                    // `include/a.hpp` and `./include/a.hpp` are the same file
+                    let key = file.canonicalize().unwrap_or(file.clone());
+
+                    if seen.insert(key) {
+                        files.push(file.to_str().unwrap().to_string());
+                    }
+                }
+                Err(e) => {
+                    report_warning(&format!("Error reading input file: {e:}"));
+                }
+            };
+        }
+    }
+
+    files
+}
+
+/// Directory of the parse cache, relative to the project
+const CACHE_DIR: &str = ".cppdoc-cache";
+
+/// The input files with the input set they belong to, and how many times each is parsed (once
+/// per configuration). Job `i` parses file `i / passes` in configuration `i % passes`.
+fn input_jobs(config: &config::Config) -> (Vec<(usize, String)>, usize) {
+    // Each file is parsed with the arguments of the first set matching it
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+
+    for (set, set_config) in config.input_sets().iter().enumerate() {
+        for file in input_files(set_config) {
+            if seen.insert(file.clone()) {
+                files.push((set, file));
+            }
+        }
+    }
+
+    let passes = config
+        .input
+        .configurations
+        .as_ref()
+        .map_or(0, Vec::len)
+        .max(1);
+
+    (files, passes)
+}
+
+/// What a `parse-worker` process prints, one JSON message per line
+#[derive(Serialize, Deserialize)]
+enum WorkerMessage {
+    /// The output of a job
+    Parsed {
+        job: usize,
+        set: usize,
+        output: Box<parser::Output>,
+    },
+
+    /// The cache hits and the clang errors and warnings, once every job is parsed
+    Done {
+        cache_hits: usize,
+        errors: usize,
+        warnings: usize,
     },
 }
 
+/// Parse `jobs` with a single clang instance, of which there can only be one per process,
+/// passing each output to `parsed`. Returns the cache hits and the diagnostic counts.
+fn run_jobs(
+    config: &config::Config,
+    jobs: impl Iterator<Item = usize>,
+    cache: Option<&Path>,
+    print_arguments: bool,
+    parsed: &mut dyn FnMut(usize, usize, parser::Output),
+) -> (usize, (usize, usize)) {
+    let clang = clang::Clang::new().unwrap();
+    let sets = config.input_sets();
+    let (files, passes) = input_jobs(config);
+    let configurations = config.input.configurations.clone().unwrap_or_default();
+
+    let mut parser = parser::Parser::new(&clang);
+    parser.set_cache_dir(cache.map(Path::to_path_buf));
+    parser.set_print_arguments(print_arguments);
+
+    for job in jobs {
+        let (set, ref file) = files[job / passes];
+
+        parser.set_defines(
+            configurations
+                .get(job % passes)
+                .map(|c| c.defines.clone())
+                .unwrap_or_default(),
+        );
+
+        let mut output = parser::Output::default();
+        parser.parse(&sets[set], file, &mut output);
+        parsed(job, set, output);
+    }
+
+    (parser.cache_hits(), parser.diagnostic_counts())
+}
+
+/// Parse the jobs numbered `worker` modulo `workers`, printing their outputs to stdout for the
+/// `build` process that spawned this one
+fn parse_worker(
+    config: &config::Config,
+    worker: usize,
+    workers: usize,
+    cache: Option<&Path>,
+    print_arguments: bool,
+) {
+    let (files, passes) = input_jobs(config);
+    let jobs = (worker..files.len() * passes).step_by(workers.max(1));
+    let mut stdout = std::io::stdout().lock();
+
+    let mut print = |message: &WorkerMessage| {
+        let line = serde_json::to_string(message).unwrap();
+
+        if writeln!(stdout, "{line}")
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            std::process::exit(1);
+        }
+    };
+
+    let (cache_hits, (errors, warnings)) = run_jobs(
+        config,
+        jobs,
+        cache,
+        print_arguments,
+        &mut |job, set, output| {
+            print(&WorkerMessage::Parsed {
+                job,
+                set,
+                output: Box::new(output),
+            });
+        },
+    );
+
+    print(&WorkerMessage::Done {
+        cache_hits,
+        errors,
+        warnings,
+    });
+}
+
+/// The cache hits, errors and warnings of a `parse-worker` process, and its outputs
+type WorkerResult = ((usize, usize, usize), Vec<(usize, usize, parser::Output)>);
+
+/// Read the messages of a `parse-worker` process until it exits, calling `parsed` with each
+/// job. `None` when the worker failed or crashed before reporting every job.
+fn read_worker(
+    mut child: std::process::Child,
+    parsed: &mut dyn FnMut(usize),
+) -> Option<WorkerResult> {
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut fragments = Vec::new();
+    let mut counts = None;
+
+    for line in stdout.lines().map_while(Result::ok) {
+        match serde_json::from_str(&line) {
+            Ok(WorkerMessage::Parsed { job, set, output }) => {
+                fragments.push((job, set, *output));
+                parsed(job);
+            }
+            Ok(WorkerMessage::Done {
+                cache_hits,
+                errors,
+                warnings,
+            }) => counts = Some((cache_hits, errors, warnings)),
+            Err(_) => break,
+        }
+    }
+
+    let success = child.wait().is_ok_and(|status| status.success());
+    counts.filter(|_| success).map(|counts| (counts, fragments))
+}
+
+/// Parse the input files, exiting when clang reports errors and `strict` is set. libclang
+/// allows a single instance per process, so `jobs` above one parse in `parse-worker` processes.
+fn parse_input(
+    config: &config::Config,
+    config_file: &str,
+    jobs: usize,
+    cache: Option<&Path>,
+    strict: bool,
+    print_arguments: bool,
+) -> parser::Output {
+    let (files, passes) = input_jobs(config);
+
+    eprintln!("Found {} input files", files.len());
+
+    // Every file is parsed once per configuration, the jobs of a file being consecutive
+    let configurations = config.input.configurations.clone().unwrap_or_default();
+    let total = files.len() * passes;
+
+    let bar = ProgressBar::new(total as u64);
+    bar.set_style(ProgressStyle::with_template("Parsing {pos}/{len} {msg}").unwrap());
+
+    let workers = jobs.clamp(1, total.max(1));
+
+    // The outputs are merged in job order, so the result doesn't depend on timing
+    let mut fragments = Vec::new();
+    let mut cache_hits = 0;
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    if workers == 1 {
+        let counts = run_jobs(
+            config,
+            0..total,
+            cache,
+            print_arguments,
+            &mut |job, set, output| {
+                fragments.push((job, set, output));
+                bar.set_message(files[job / passes].1.clone());
+                bar.inc(1);
+            },
+        );
+
+        (cache_hits, (errors, warnings)) = counts;
+    } else {
+        let exe = std::env::current_exe().unwrap_or_else(|e| {
+            report_error(&format!(
+                "Unable to find the cppdoc executable for --jobs: {e}"
+            ));
+            std::process::exit(1);
+        });
+
+        let children = (0..workers)
+            .map(|worker| {
+                let mut command = std::process::Command::new(&exe);
+                command
+                    .args(["parse-worker", "--config-file", config_file])
+                    .args([
+                        "--worker",
+                        &worker.to_string(),
+                        "--workers",
+                        &workers.to_string(),
+                    ])
+                    .stdout(std::process::Stdio::piped());
+
+                if cache.is_none() {
+                    command.arg("--no-cache");
+                }
+
+                if print_arguments {
+                    command.arg("--print-clang-args");
+                }
+
+                command.spawn()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| {
+                report_error(&format!("Unable to start the parsing workers: {e}"));
+                std::process::exit(1);
+            });
+
+        // A thread per worker reads its messages, clang itself only runs in the workers
+        let (bar, files) = (&bar, &files);
+        let results = std::thread::scope(|scope| {
+            let readers = children
+                .into_iter()
+                .map(|child| {
+                    scope.spawn(move || {
+                        read_worker(child, &mut |job| {
+                            bar.set_message(files[job / passes].1.clone());
+                            bar.inc(1);
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            readers
+                .into_iter()
+                .map(|reader| reader.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        for result in results {
+            let Some(((hits, worker_errors, worker_warnings), worker_fragments)) = result else {
+                bar.finish_and_clear();
+                report_error("A parsing worker failed, see the errors above");
+                std::process::exit(1);
+            };
+
+            cache_hits += hits;
+            errors += worker_errors;
+            warnings += worker_warnings;
+            fragments.extend(worker_fragments);
+        }
+    }
+
+    bar.finish_and_clear();
+
+    if errors + warnings > 0 {
+        eprintln!("{errors} errors, {warnings} warnings while parsing");
+    }
+
+    if strict && errors > 0 {
+        report_error("Stopping because of the errors while parsing (--strict or input.fail_on_error)");
+        std::process::exit(1);
+    }
+
+    if cache_hits > 0 {
+        report_warning(&format!(
+            "Reused the cached parse of {cache_hits} unchanged files. Changes to the headers they \
+             include aren't detected, run with --fresh to parse everything again"
+        ));
+    }
+
+    fragments.sort_by_key(|(i, _, _)| *i);
+
+    // The outputs of a file in every configuration become a single one, where the entities
+    // only some configurations declare know which
+    if !configurations.is_empty() {
+        let mut outputs = fragments.into_iter().peekable();
+        fragments = Vec::new();
+
+        while let Some((i, set, output)) = outputs.next() {
+            let mut named = vec![(configurations[i % passes].name.clone(), output)];
+
+            while let Some((j, _, output)) = outputs.next_if(|(j, _, _)| j / passes == i / passes) {
+                named.push((configurations[j % passes].name.clone(), output));
+            }
+
+            fragments.push((i, set, parser::merge_configurations(named)));
+        }
+    }
+
+    let mut output: parser::Output = Default::default();
+
+    // Set of the files each entity was first declared in. The same record, enum... defined by
+    // the files of two sets keeps its first definition, functions become overloads as usual.
+    let mut declared_in: HashMap<String, usize> = HashMap::new();
+
+    for (_, set, mut fragment) in fragments {
+        let mut conflicts = HashSet::new();
+
+        for (name, entry) in fragment
+            .index
+            .iter()
+            .chain(fragment.headers.values().flat_map(|h| h.index.iter()))
+        {
+            // Namespaces and namespace aliases can be reopened and redeclared
+            if matches!(
+                entry.kind.as_str(),
+                "namespace" | "inline namespace" | "namespace alias" | "function"
+            ) {
+                continue;
+            }
+
+            match declared_in.get(name) {
+                Some(&first) if first != set => {
+                    if conflicts.insert(name.clone()) {
+                        report_warning(&format!(
+                            "“{name}” is defined by the files of input sets {} and {}, keeping the first definition",
+                            first + 1,
+                            set + 1
+                        ));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    declared_in.insert(name.clone(), set);
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            parser::remove_declarations(&mut fragment, &conflicts);
+        }
+
+        parser::merge_outputs(&mut output, fragment);
+    }
+
+    report_collisions(&output.index);
+
+    output
+}
+
+/// Warn about the names referring to several entities, links to them only go to one
+fn report_collisions(index: &index::Index) {
+    for (name, entries) in index.collisions() {
+        let entities = entries
+            .iter()
+            .map(|entry| match &entry.location {
+                Some(location) => {
+                    format!("{} at {}:{}", entry.kind, location.file, location.line)
+                }
+                None => entry.kind.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        report_warning(&format!(
+            "“{name}” names several entities ({entities}), links to it go to the {}",
+            index.get(name).map(String::as_str).unwrap_or_default()
+        ));
+    }
+}
+
 fn main() {
     let args = Cli::parse();
 
@@ -56,6 +792,17 @@ fn main() {
         Commands::Build {
             dump_json,
             config_file,
+            from_json,
+            debug_context,
+            debug_context_output,
+            api_version,
+            prune,
+            dry_run,
+            jobs,
+            no_cache,
+            fresh,
+            strict,
+            print_clang_args,
         } => {
             let config_file = config_file.unwrap_or("cppdoc.toml".to_string());
 
@@ -67,28 +814,65 @@ fn main() {
                 }
             };
 
-            let clang = clang::Clang::new().unwrap();
-            let mut parser = parser::Parser::new(&clang);
+            let strict = strict || config.input.fail_on_error.unwrap_or(false);
 
-            let mut output: parser::Output = Default::default();
-
-            let bar = ProgressBar::new_spinner();
-
-            for file in glob(&config.input.glob).expect("Failed to read glob pattern") {
-                match file {
-                    Ok(file) => {
-                        bar.set_message(format!("Parsing {}", file.to_str().unwrap()));
-                        parser.parse(&config, file.to_str().unwrap(), &mut output);
-                        bar.tick();
-                    },
+            let mut output: parser::Output = match from_json {
+                Some(ref path) => match std::fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+                {
+                    Ok(output) => output,
                     Err(e) => {
-                        report_warning(&format!("Error reading input file: {e:}"));
+                        report_error(&format!("Error reading JSON output “{path}”: {e}"));
+                        std::process::exit(1);
                     }
-                };
+                },
+                None => {
+                    let jobs = jobs.unwrap_or_else(|| {
+                        std::thread::available_parallelism().map_or(1, |n| n.get())
+                    });
 
+                    if fresh {
+                        if let Err(e) = std::fs::remove_dir_all(CACHE_DIR) {
+                            if e.kind() != std::io::ErrorKind::NotFound {
+                                report_warning(&format!("Error clearing the parse cache: {e}"));
+                            }
+                        }
+                    }
+
+                    let cache = (!no_cache).then_some(Path::new(CACHE_DIR));
+
+                    parse_input(&config, &config_file, jobs, cache, strict, print_clang_args)
+                }
+            };
+
+            copydoc::resolve_copydocs(&mut output);
+            examples::load_examples(&mut output, &config);
+
+            if let Some(ref version) = config.project.current_version {
+                output.current_version = Some(version.clone());
             }
 
-            bar.finish_and_clear();
+            if let Some(ref version) = api_version {
+                filter::filter_since(
+                    &mut output,
+                    version,
+                    config.project.version_scheme.as_deref().unwrap_or("semver"),
+                );
+            }
+
+            if config.output.require_comment.unwrap_or(false) {
+                let hidden = filter::filter_undocumented(&mut output);
+
+                if hidden > 0 {
+                    report_warning(&format!(
+                        "{hidden} undocumented symbols are hidden by output.require_comment"
+                    ));
+                }
+            }
+
+            parser::collect_group_members(&mut output);
+            parser::collect_implementers(&mut output, strict);
 
             if dump_json {
                 let json = serde_json::to_string_pretty(&output).unwrap();
@@ -97,23 +881,114 @@ fn main() {
             }
 
             let root_namespace = if let Some(ref root_namespace) = config.output.root_namespace {
-                // Find namespace
-                output
-                    .root
-                    .namespaces
-                    .iter_mut()
-                    .find(|ns| ns.name == *root_namespace)
-                    .unwrap()
+                match find_root_namespace(&mut output.root, root_namespace) {
+                    Ok(ns) => ns,
+                    Err(e) => {
+                        report_error(&e);
+                        std::process::exit(1);
+                    }
+                }
             } else {
                 &mut output.root
             };
 
+            if root_namespace.records.is_empty()
+                && root_namespace.functions.is_empty()
+                && root_namespace.namespaces.is_empty()
+                && root_namespace.enums.is_empty()
+                && root_namespace.aliases.is_empty()
+                && root_namespace.variables.is_empty()
+                && root_namespace.concepts.is_empty()
+            {
+                report_warning(&format!(
+                    "The root namespace{} is empty, check the input globs and the filters \
+                     (output.exclude_symbols, output.require_comment...)",
+                    config
+                        .output
+                        .root_namespace
+                        .as_ref()
+                        .map(|name| format!(" “{name}”"))
+                        .unwrap_or_default()
+                ));
+            }
+
             let mut doctests = Vec::new();
 
+            let mut extra_sources = Vec::new();
+
+            for g in &config.pages.extra.clone().unwrap_or_default() {
+                for file in glob(g).expect("Failed to read glob pattern") {
+                    match file {
+                        Ok(page_path) => {
+                            match read_extra_page(&page_path, &config) {
+                                Ok(source) => extra_sources.push((page_path, source)),
+                                Err(e) => {
+                                    report_warning(&format!("Skipping extra file “{page_path:?}”: {e}"));
+                                }
+                            };
+                        },
+                        Err(e) => {
+                            report_warning(&format!("Error reading extra file “{g}”: {e}"));
+                        }
+                    };
+                }
+            }
+
+            let index_source =
+                config.pages.index.as_ref().map(|x| std::fs::read_to_string(x).unwrap());
+
+            output.index.set_method_anchors(render::method_anchors(root_namespace));
+            output.index.set_namespace_aliases(templates::namespace_aliases(root_namespace));
+
+            // Anchors are collected from every comment and page first, so @ref can point anywhere
+            let scope = config.output.root_namespace.as_deref().unwrap_or_default();
+            let mut anchor_pages: Vec<_> = extra_sources
+                .iter()
+                .map(|(path, source)| {
+                    let name = path.display().to_string();
+                    let url = format!("{}/{}.html", config.output.base_url, name);
+                    (name, url, source.as_str())
+                })
+                .collect();
+
+            if let (Some(path), Some(source)) = (&config.pages.index, &index_source) {
+                let url = format!("{}/index.html", config.output.base_url);
+                anchor_pages.push((path.clone(), url, source.as_str()));
+            }
+
+            let anchors =
+                refs::collect_anchors(root_namespace, scope, &anchor_pages, &output.index, &config);
+            refs::resolve_comment_refs(root_namespace, scope, &anchors, &output.index, &config);
+            let image_files = images::resolve_images(root_namespace, scope, &config);
+
             render::process_namespace(root_namespace, &output.index, &mut doctests, &config);
 
-            let index = match config.pages.index {
-                Some(ref x) => std::fs::read_to_string(x).unwrap(),
+            for group in &mut output.groups {
+                render::process_group(group, &output.index, &mut doctests, &config);
+            }
+
+            if !config.output.keep_empty_namespaces.unwrap_or(false) {
+                render::prune_empty_namespaces(
+                    root_namespace,
+                    config.output.root_namespace.as_deref().unwrap_or_default(),
+                    &mut output.index,
+                );
+            }
+            render::attach_inherited(root_namespace, &output.index);
+            render::sort_members(
+                root_namespace,
+                config.output.member_order.as_deref().unwrap_or("alphabetical"),
+            );
+
+            let index = match index_source {
+                Some(ref source) => refs::resolve_refs(
+                    source,
+                    config.pages.index.as_deref().unwrap_or_default(),
+                    scope,
+                    &anchors,
+                    &output.index,
+                    &config,
+                ),
                 None => match root_namespace.comment {
                     Some(ref comment) => comment.description.clone(),
                     None => String::new(),
@@ -125,32 +1000,55 @@ fn main() {
 
             let mut extra_pages = Vec::new();
 
-            for g in &config.pages.extra.clone().unwrap_or_default() {
-                for file in glob(g).expect("Failed to read glob pattern") {
-                    match file {
-                        Ok(page_path) => {
-                            match std::fs::read_to_string(&page_path) {
-                                Ok(source) => {
-                                    let mut page =
-                                        render::process_markdown(&source, &output.index, &mut doctests, &config);
-                                    if page.title.is_empty() {
-                                        page.title = page_path.file_name().unwrap().to_string_lossy().into_owned();
-                                    }
-                                    page.path = page_path;
-                                    extra_pages.push(page);
-                                },
-                                Err(e) => {
-                                    report_warning(&format!("Error reading extra file “{page_path:?}”: {e}"));
-                                }
-                            };
-                        },
-                        Err(e) => {
-                            report_warning(&format!("Error reading extra file “{g}”: {e}"));
+            for (page_path, source) in &extra_sources {
+                let source = refs::resolve_refs(
+                    source,
+                    &page_path.display().to_string(),
+                    scope,
+                    &anchors,
+                    &output.index,
+                    &config,
+                );
+                let mut page =
+                    render::process_markdown(&source, &output.index, &mut doctests, &config);
+                if page.title.is_empty() {
+                    page.title = page_path.file_name().unwrap().to_string_lossy().into_owned();
+                }
+                page.path = page_path.clone();
+                extra_pages.push(page);
+            }
+
+            if let Some(ref license_file) = config.project.license_file {
+                match std::fs::read_to_string(license_file) {
+                    Ok(source) => {
+                        let mut page = if license_file.ends_with(".md") {
+                            render::process_markdown(&source, &output.index, &mut doctests, &config)
+                        } else {
+                            render::Page {
+                                title: String::new(),
+                                content: format!("<pre>{}</pre>", render::escape_html(&source)),
+                                path: Default::default(),
+                            }
+                        };
+
+                        if page.title.is_empty() {
+                            page.title = "License".to_string();
                         }
-                    };
+                        page.path = "license".into();
+                        extra_pages.push(page);
+                    }
+                    Err(e) => {
+                        report_warning(&format!("Error reading license file “{license_file}”: {e}"));
+                    }
                 }
             }
 
+            let directories = dirs::collect(&output.files, &config);
+
+            for dir in &directories {
+                extra_pages.push(dirs::output_dir_page(dir, &output.index, &mut doctests, &config));
+            }
+
             let pages = Pages {
                 index: index_html,
                 extra: extra_pages,
@@ -205,6 +1103,15 @@ fn main() {
                 .unwrap();
             }
 
+            let mut site = templates::Site {
+                base_classes: templates::base_classes(root_namespace, &output.index),
+                groups: output.groups.clone(),
+                implementers: output.implementers.clone(),
+                dir_pages: dirs::dir_pages(&directories, &config),
+                debug_context: debug_context.map(|name| (name, debug_context_output)),
+                ..Default::default()
+            };
+
             let tera = templates::init(&output.index, &config);
             let mut context = tera::Context::new();
 
@@ -216,9 +1123,12 @@ fn main() {
                 context.insert("content", &page.content);
                 context.insert("title", &page.title);
 
-                std::fs::write(
+                manifest::write(
+                    &site.manifest,
                     format!("{}/{}.html", config.output.path, page.path.display()),
-                    tera.render("docpage", &context).unwrap(),
+                    templates::render(&tera, "docpage", &context, &page.title, &site)
+                        .unwrap_or_else(|_| std::process::exit(1)),
+                    manifest::Category::ExtraPage,
                 )
                 .map_err(|e| {
                     report_error(&format!("Error writing extra page file: {}", e));
@@ -227,9 +1137,12 @@ fn main() {
                 .unwrap();
             }
 
-            std::fs::write(
+            manifest::write(
+                &site.manifest,
                 format!("{}/search.html", config.output.path),
-                tera.render("search", &context).unwrap(),
+                templates::render(&tera, "search", &context, "search", &site)
+                    .unwrap_or_else(|_| std::process::exit(1)),
+                manifest::Category::Search,
             )
             .map_err(|e| {
                 report_error(&format!("Error writing search page file: {}", e));
@@ -237,11 +1150,41 @@ fn main() {
             })
             .unwrap();
 
+            if config.output.emit_source.unwrap_or(false) {
+                site.source_pages =
+                    source::output_source_pages(&output.files, &config, &tera, &mut context, &site);
+            }
+
             let bar = ProgressBar::new_spinner();
             bar.enable_steady_tick(Duration::from_millis(100));
             bar.set_message("Rendering root namespace");
-            templates::output_namespace(root_namespace, &pages, &config, &output.index, &tera)
-                .unwrap();
+            templates::output_namespace(
+                root_namespace,
+                &pages,
+                &config,
+                &output.index,
+                &site,
+                &tera,
+            )
+            .unwrap();
+            templates::output_groups(
+                root_namespace,
+                &pages,
+                &config,
+                &output.index,
+                &site,
+                &tera,
+            )
+            .unwrap();
+            templates::output_examples(
+                &output.examples,
+                &pages,
+                &config,
+                &output.index,
+                &site,
+                &tera,
+            )
+            .unwrap();
             bar.finish_and_clear();
 
             // Copy everything in the static directory to the output directory
@@ -250,30 +1193,67 @@ fn main() {
                 let path = entry.path();
                 let filename = path.file_name().unwrap();
                 let dest = format!("{}/{}", config.output.path, filename.to_str().unwrap());
-                std::fs::copy(&path, &dest).unwrap();
+                manifest::copy(&site.manifest, &path, &dest, manifest::Category::Static).unwrap();
             }
 
+            images::copy_images(&image_files, &config, &site.manifest);
+
             // Make a new, more searchable index
             let mut id: i32 = 0;
             let mut index = Vec::new();
 
-            for item in &output.index {
+            let mut deprecated = HashSet::new();
+            collect_deprecated(root_namespace, &mut deprecated);
+
+            // In name order, so that ids are the same from one build to the next. Names
+            // referring to several entities get an entry for each of them.
+            let mut items = output.index.iter().collect::<Vec<_>>();
+            items.sort_by(|a, b| (a.0, &a.1.kind).cmp(&(b.0, &b.1.kind)));
+
+            for (name, entry) in items {
+                // Inline namespaces are collapsed, only list their content once
+                if entry.kind == "inline namespace"
+                    || render::collapse_inline_namespaces(name, &output.index) != *name
+                {
+                    continue;
+                }
+
                 index.push(SearchIndex {
                     id,
-                    name: item.0.clone().replace("\"", "&quot;"),
-                    link: match item.1.as_str() {
+                    name: render::escape_html(name),
+                    link: match entry.kind.as_str() {
                         "namespace" => {
                             format!(
                                 "{}/index",
-                                get_path_for_name(item.0, &output.index).unwrap_or_default()
+                                get_path_for_name(name, &output.index).unwrap_or_default()
                             )
                         }
-                        _ => get_path_for_name(item.0, &output.index).unwrap_or_default(),
-                    }
-                    .replace("\"", "&quot;")
-                    .to_string(),
+                        // The page of the aliased namespace
+                        "namespace alias" => output.index.namespace_alias_target(name)
+                            .and_then(|target| get_path_for_name(target, &output.index))
+                            .map(|path| format!("{path}/index"))
+                            .unwrap_or_default(),
+                        kind => render::get_path_for_entity(name, kind).unwrap_or_default(),
+                    },
+
+                    kind: entry.kind.clone(),
+                    anchor: None,
+                    deprecated: deprecated.contains(name),
+                });
+
+                id += 1;
+            }
+
+            push_overload_entries(root_namespace, &output.index, &mut index, &mut id);
 
-                    kind: item.1.clone(),
+            for group in &output.groups {
+                index.push(SearchIndex {
+                    id,
+                    name: render::escape_html(templates::group_title(group)),
+                    link: format!("group.{}", group.name),
+                    kind: "group".to_string(),
+                    anchor: None,
+                    deprecated: false,
                 });
 
                 id += 1;
@@ -286,6 +1266,8 @@ fn main() {
                     name: page.title.clone(),
                     link: page.path.to_string_lossy().into_owned(),
                     kind: "page".to_string(),
+                    anchor: None,
+                    deprecated: false,
                 });
 
                 id += 1;
@@ -293,13 +1275,123 @@ fn main() {
 
             let index_json = serde_json::to_string_pretty(&index).unwrap();
 
-            std::fs::write(
+            manifest::write(
+                &site.manifest,
                 format!("{}/search_index.json", config.output.path),
                 index_json,
+                manifest::Category::Search,
             )
             .unwrap();
 
-            println!("Documentation generated in {}", config.output.path);
+            if let Err(e) = manifest::write_manifest(&site.manifest, &config.output.path) {
+                report_error(&format!("Error writing manifest: {}", e));
+                std::process::exit(1);
+            }
+
+            if prune {
+                match manifest::prune(&site.manifest, &config.output.path, dry_run) {
+                    Ok(removed) => {
+                        for path in removed {
+                            if dry_run {
+                                println!("Would remove {}", path.display());
+                            } else {
+                                println!("Removed {}", path.display());
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        report_error(&format!("Error pruning output directory: {}", e));
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            // A context dumped to stdout is kept alone there, for tools reading it
+            if !matches!(site.debug_context, Some((_, None))) {
+                println!("Documentation generated in {}", config.output.path);
+            }
+        }
+        Commands::ParseWorker {
+            config_file,
+            worker,
+            workers,
+            no_cache,
+            print_clang_args,
+        } => {
+            let config = match config::Config::new(&config_file) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("Error reading config file: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let cache = (!no_cache).then_some(Path::new(CACHE_DIR));
+            parse_worker(&config, worker, workers, cache, print_clang_args);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    /// A stand-in for a `parse-worker` process, running `script` in a shell
+    fn worker(script: &str) -> std::process::Child {
+        Command::new("sh")
+            .args(["-c", script])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    fn message(message: &WorkerMessage) -> String {
+        format!("'{}'", serde_json::to_string(message).unwrap())
+    }
+
+    fn parsed(job: usize) -> String {
+        message(&WorkerMessage::Parsed {
+            job,
+            set: 0,
+            output: Box::default(),
+        })
+    }
+
+    fn done() -> String {
+        message(&WorkerMessage::Done {
+            cache_hits: 1,
+            errors: 2,
+            warnings: 3,
+        })
+    }
+
+    #[test]
+    fn worker_outputs_are_collected() {
+        let mut jobs = Vec::new();
+        let child = worker(&format!("printf '%s\\n' {} {} {}", parsed(0), parsed(2), done()));
+
+        let (counts, fragments) = read_worker(child, &mut |job| jobs.push(job)).unwrap();
+
+        assert_eq!(counts, (1, 2, 3));
+        assert_eq!(fragments.iter().map(|(job, _, _)| *job).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(jobs, [0, 2]);
+    }
+
+    #[test]
+    fn failing_worker_is_reported() {
+        // Even after printing every message, a worker exiting with an error failed
+        let child = worker(&format!("printf '%s\\n' {} {}; exit 1", parsed(0), done()));
+        assert!(read_worker(child, &mut |_| {}).is_none());
+    }
+
+    #[test]
+    fn crashing_worker_is_reported() {
+        // The files this worker didn't get to would otherwise be missing from the output
+        let child = worker(&format!("printf '%s\\n' {}; kill -9 $$", parsed(0)));
+        assert!(read_worker(child, &mut |_| {}).is_none());
+
+        let child = worker(&format!("printf '%s\\n' {} 'Segmentation fault'", parsed(0)));
+        assert!(read_worker(child, &mut |_| {}).is_none());
+    }
+}
@@ -0,0 +1,173 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    EntityPage,
+    ExtraPage,
+    Search,
+    Static,
+    Asset,
+    Source,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Entry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub category: Category,
+}
+
+/// The files written during a build, so that the manifest never has to guess from the contents
+/// of the output directory (which may hold stale or unrelated files)
+#[derive(Debug, Default)]
+pub struct Recorder {
+    written: Mutex<BTreeMap<PathBuf, Category>>,
+
+    /// Files kept by `--prune` but left out of the manifest, like `--debug-context-output`
+    kept: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl Recorder {
+    fn record(&self, path: &Path, category: Category) {
+        self.written
+            .lock()
+            .unwrap()
+            .insert(path.components().collect(), category);
+    }
+
+    /// Keep `path` when pruning, without listing it in the manifest
+    pub fn keep<P: AsRef<Path>>(&self, path: P) {
+        self.kept
+            .lock()
+            .unwrap()
+            .insert(path.as_ref().components().collect());
+    }
+
+    fn relative_files(&self, output_path: &str) -> BTreeMap<PathBuf, Category> {
+        let root = Path::new(output_path).components().collect::<PathBuf>();
+
+        self.written
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(path, category)| {
+                path.strip_prefix(&root)
+                    .ok()
+                    .map(|p| (p.to_path_buf(), *category))
+            })
+            .collect()
+    }
+
+    fn kept_files(&self, output_path: &str) -> BTreeSet<PathBuf> {
+        let root = Path::new(output_path).components().collect::<PathBuf>();
+
+        self.kept
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|path| path.strip_prefix(&root).ok().map(Path::to_path_buf))
+            .collect()
+    }
+}
+
+/// `std::fs::write`, keeping track of the file for the manifest
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(
+    recorder: &Recorder,
+    path: P,
+    contents: C,
+    category: Category,
+) -> std::io::Result<()> {
+    std::fs::write(&path, contents)?;
+    recorder.record(path.as_ref(), category);
+    Ok(())
+}
+
+/// `std::fs::copy`, keeping track of the destination for the manifest
+pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(
+    recorder: &Recorder,
+    from: P,
+    to: Q,
+    category: Category,
+) -> std::io::Result<u64> {
+    let ret = std::fs::copy(from, &to)?;
+    recorder.record(to.as_ref(), category);
+    Ok(ret)
+}
+
+/// Write `manifest.json` at the root of the output directory
+pub fn write_manifest(recorder: &Recorder, output_path: &str) -> std::io::Result<()> {
+    let mut entries = Vec::new();
+
+    for (path, category) in recorder.relative_files(output_path) {
+        let contents = std::fs::read(Path::new(output_path).join(&path))?;
+
+        entries.push(Entry {
+            path: path.to_string_lossy().replace('\\', "/"),
+            size: contents.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&contents)),
+            category,
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&entries).unwrap();
+    std::fs::write(Path::new(output_path).join("manifest.json"), json)
+}
+
+/// Delete the files of the output directory that this build didn't write. Returns the removed
+/// paths; with `dry_run` nothing is deleted.
+pub fn prune(
+    recorder: &Recorder,
+    output_path: &str,
+    dry_run: bool,
+) -> std::io::Result<Vec<PathBuf>> {
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+
+            // Never follow symlinks, they may point outside of the output directory
+            if file_type.is_dir() {
+                walk(&entry.path(), out)?;
+            } else {
+                out.push(entry.path());
+            }
+        }
+
+        Ok(())
+    }
+
+    let root = Path::new(output_path);
+    let written = recorder.relative_files(output_path);
+    let kept = recorder.kept_files(output_path);
+
+    let mut files = Vec::new();
+    walk(root, &mut files)?;
+
+    let mut removed = Vec::new();
+
+    for file in files {
+        let relative = file.strip_prefix(root).unwrap().to_path_buf();
+
+        if written.contains_key(&relative)
+            || kept.contains(&relative)
+            || relative == Path::new("manifest.json")
+        {
+            continue;
+        }
+
+        if !dry_run {
+            std::fs::remove_file(&file)?;
+        }
+
+        removed.push(relative);
+    }
+
+    Ok(removed)
+}
@@ -1,23 +1,150 @@
 use crate::comment;
 use crate::config;
-use serde::Serialize;
-use std::collections::HashMap;
-
-#[derive(Serialize, Debug, Clone)]
+use crate::copydoc;
+use crate::index::Index;
+use crate::render;
+use crate::report::{report_error, report_warning};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Comment {
     pub description: String,
     pub brief: String,
     #[serde(rename = "impl")]
     pub impl_: Option<Vec<String>>,
+
+    /// Version the entity was introduced in, from `@since`
+    pub since: Option<String>,
+
+    /// Name and description of the parameters documented with `@param`, the description
+    /// starting with the direction (`[in]`, `[out]`, `[in,out]`) when one is given
+    #[serde(default)]
+    pub params: Vec<(String, String)>,
+
+    /// What a function returns, from `@return`/`@returns`
+    #[serde(default)]
+    pub returns: Option<String>,
+
+    /// Values a function returns, with their meaning, from `@retval VALUE description`
+    #[serde(default)]
+    pub retvals: Vec<(String, String)>,
+
+    /// Exceptions a function throws, with when, from `@throws Type description` (and
+    /// `@throw`/`@exception`), one entry per type
+    #[serde(default)]
+    pub throws: Vec<(String, String)>,
+
+    /// Name and description of the template parameters documented with `@tparam`
+    #[serde(default)]
+    pub tparams: Vec<(String, String)>,
+
+    /// Related symbols or markdown links, from `@see`/`@sa`, as HTML once rendered
+    #[serde(default)]
+    pub see_also: Vec<String>,
+
+    /// Admonitions from `@note`, `@warning`, `@attention` and `@bug`, and the `@internal`
+    /// sections, with their text
+    #[serde(default)]
+    pub notes: Vec<(NoteKind, String)>,
+
+    /// Message of `@deprecated` (empty when it has none), as HTML linking the symbols it
+    /// names once rendered
+    #[serde(default)]
+    pub deprecated: Option<String>,
+
+    /// Names of the groups the entity is in, from `@ingroup`
+    #[serde(default)]
+    pub groups: Vec<String>,
+
+    /// Entity whose documentation `@copydoc` copies, until `copydoc::resolve_copydocs` does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copydoc: Option<String>,
+
+    /// Whether the brief is the first sentence of the description, which still starts with it,
+    /// rather than an explicit `@brief`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_brief: bool,
+
+    /// Conditions on the call, from `@pre`, as HTML once rendered
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preconditions: Vec<String>,
+
+    /// Conditions once the call returns, from `@post`, as HTML once rendered
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub postconditions: Vec<String>,
+
+    /// Whether the comment starts with `@internal`, the entity counting as undocumented
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub internal: bool,
+
+    /// Example files shown with `@example`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<Example>,
+}
+
+/// A source file that `@example file` shows in the documentation of an entity
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Example {
+    /// Path of the file in one of `doctests.example_dirs`
+    pub file: String,
+
+    /// `@example{doctest}`, the file is compiled (and run) with the doctests
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub doctest: bool,
+
+    /// Content of the file, read by `examples::load_examples` after parsing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+/// Kind of an admonition, after the command starting it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteKind {
+    Note,
+    Warning,
+    Attention,
+    Bug,
+
+    /// `@internal` sections, only shown with `output.show_internal`
+    Internal,
 }
 
-#[derive(Serialize, Debug, Clone)]
+impl NoteKind {
+    /// Name of the command, also the CSS class of the callout
+    pub fn name(self) -> &'static str {
+        match self {
+            NoteKind::Note => "note",
+            NoteKind::Warning => "warning",
+            NoteKind::Attention => "attention",
+            NoteKind::Bug => "bug",
+            NoteKind::Internal => "internal",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            NoteKind::Note => "Note",
+            NoteKind::Warning => "Warning",
+            NoteKind::Attention => "Attention",
+            NoteKind::Bug => "Bug",
+            NoteKind::Internal => "Internal",
+        }
+    }
+}
+
+// Nested types are few and built once, boxing them isn't worth the churn
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum NestedField {
     Record(Record),
     Enum(Enum),
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Field {
     pub name: String,
     #[serde(rename = "type")]
@@ -26,9 +153,30 @@ pub struct Field {
 
     #[serde(rename = "struct")]
     pub struct_: Option<NestedField>,
+
+    /// Default argument of a function parameter, as written in the source
+    pub default_value: Option<String>,
+
+    /// Width of a bit-field
+    pub bit_width: Option<u32>,
+
+    /// Access of a record member ("public", "protected")
+    pub access: Option<String>,
+
+    /// Names of the `input.configurations` declaring a record member, empty when all of them do
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub available_in: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BaseClass {
+    pub name: String,
+    pub access: String,
+    #[serde(rename = "virtual")]
+    pub virtual_: bool,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Record {
     pub name: String,
     pub fields: Vec<Field>,
@@ -36,26 +184,131 @@ pub struct Record {
     pub kind: String,
     pub namespace: Option<String>,
     pub ctor: Vec<Function>,
+    pub dtor: Option<Function>,
     pub methods: Vec<Function>,
+
+    /// Conversion operators (`operator bool`...), their return type is the target type
+    #[serde(default)]
+    pub conversions: Vec<Function>,
+
+    /// Public static data members
+    #[serde(default)]
+    pub static_fields: Vec<Variable>,
+
+    /// Free functions declared as friends in the record body
+    #[serde(default)]
+    pub friends: Vec<Function>,
+
+    /// Base class whose constructors are inherited with `using Base::Base;`
+    #[serde(default)]
+    pub inherited_ctors: Option<String>,
+
+    /// Other using-declarations (`using Base::value_type;`), the type is the qualified member
+    #[serde(default)]
+    pub using_declarations: Vec<Alias>,
+
+    /// Public methods inherited from documented public bases, grouped by base (qualified name).
+    /// Filled in after parsing, see `render::attach_inherited`
+    #[serde(default)]
+    pub inherited: Vec<(String, Vec<Function>)>,
     pub template: Option<Template>,
     pub nested: Option<Vec<NestedField>>,
+    #[serde(default)]
+    pub bases: Vec<BaseClass>,
+
+    /// Message of a `[[deprecated]]` attribute (empty when it has none)
+    pub deprecated: Option<String>,
+
+    /// Attributes of the declaration, e.g. `nodiscard` for `[[nodiscard]]`
+    #[serde(default)]
+    pub attributes: Vec<String>,
+
+    /// Only declared (`struct File;`), its definition replaces it when parsed too
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub forward_declaration: bool,
+
+    #[serde(default, rename = "final")]
+    pub final_: bool,
+
+    /// Has pure virtual methods (declared or inherited), can't be instantiated
+    #[serde(default, rename = "abstract")]
+    pub abstract_: bool,
+
+    /// Exported from the module interface it is declared in
+    #[serde(default)]
+    pub exported: bool,
+
+    /// The copy constructor and assignment are usable: neither deleted, private nor suppressed
+    #[serde(default = "default_true")]
+    pub copyable: bool,
+
+    /// The move constructor and assignment are usable, or copies are used instead
+    #[serde(default = "default_true")]
+    pub movable: bool,
+
+    /// Where it's declared, for the "Defined in" line
+    pub location: Option<SourceLocation>,
+
+    /// The `#include` giving access to it, only on namespace-level entities
+    #[serde(default)]
+    pub include_path: Option<String>,
+
+    /// Names of the `input.configurations` declaring it, empty when all of them do
+    #[serde(default)]
+    pub available_in: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EnumValue {
     pub name: String,
     pub comment: Option<Comment>,
+
+    /// Computed value of the constant
+    pub value: Option<String>,
+
+    /// Whether the value is assigned in the declaration (`Name = 3`)
+    #[serde(default)]
+    pub explicit: bool,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Enum {
     pub name: String,
     pub comment: Option<Comment>,
     pub namespace: Option<String>,
     pub values: Vec<EnumValue>,
+
+    /// `enum class`/`enum struct`
+    #[serde(default)]
+    pub scoped: bool,
+
+    /// Underlying type, only when spelled out in the declaration
+    pub underlying_type: Option<String>,
+
+    /// Message of a `[[deprecated]]` attribute (empty when it has none)
+    pub deprecated: Option<String>,
+
+    /// Exported from the module interface it is declared in
+    #[serde(default)]
+    pub exported: bool,
+
+    /// Where it's declared, for the "Defined in" line
+    pub location: Option<SourceLocation>,
+
+    /// The `#include` giving access to it, only on namespace-level entities
+    #[serde(default)]
+    pub include_path: Option<String>,
+
+    /// Names of the `input.configurations` declaring it, empty when all of them do
+    #[serde(default)]
+    pub available_in: Vec<String>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FunctionProps {
     #[serde(rename = "const")]
     pub const_: bool,
@@ -63,21 +316,69 @@ pub struct FunctionProps {
     pub static_: bool,
     #[serde(rename = "virtual")]
     pub virtual_: bool,
+    #[serde(rename = "noexcept")]
+    pub noexcept_: bool,
+
+    /// Condition of a `noexcept(expr)` specification, as written in the source
+    pub noexcept_expr: Option<String>,
+
+    /// The whole exception specification as written, `noexcept(false)` or `throw(std::bad_alloc)`
+    #[serde(default)]
+    pub exception_spec: Option<String>,
+
+    /// Declared `= delete`
+    pub deleted: bool,
+    /// Declared `= default`
+    pub defaulted: bool,
+
+    #[serde(rename = "constexpr")]
+    pub constexpr_: bool,
+    #[serde(rename = "consteval")]
+    pub consteval_: bool,
+    #[serde(rename = "inline")]
+    pub inline_: bool,
+    #[serde(rename = "explicit")]
+    pub explicit_: bool,
+
+    /// `&` or `&&` for ref-qualified member functions
+    pub ref_qualifier: Option<String>,
+
+    #[serde(rename = "override")]
+    pub override_: bool,
+    #[serde(rename = "final")]
+    pub final_: bool,
+
+    /// Declared `= 0`
+    pub pure_virtual: bool,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TemplateParameter {
     pub name: String,
     #[serde(rename = "type")]
     pub type_: String,
+
+    /// Parameter pack (`typename... Args`)
+    #[serde(default)]
+    pub is_pack: bool,
+
+    /// Default argument, as written in the source
+    pub default_value: Option<String>,
+
+    /// Its `@tparam` description, filled when rendering
+    #[serde(default)]
+    pub comment: Option<Comment>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Template {
     pub parameters: Vec<TemplateParameter>,
+
+    /// Constraint of the `requires` clause of a constrained template
+    pub requires: Option<String>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Function {
     pub name: String,
     pub return_type: String,
@@ -87,18 +388,152 @@ pub struct Function {
     pub namespace: Option<String>,
     pub template: Option<Template>,
     pub overloads: Option<Vec<Function>>,
+
+    /// Anchor of this overload on the page it shares with the other overloads. Methods and
+    /// constructors always have one, on their record page.
+    pub anchor: Option<String>,
+
+    /// Access of a member function ("public", "protected"), `None` for free functions
+    pub access: Option<String>,
+
+    /// Message of a `[[deprecated]]` attribute (empty when it has none)
+    pub deprecated: Option<String>,
+
+    /// Attributes of the declaration, e.g. `nodiscard` for `[[nodiscard]]`
+    #[serde(default)]
+    pub attributes: Vec<String>,
+
+    /// Declared in an `extern "C"` block or with `extern "C"`
+    #[serde(default)]
+    pub extern_c: bool,
+
+    /// Exported from the module interface it is declared in
+    #[serde(default)]
+    pub exported: bool,
+
+    /// Suffix of a user-defined literal operator, `_kb` for `operator""_kb`
+    #[serde(default)]
+    pub udl_suffix: Option<String>,
+
+    /// Where it's declared, for the "Defined in" line
+    pub location: Option<SourceLocation>,
+
+    /// The `#include` giving access to it, only on namespace-level entities
+    #[serde(default)]
+    pub include_path: Option<String>,
+
+    /// Names of the `input.configurations` declaring it, empty when all of them do
+    #[serde(default)]
+    pub available_in: Vec<String>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Alias {
     pub namespace: Option<String>,
     pub name: String,
     #[serde(rename = "type")]
     pub type_: String,
     pub comment: Option<Comment>,
+
+    /// Message of a `[[deprecated]]` attribute (empty when it has none)
+    pub deprecated: Option<String>,
+
+    /// Where it's declared, for the "Defined in" line
+    pub location: Option<SourceLocation>,
+
+    /// The `#include` giving access to it, only on namespace-level entities
+    #[serde(default)]
+    pub include_path: Option<String>,
+
+    /// Names of the `input.configurations` declaring it, empty when all of them do
+    #[serde(default)]
+    pub available_in: Vec<String>,
+}
+
+/// `namespace fs = std::filesystem;`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NamespaceAlias {
+    pub name: String,
+    pub namespace: Option<String>,
+
+    /// Qualified name of the aliased namespace, `std::filesystem`
+    pub target: String,
+    pub comment: Option<Comment>,
+
+    /// Where it's declared, for the "Defined in" line
+    pub location: Option<SourceLocation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub initializer: Option<String>,
+    #[serde(rename = "constexpr")]
+    pub constexpr_: bool,
+    #[serde(rename = "static")]
+    pub static_: bool,
+    #[serde(rename = "inline")]
+    pub inline_: bool,
+    pub comment: Option<Comment>,
+    pub namespace: Option<String>,
+
+    /// Template parameters of a variable template
+    pub template: Option<Template>,
+
+    /// Message of a `[[deprecated]]` attribute (empty when it has none)
+    #[serde(default)]
+    pub deprecated: Option<String>,
+
+    /// Where it's declared, for the "Defined in" line
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+
+    /// The `#include` giving access to it, only on namespace-level entities
+    #[serde(default)]
+    pub include_path: Option<String>,
+
+    /// Names of the `input.configurations` declaring it, empty when all of them do
+    #[serde(default)]
+    pub available_in: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Concept {
+    pub name: String,
+    pub template: Template,
+
+    /// The constraint expression, as written in the source
+    pub constraint: String,
+    pub comment: Option<Comment>,
+    pub namespace: Option<String>,
+
+    /// Message of a `[[deprecated]]` attribute (empty when it has none)
+    #[serde(default)]
+    pub deprecated: Option<String>,
+
+    /// Where it's declared, for the "Defined in" line
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+
+    /// The `#include` giving access to it
+    #[serde(default)]
+    pub include_path: Option<String>,
+
+    /// Names of the `input.configurations` declaring it, empty when all of them do
+    #[serde(default)]
+    pub available_in: Vec<String>,
+}
+
+/// Position of a declaration, the file is relative to `input.source_root`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Namespace {
     pub name: String,
     pub comment: Option<Comment>,
@@ -108,201 +543,2174 @@ pub struct Namespace {
     pub enums: Vec<Enum>,
     pub aliases: Vec<Alias>,
     pub namespace: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<Variable>,
+    #[serde(default)]
+    pub concepts: Vec<Concept>,
+
+    /// `inline namespace`, only kept when inline namespaces aren't collapsed into their parent
+    #[serde(default, rename = "inline")]
+    pub inline_: bool,
+
+    /// The C++20 modules (`export module mylib;`) declaring content in this namespace
+    #[serde(default)]
+    pub modules: Vec<String>,
+
+    #[serde(default)]
+    pub namespace_aliases: Vec<NamespaceAlias>,
+
+    /// Where it's declared, for the "Defined in" line
+    pub location: Option<SourceLocation>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Output {
     pub root: Namespace,
-    pub index: HashMap<String, String>,
+    pub index: Index,
+
+    /// Qualified names of the entities first declared in each parsed file
+    #[serde(default)]
+    pub files: BTreeMap<String, Vec<String>>,
+
+    /// Output of the headers under `input.include_paths_to_document` that the file includes,
+    /// merged (and emptied) by `merge_outputs`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Output>,
+
+    /// `project.current_version`, for the changelog tools reading the dump
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_version: Option<String>,
+
+    /// Groups defined by `@defgroup` in the parsed files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<Group>,
+
+    /// Comments of the entities of the excluded namespaces, by qualified name, for the
+    /// `@copydoc`s naming implementation details
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hidden_comments: BTreeMap<String, Comment>,
+
+    /// Qualified names of the entities naming each entity in their `@impl`, for the
+    /// "Implemented by" lists
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub implementers: BTreeMap<String, Vec<String>>,
+
+    /// The files shown by `@example`, for the examples index page
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<ExampleFile>,
 }
 
-pub struct Parser<'a> {
-    index: clang::Index<'a>,
+/// An example file of the examples index page
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExampleFile {
+    pub file: String,
+    pub code: String,
+
+    /// Qualified names of the entities showing it
+    pub used_by: Vec<String>,
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(clang: &'a clang::Clang) -> Self {
-        let index = clang::Index::new(clang, false, false);
-        Parser { index }
+/// A topic of the documentation, from `@defgroup name Title`, gathering the entities that
+/// declare themselves in it with `@ingroup name`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Group {
+    pub name: String,
+    pub title: String,
+    pub comment: Option<Comment>,
+
+    /// Qualified names of the members, filled in after parsing by `collect_group_members`
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Qualified name of the entity `name` declared in `scope`, the qualified name of its namespace
+/// or record: empty, or `None`, at global scope
+pub fn qualified_name<'a>(scope: impl Into<Option<&'a str>>, name: &str) -> String {
+    match scope.into() {
+        Some(scope) if !scope.is_empty() => format!("{}::{}", scope, name),
+        _ => name.to_string(),
     }
+}
 
-    fn parse_template(&self, node: clang::Entity) -> Template {
-        Template {
-            parameters: node
-                .get_children()
-                .iter()
-                .filter(|c| {
-                    c.get_kind() == clang::EntityKind::TemplateTypeParameter
-                        || c.get_kind() == clang::EntityKind::NonTypeTemplateParameter
-                        || c.get_kind() == clang::EntityKind::TemplateTemplateParameter
-                })
-                .map(|c| TemplateParameter {
-                    name: c.get_name().unwrap_or_default(),
-                    type_: match c.get_kind() {
-                        clang::EntityKind::TemplateTypeParameter => "typename".to_string(),
-                        clang::EntityKind::NonTypeTemplateParameter => {
-                            c.get_type().unwrap().get_display_name()
-                        }
-                        clang::EntityKind::TemplateTemplateParameter => "template".to_string(),
-                        _ => unreachable!(),
-                    },
-                })
-                .collect(),
+/// Add the groups of a file to those defined before it. A group defined again keeps its first
+/// title, their comments are merged.
+fn merge_groups(into: &mut Vec<Group>, from: Vec<Group>) {
+    for group in from {
+        match into.iter_mut().find(|g| g.name == group.name) {
+            Some(existing) => {
+                if existing.title.is_empty() {
+                    existing.title = group.title;
+                }
+
+                merge_namespace_comment(&mut existing.comment, group.comment);
+            }
+            None => into.push(group),
         }
     }
+}
 
-    fn parse_function(&self, node: clang::Entity) -> Function {
-        let mut ret = Function {
-            name: node.get_name().unwrap(),
-            return_type: node.get_result_type().unwrap().get_display_name(),
-            parameters: Vec::new(),
-            comment: None,
-            props: FunctionProps {
-                const_: node.is_const_method(),
-                static_: node.is_static_method(),
-                virtual_: node.is_virtual_method(),
-            },
-            namespace: None,
-            template: None,
-            overloads: None,
-        };
+/// Replace the names of the `@impl`s by the qualified names of the entities (or methods) they
+/// name, and list the implementers of these entities in `output.implementers`. The names naming
+/// nothing documented are kept as written, with a warning when `strict` is set.
+pub fn collect_implementers(output: &mut Output, strict: bool) {
+    let index = &output.index;
+    let mut implementers = BTreeMap::<String, Vec<String>>::new();
 
-        // Handle function names with quotes, like operator"", so that links don't fuck up
-        ret.name = ret.name.replace("\"", "&quot");
+    // Methods aren't in the index, `@impl Drawable::draw` names one
+    let methods = render::method_anchors(&output.root);
 
-        if let Some(c) = node.get_comment() { ret.comment = Some(comment::parse_comment(c)); }
+    copydoc::for_each_comment(&mut output.root, "", &mut |name, comment| {
+        let scope = name.rsplit_once("::").map_or("", |(scope, _)| scope);
 
-        for c in node
-            .get_children()
-            .iter()
-            .filter(|c| c.get_kind() == clang::EntityKind::ParmDecl)
-        {
-            let field = Field {
-                name: c.get_name().unwrap_or_default(),
-                type_: c.get_type().unwrap().get_display_name(),
-                comment: None,
-                struct_: None,
+        for interface in comment.impl_.iter_mut().flatten() {
+            let found = |candidate: &str| {
+                index.contains_key(candidate) || methods.contains_key(candidate)
             };
-            ret.parameters.push(field);
-        }
 
-        if node.get_kind() == clang::EntityKind::FunctionTemplate {
-            ret.template = Some(self.parse_template(node));
+            match index.resolve_with(interface, scope, found) {
+                Some(qualified) => {
+                    let names = implementers.entry(qualified.clone()).or_default();
+
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+
+                    *interface = qualified;
+                }
+                None if strict => report_warning(&format!(
+                    "“{name}” implements “{interface}” with @impl, which names nothing documented"
+                )),
+                None => {}
+            }
         }
+    });
 
-        ret
+    output.implementers = implementers;
+}
+
+/// Fill in the members of the groups from the `@ingroup`s of the namespace-level entities
+/// (and nested records and enums), warning about the groups that no `@defgroup` defines
+pub fn collect_group_members(output: &mut Output) {
+    fn add(groups: &mut [Group], name: String, comment: &Option<Comment>) {
+        for group_name in comment.iter().flat_map(|c| &c.groups) {
+            match groups.iter_mut().find(|g| g.name == *group_name) {
+                Some(group) => group.members.push(name.clone()),
+                None => report_warning(&format!(
+                    "“{name}” is in the group “{group_name}” that no @defgroup defines"
+                )),
+            }
+        }
     }
 
-    fn parse_record(&self, node: clang::Entity) -> Record {
-        let mut ret = Record {
-            name: node.get_name().unwrap(),
-            fields: Vec::new(),
-            comment: None,
-            kind: match node.get_kind() {
-                clang::EntityKind::StructDecl => "struct".to_string(),
-                clang::EntityKind::ClassDecl => "class".to_string(),
-                clang::EntityKind::ClassTemplate => "class".to_string(),
-                clang::EntityKind::UnionDecl => "union".to_string(),
-                _ => {
-                    println!("Unexpected record child kind: {:?}", node.get_kind());
-                    unreachable!()
+    fn walk_record(record: &Record, scope: &str, groups: &mut [Group]) {
+        let name = qualified_name(scope, &record.name);
+
+        for nested in record.nested.iter().flatten() {
+            match nested {
+                NestedField::Record(r) => walk_record(r, &name, groups),
+                NestedField::Enum(e) => {
+                    add(groups, qualified_name(name.as_str(), &e.name), &e.comment)
                 }
-            },
-            namespace: None,
-            ctor: Vec::new(),
-            methods: Vec::new(),
-            template: None,
-            nested: None,
-        };
+            }
+        }
 
-        if let Some(c) = node.get_comment() { ret.comment = Some(comment::parse_comment(c)); }
+        add(groups, name, &record.comment);
+    }
 
-        if node.get_kind() == clang::EntityKind::ClassTemplate {
-            ret.template = Some(self.parse_template(node));
+    fn walk(ns: &Namespace, scope: &str, groups: &mut [Group]) {
+        for function in &ns.functions {
+            add(groups, qualified_name(scope, &function.name), &function.comment);
+        }
+
+        for record in &ns.records {
+            walk_record(record, scope, groups);
+        }
+
+        for enm in &ns.enums {
+            add(groups, qualified_name(scope, &enm.name), &enm.comment);
+        }
+
+        for alias in &ns.aliases {
+            add(groups, qualified_name(scope, &alias.name), &alias.comment);
+        }
+
+        for variable in &ns.variables {
+            add(groups, qualified_name(scope, &variable.name), &variable.comment);
+        }
+
+        for concept in &ns.concepts {
+            add(groups, qualified_name(scope, &concept.name), &concept.comment);
+        }
+
+        for child in &ns.namespaces {
+            let name = qualified_name(scope, &child.name);
+            walk(child, &name, groups);
+            add(groups, name, &child.comment);
+        }
+    }
+
+    for group in &mut output.groups {
+        group.members.clear();
+    }
+
+    walk(&output.root, "", &mut output.groups);
+
+    for group in &mut output.groups {
+        group.members.sort();
+        group.members.dedup();
+    }
+}
+
+/// The entities an output declares, for `Output::files`
+fn declared_names(index: &Index) -> Vec<String> {
+    let mut declared = index
+        .iter()
+        .filter(|(name, entry)| {
+            entry.kind != "namespace"
+                && entry.kind != "inline namespace"
+                && render::collapse_inline_namespaces(name, index) == **name
+        })
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+
+    declared.sort();
+    declared.dedup();
+    declared
+}
+
+/// Merge the output of one file into the output of the files parsed before it, the same way
+/// declarations of a single file are merged: reopened namespaces are combined, same-named
+/// functions become overloads and the members of redeclared records are appended.
+/// Merging the per-file outputs in file order gives the same result as parsing them in turn.
+///
+/// Each file is only merged once: a header included by several parsed files (or also parsed
+/// itself) is documented the first time it's seen. Deduplicating by file rather than by name
+/// keeps overloads and reopened records declared in different headers.
+pub fn merge_outputs(into: &mut Output, mut from: Output) {
+    let headers = std::mem::take(&mut from.headers);
+
+    if !from.files.keys().any(|file| into.files.contains_key(file)) {
+        merge_file(into, from);
+    }
+
+    for (_, header) in headers {
+        merge_outputs(into, header);
+    }
+}
+
+fn merge_file(into: &mut Output, from: Output) {
+    for (file, declared) in from.files {
+        let declared = declared
+            .into_iter()
+            .filter(|name| !into.index.contains_key(name))
+            .collect();
+
+        into.files.insert(file, declared);
+    }
+
+    into.index.extend(from.index);
+    merge_namespace(&mut into.root, from.root);
+    merge_groups(&mut into.groups, from.groups);
+
+    for (name, comment) in from.hidden_comments {
+        into.hidden_comments.entry(name).or_insert(comment);
+    }
+}
+
+/// Remove the records, enums, aliases, variables and concepts with these qualified names from
+/// an output, along with the headers it carries
+pub fn remove_declarations(output: &mut Output, names: &HashSet<String>) {
+    fn walk(ns: &mut Namespace, scope: &str, names: &HashSet<String>) {
+        ns.records.retain(|r| !names.contains(&qualified_name(scope, &r.name)));
+        ns.enums.retain(|e| !names.contains(&qualified_name(scope, &e.name)));
+        ns.aliases.retain(|a| !names.contains(&qualified_name(scope, &a.name)));
+        ns.namespace_aliases.retain(|a| !names.contains(&qualified_name(scope, &a.name)));
+        ns.variables.retain(|v| !names.contains(&qualified_name(scope, &v.name)));
+        ns.concepts.retain(|c| !names.contains(&qualified_name(scope, &c.name)));
+
+        for child in &mut ns.namespaces {
+            let scope = qualified_name(scope, &child.name);
+            walk(child, &scope, names);
+        }
+    }
+
+    walk(&mut output.root, "", names);
+
+    for declared in output.files.values_mut() {
+        declared.retain(|name| !names.contains(name));
+    }
+
+    for header in output.headers.values_mut() {
+        remove_declarations(header, names);
+    }
+}
+
+/// Whether `a` and `b` declare the same function: same name, parameter types and qualifiers
+fn same_signature(a: &Function, b: &Function) -> bool {
+    a.name == b.name
+        && a.props.const_ == b.props.const_
+        && a.props.ref_qualifier == b.props.ref_qualifier
+        && a.template.is_some() == b.template.is_some()
+        && a.parameters.len() == b.parameters.len()
+        && a.parameters
+            .iter()
+            .zip(&b.parameters)
+            .all(|(a, b)| a.type_ == b.type_)
+}
+
+/// Merge `other`, another declaration of the `existing` function, into it. The documented one
+/// is kept, and when both are their comments are merged.
+fn merge_redeclaration(existing: &mut Function, mut other: Function) {
+    let Some(comment) = other.comment.take() else {
+        return;
+    };
+
+    if let Some(existing_comment) = &mut existing.comment {
+        comment::merge_comments(existing_comment, comment);
+        return;
+    }
+
+    // Default arguments are only written on the first declaration
+    for (parameter, previous) in other.parameters.iter_mut().zip(&existing.parameters) {
+        if parameter.default_value.is_none() {
+            parameter.default_value = previous.default_value.clone();
         }
+    }
+
+    other.comment = Some(comment);
+    other.anchor = existing.anchor.take();
+    other.overloads = existing.overloads.take();
+    *existing = other;
+}
+
+/// Add `function` to the functions of a namespace. Redeclarations of a function already there
+/// are merged into it, other functions of the same name become its overloads. Functions are
+/// keyed on their fully qualified name, so that same-named functions of different namespaces
+/// never end up on the same page. Returns whether `function` is a new function.
+fn add_function(functions: &mut Vec<Function>, function: Function) -> bool {
+    let Some(existing) = functions
+        .iter_mut()
+        .find(|f| f.name == function.name && f.namespace == function.namespace)
+    else {
+        functions.push(function);
+        return true;
+    };
+
+    if same_signature(existing, &function) {
+        merge_redeclaration(existing, function);
+        return false;
+    }
+
+    let overloads = existing.overloads.get_or_insert_with(Vec::new);
+
+    match overloads.iter_mut().find(|o| same_signature(o, &function)) {
+        Some(overload) => merge_redeclaration(overload, function),
+        None => push_overload(existing, function),
+    }
+
+    false
+}
+
+/// Add `function` to the overloads of `existing`. The signatures on their shared page are
+/// numbered from `overload-1`, the first declaration's.
+fn push_overload(existing: &mut Function, mut function: Function) {
+    existing.anchor.get_or_insert_with(|| "overload-1".to_string());
+
+    let overloads = existing.overloads.get_or_insert_with(Vec::new);
+    function.anchor = Some(format!("overload-{}", overloads.len() + 2));
+    overloads.push(function);
+}
+
+/// Add `functions`, with their overloads, to the functions of a namespace or record
+fn merge_functions(into: &mut Vec<Function>, functions: Vec<Function>) {
+    for mut function in functions {
+        let overloads = function.overloads.take().unwrap_or_default();
+
+        for function in std::iter::once(function).chain(overloads) {
+            add_function(into, function);
+        }
+    }
+}
+
+/// Add the entities of `from` that `into` doesn't have yet, by `name`
+fn merge_by_name<T>(into: &mut Vec<T>, from: Vec<T>, name: fn(&T) -> &str) {
+    for entity in from {
+        if !into.iter().any(|e| name(e) == name(&entity)) {
+            into.push(entity);
+        }
+    }
+}
+
+/// Name of a record or enum nested in a record
+fn nested_name(nested: &NestedField) -> &str {
+    match nested {
+        NestedField::Record(r) => &r.name,
+        NestedField::Enum(e) => &e.name,
+    }
+}
+
+/// Merge `record` into the `existing` record of the same name. Forward declarations give way to
+/// the definition, lending it their comment when it has none. Other redeclarations, like
+/// template specializations, add the members `existing` doesn't have.
+fn merge_record(existing: &mut Record, mut record: Record) {
+    if record.forward_declaration {
+        if existing.comment.is_none() {
+            existing.comment = record.comment;
+        }
+    } else if existing.forward_declaration {
+        if record.comment.is_none() {
+            record.comment = existing.comment.take();
+        }
+
+        *existing = record;
+    } else {
+        merge_functions(&mut existing.methods, record.methods);
+        merge_functions(&mut existing.ctor, record.ctor);
+        merge_functions(&mut existing.friends, record.friends);
+        merge_by_name(&mut existing.conversions, record.conversions, |f| f.name.as_str());
+        merge_by_name(&mut existing.fields, record.fields, |f| f.name.as_str());
+        merge_by_name(&mut existing.static_fields, record.static_fields, |v| v.name.as_str());
+        merge_by_name(
+            &mut existing.using_declarations,
+            record.using_declarations,
+            |a| a.name.as_str(),
+        );
+        merge_by_name(&mut existing.bases, record.bases, |b| b.name.as_str());
+
+        if let Some(nested) = record.nested {
+            merge_by_name(existing.nested.get_or_insert_with(Vec::new), nested, nested_name);
+        }
+
+        if existing.dtor.is_none() {
+            existing.dtor = record.dtor;
+        }
+
+        set_member_anchors(existing);
+    }
+}
+
+/// Number the overloads of a member function from its `anchor`: `method-open` when there is a
+/// single one, `method-open-1`, `method-open-2`... otherwise
+fn number_overloads(function: &mut Function, anchor: &str) {
+    match function.overloads.as_mut().filter(|o| !o.is_empty()) {
+        Some(overloads) => {
+            function.anchor = Some(format!("{anchor}-1"));
+
+            for (i, overload) in overloads.iter_mut().enumerate() {
+                overload.anchor = Some(format!("{anchor}-{}", i + 2));
+            }
+        }
+        None => function.anchor = Some(anchor.to_string()),
+    }
+}
+
+/// Give every method and constructor of a record its own anchor on the record page
+fn set_member_anchors(record: &mut Record) {
+    for method in &mut record.methods {
+        let anchor = format!("method-{}", render::sanitize_symbol_filename(&method.name));
+        number_overloads(method, &anchor);
+    }
+
+    for ctor in &mut record.ctor {
+        number_overloads(ctor, "ctor");
+    }
+}
+
+/// Merge the comment of a reopened namespace into the one it already has, if any
+fn merge_namespace_comment(into: &mut Option<Comment>, from: Option<Comment>) {
+    let Some(from) = from else {
+        return;
+    };
+
+    match into {
+        Some(existing) => comment::merge_comments(existing, from),
+        None => *into = Some(from),
+    }
+}
+
+fn merge_namespace(into: &mut Namespace, from: Namespace) {
+    merge_namespace_comment(&mut into.comment, from.comment);
+    merge_functions(&mut into.functions, from.functions);
+
+    for record in from.records {
+        match into.records.iter_mut().find(|r| r.name == record.name) {
+            Some(existing) => merge_record(existing, record),
+            None => into.records.push(record),
+        }
+    }
+
+    merge_by_name(&mut into.variables, from.variables, |v| v.name.as_str());
+    merge_by_name(&mut into.enums, from.enums, |e| e.name.as_str());
+    merge_by_name(&mut into.aliases, from.aliases, |a| a.name.as_str());
+    merge_by_name(&mut into.concepts, from.concepts, |c| c.name.as_str());
+    merge_by_name(&mut into.namespace_aliases, from.namespace_aliases, |a| a.name.as_str());
+
+    for module in from.modules {
+        if !into.modules.contains(&module) {
+            into.modules.push(module);
+        }
+    }
+
+    for namespace in from.namespaces {
+        match into.namespaces.iter_mut().find(|n| n.name == namespace.name) {
+            Some(existing) => merge_namespace(existing, namespace),
+            None => into.namespaces.push(namespace),
+        }
+    }
+}
+
+/// Merge the outputs of a file parsed in each of `input.configurations`, given with the name
+/// of their configuration. What every configuration declares is documented once, as usual, the
+/// rest gets the names of the configurations declaring it in `available_in`.
+pub fn merge_configurations(outputs: Vec<(String, Output)>) -> Output {
+    let count = outputs.len();
+    let mut merged = Output::default();
+
+    for (name, mut output) in outputs {
+        for_each_availability(&mut output, &mut |available_in| {
+            available_in.push(name.clone())
+        });
+        merge_configuration(&mut merged, output);
+    }
+
+    for_each_availability(&mut merged, &mut |available_in| {
+        if available_in.len() == count {
+            available_in.clear();
+        }
+    });
+
+    merged
+}
+
+/// Call `f` on the `available_in` of the namespace-level entities of an output and of the
+/// members of its records
+fn for_each_availability(output: &mut Output, f: &mut dyn FnMut(&mut Vec<String>)) {
+    fn walk_functions(functions: &mut [Function], f: &mut dyn FnMut(&mut Vec<String>)) {
+        for function in functions {
+            f(&mut function.available_in);
+            walk_functions(function.overloads.as_deref_mut().unwrap_or_default(), f);
+        }
+    }
+
+    fn walk_record(record: &mut Record, f: &mut dyn FnMut(&mut Vec<String>)) {
+        f(&mut record.available_in);
+        walk_functions(&mut record.methods, f);
+        walk_functions(&mut record.ctor, f);
+        walk_functions(&mut record.friends, f);
+        walk_functions(&mut record.conversions, f);
+        walk_functions(record.dtor.as_mut().map(std::slice::from_mut).unwrap_or_default(), f);
+
+        record.fields.iter_mut().for_each(|field| f(&mut field.available_in));
+        record.static_fields.iter_mut().for_each(|v| f(&mut v.available_in));
+        record.using_declarations.iter_mut().for_each(|a| f(&mut a.available_in));
+
+        for nested in record.nested.iter_mut().flatten() {
+            match nested {
+                NestedField::Record(r) => walk_record(r, f),
+                NestedField::Enum(e) => f(&mut e.available_in),
+            }
+        }
+    }
+
+    fn walk(ns: &mut Namespace, f: &mut dyn FnMut(&mut Vec<String>)) {
+        walk_functions(&mut ns.functions, f);
+
+        for record in &mut ns.records {
+            walk_record(record, f);
+        }
+
+        ns.enums.iter_mut().for_each(|e| f(&mut e.available_in));
+        ns.aliases.iter_mut().for_each(|a| f(&mut a.available_in));
+        ns.variables.iter_mut().for_each(|v| f(&mut v.available_in));
+        ns.concepts.iter_mut().for_each(|c| f(&mut c.available_in));
+
+        for child in &mut ns.namespaces {
+            walk(child, f);
+        }
+    }
+
+    walk(&mut output.root, f);
+
+    for header in output.headers.values_mut() {
+        for_each_availability(header, f);
+    }
+}
+
+/// Merge the output of a file in one configuration into its output in the others
+fn merge_configuration(into: &mut Output, from: Output) {
+    for (file, declared) in from.files {
+        let names = into.files.entry(file).or_default();
+        names.extend(declared);
+        names.sort();
+        names.dedup();
+    }
+
+    into.index.extend(from.index);
+    merge_configuration_namespace(&mut into.root, from.root);
+    merge_groups(&mut into.groups, from.groups);
+
+    for (name, comment) in from.hidden_comments {
+        into.hidden_comments.entry(name).or_insert(comment);
+    }
+
+    for (path, header) in from.headers {
+        merge_configuration(into.headers.entry(path).or_default(), header);
+    }
+}
+
+/// The declaration of `function` among `functions`, the primary one or one of its overloads
+fn find_declaration<'a>(
+    functions: &'a mut [Function],
+    function: &Function,
+) -> Option<&'a mut Function> {
+    let existing = functions
+        .iter_mut()
+        .find(|f| f.name == function.name && f.namespace == function.namespace)?;
+
+    if same_signature(existing, function) {
+        return Some(existing);
+    }
+
+    existing
+        .overloads
+        .iter_mut()
+        .flatten()
+        .find(|o| same_signature(o, function))
+}
+
+/// Add the functions of a configuration, and their overloads, to those of the others
+fn merge_configuration_functions(into: &mut Vec<Function>, from: Vec<Function>) {
+    for mut function in from {
+        let overloads = function.overloads.take().unwrap_or_default();
+
+        for function in std::iter::once(function).chain(overloads) {
+            match find_declaration(into, &function) {
+                Some(existing) => existing.available_in.extend(function.available_in),
+                None => {
+                    add_function(into, function);
+                }
+            }
+        }
+    }
+}
+
+/// Add the entities of a configuration to those of the others, the ones already there only
+/// get its name
+fn merge_configuration_entities<T>(
+    into: &mut Vec<T>,
+    from: Vec<T>,
+    name: fn(&T) -> &str,
+    available_in: fn(&mut T) -> &mut Vec<String>,
+) {
+    for mut entity in from {
+        match into.iter_mut().find(|e| name(e) == name(&entity)) {
+            Some(existing) => available_in(existing).append(available_in(&mut entity)),
+            None => into.push(entity),
+        }
+    }
+}
+
+/// Merge a record of a configuration into the same record of the others, member by member
+fn merge_configuration_record(existing: &mut Record, record: Record) {
+    existing.available_in.extend(record.available_in);
+
+    merge_configuration_functions(&mut existing.methods, record.methods);
+    merge_configuration_functions(&mut existing.ctor, record.ctor);
+    merge_configuration_functions(&mut existing.friends, record.friends);
+    merge_configuration_functions(&mut existing.conversions, record.conversions);
+
+    if let Some(dtor) = record.dtor {
+        match &mut existing.dtor {
+            Some(existing) => existing.available_in.extend(dtor.available_in),
+            None => existing.dtor = Some(dtor),
+        }
+    }
+
+    merge_configuration_entities(
+        &mut existing.fields,
+        record.fields,
+        |f| f.name.as_str(),
+        |f| &mut f.available_in,
+    );
+    merge_configuration_entities(
+        &mut existing.static_fields,
+        record.static_fields,
+        |v| v.name.as_str(),
+        |v| &mut v.available_in,
+    );
+    merge_configuration_entities(
+        &mut existing.using_declarations,
+        record.using_declarations,
+        |a| a.name.as_str(),
+        |a| &mut a.available_in,
+    );
+
+    for nested in record.nested.into_iter().flatten() {
+        let into = existing.nested.get_or_insert_with(Vec::new);
+
+        let Some(same) = into.iter_mut().find(|n| nested_name(n) == nested_name(&nested)) else {
+            into.push(nested);
+            continue;
+        };
+
+        match (same, nested) {
+            (NestedField::Record(same), NestedField::Record(record)) => {
+                merge_configuration_record(same, record)
+            }
+            (NestedField::Enum(same), NestedField::Enum(mut enum_)) => {
+                same.available_in.append(&mut enum_.available_in)
+            }
+            _ => {}
+        }
+    }
+
+    set_member_anchors(existing);
+}
+
+fn merge_configuration_namespace(into: &mut Namespace, from: Namespace) {
+    if into.comment.is_none() {
+        into.comment = from.comment;
+    }
+
+    merge_configuration_functions(&mut into.functions, from.functions);
+
+    for record in from.records {
+        match into.records.iter_mut().find(|r| r.name == record.name) {
+            Some(existing) => merge_configuration_record(existing, record),
+            None => into.records.push(record),
+        }
+    }
+
+    merge_configuration_entities(
+        &mut into.enums,
+        from.enums,
+        |e| e.name.as_str(),
+        |e| &mut e.available_in,
+    );
+    merge_configuration_entities(
+        &mut into.aliases,
+        from.aliases,
+        |a| a.name.as_str(),
+        |a| &mut a.available_in,
+    );
+    merge_configuration_entities(
+        &mut into.variables,
+        from.variables,
+        |v| v.name.as_str(),
+        |v| &mut v.available_in,
+    );
+    merge_configuration_entities(
+        &mut into.concepts,
+        from.concepts,
+        |c| c.name.as_str(),
+        |c| &mut c.available_in,
+    );
+
+    for alias in from.namespace_aliases {
+        if !into.namespace_aliases.iter().any(|a| a.name == alias.name) {
+            into.namespace_aliases.push(alias);
+        }
+    }
+
+    for module in from.modules {
+        if !into.modules.contains(&module) {
+            into.modules.push(module);
+        }
+    }
+
+    for namespace in from.namespaces {
+        match into.namespaces.iter_mut().find(|n| n.name == namespace.name) {
+            Some(existing) => merge_configuration_namespace(existing, namespace),
+            None => into.namespaces.push(namespace),
+        }
+    }
+}
+
+/// Join tokens back into source-like text, only keeping spaces where they help readability
+pub fn tokens_to_string(tokens: &[clang::token::Token]) -> String {
+    let mut ret = String::new();
+    let mut previous = String::new();
+
+    for token in tokens {
+        let spelling = token.get_spelling();
+
+        let no_space_after = ["(", "[", "{", "::", ".", "->", "~", "!", "<"];
+        let no_space_before = [")", "]", "}", ",", ";", "::", ".", "->", "(", "[", "<", ">"];
+
+        if !ret.is_empty()
+            && !no_space_after.contains(&previous.as_str())
+            && !no_space_before.contains(&spelling.as_str())
+        {
+            ret.push(' ');
+        }
+
+        ret.push_str(&spelling);
+        previous = spelling;
+    }
+
+    ret
+}
+
+/// The type of `using Name = type;` as written, from the tokens after the `=`
+fn aliased_type_as_written(node: &clang::Entity) -> Option<String> {
+    let tokens = node.get_range()?.tokenize();
+    let start = tokens.iter().position(|t| t.get_spelling() == "=")? + 1;
+    let end = tokens
+        .iter()
+        .rposition(|t| t.get_spelling() != ";")
+        .map_or(tokens.len(), |i| i + 1);
+
+    Some(tokens_to_string(tokens.get(start..end)?)).filter(|t| !t.is_empty())
+}
+
+/// The message of a `[[deprecated]]` attribute, empty if it has none. libclang only tells
+/// whether an entity is deprecated, the message is read from the tokens of the attribute.
+fn parse_deprecated(node: &clang::Entity) -> Option<String> {
+    if node.get_availability() != clang::Availability::Deprecated {
+        return None;
+    }
+
+    let message = node
+        .get_children()
+        .iter()
+        .filter(|c| c.get_kind() == clang::EntityKind::UnexposedAttr)
+        .filter_map(|c| c.get_range())
+        .map(|r| r.tokenize())
+        .find(|tokens| tokens.iter().any(|t| t.get_spelling() == "deprecated"))
+        .and_then(|tokens| {
+            tokens
+                .iter()
+                .find(|t| t.get_kind() == clang::token::TokenKind::Literal)
+                .map(|t| t.get_spelling().trim_matches('"').to_string())
+        });
+
+    Some(message.unwrap_or_default())
+}
+
+/// The constraint of the first `requires` clause in `tokens`. A requires-clause is a
+/// conjunction/disjunction of primary expressions, which tells where it ends.
+fn parse_requires_clause(tokens: &[clang::token::Token]) -> Option<String> {
+    let spelling = |i: usize| tokens.get(i).map(|t| t.get_spelling()).unwrap_or_default();
+
+    // Index after the bracket closing the one at `start`
+    let skip_brackets = |start: usize, open: &str, close: &str| {
+        let mut depth = 0;
+        let mut i = start;
+
+        while i < tokens.len() {
+            let s = spelling(i);
+
+            if s == open {
+                depth += 1;
+            } else if s == close {
+                depth -= 1;
+            } else if close == ">" && s == ">>" {
+                depth -= 2;
+            }
+
+            i += 1;
+
+            if depth <= 0 {
+                break;
+            }
+        }
+
+        i
+    };
+
+    let start = tokens.iter().position(|t| t.get_spelling() == "requires")? + 1;
+    let mut end = start;
+
+    loop {
+        if spelling(end) == "(" {
+            end = skip_brackets(end, "(", ")");
+        } else {
+            // A (qualified) name, possibly with template arguments: `std::integral<T>`
+            if spelling(end) == "::" {
+                end += 1;
+            }
+
+            end += 1;
+
+            loop {
+                if spelling(end) == "<" {
+                    end = skip_brackets(end, "<", ">");
+                } else if spelling(end) == "::" {
+                    end += 2;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        if spelling(end) == "&&" || spelling(end) == "||" {
+            end += 1;
+        } else {
+            break;
+        }
+    }
+
+    Some(tokens_to_string(&tokens[start..end.min(tokens.len())]))
+}
+
+/// Whether a (template) parameter declares a pack, i.e. has an ellipsis before its default
+fn is_pack(node: &clang::Entity) -> bool {
+    node.get_range().is_some_and(|r| {
+        r.tokenize()
+            .iter()
+            .take_while(|t| t.get_spelling() != "=")
+            .any(|t| t.get_spelling() == "...")
+    })
+}
+
+/// `file:line` of `node`, for the warnings about it
+fn position(node: &clang::Entity) -> String {
+    let Some(location) = node.get_location().map(|l| l.get_spelling_location()) else {
+        return "<unknown location>".to_string();
+    };
+
+    match location.file {
+        Some(file) => format!("{}:{}", file.get_path().display(), location.line),
+        None => format!("<unknown file>:{}", location.line),
+    }
+}
+
+/// The first child of `node` of the given kind
+fn find_child<'tu>(
+    node: &clang::Entity<'tu>,
+    kind: clang::EntityKind,
+) -> Option<clang::Entity<'tu>> {
+    node.get_children().into_iter().find(|c| c.get_kind() == kind)
+}
+
+fn has_child(node: &clang::Entity, kind: clang::EntityKind) -> bool {
+    node.get_children().iter().any(|c| c.get_kind() == kind)
+}
+
+/// `= delete` functions, clang has no cursor property for them so this looks at the tokens
+fn is_deleted(node: &clang::Entity) -> bool {
+    let Some(range) = node.get_range() else {
+        return false;
+    };
+
+    let spellings = range
+        .tokenize()
+        .iter()
+        .rev()
+        .take(2)
+        .map(|t| t.get_spelling())
+        .collect::<Vec<_>>();
+
+    spellings == ["delete", "="]
+}
+
+/// Whether a constructor or assignment operator of `record` copies (`const T &`) or moves
+/// (`T &&`)
+fn special_member_kind(record: &str, member: &clang::Entity) -> Option<&'static str> {
+    let parameters = member.get_arguments()?;
+
+    if parameters.len() != 1 {
+        return None;
+    }
+
+    let type_ = parameters[0].get_type()?.get_display_name().replace(' ', "");
+    let base = type_.trim_start_matches("const").trim_end_matches('&');
+
+    if base.split('<').next()?.rsplit("::").next() != Some(record) {
+        return None;
+    }
+
+    if type_.ends_with("&&") {
+        Some("move")
+    } else if type_.ends_with('&') {
+        Some("copy")
+    } else {
+        None
+    }
+}
+
+/// Whether a record can be copied and moved. libclang doesn't expose the implicitly declared
+/// special members, so this goes by the declared ones: deleted or non-public ones prevent
+/// copies (or moves), and declaring a move operation suppresses the implicit copy ones.
+fn copy_and_move(node: &clang::Entity, record: &str) -> (bool, bool) {
+    let mut copy: Option<bool> = None;
+    let mut move_: Option<bool> = None;
+
+    for c in node.get_children() {
+        let is_special = match c.get_kind() {
+            clang::EntityKind::Constructor => true,
+            clang::EntityKind::Method => c.get_name().as_deref() == Some("operator="),
+            _ => false,
+        };
+
+        if !is_special {
+            continue;
+        }
+
+        let usable =
+            !is_deleted(&c) && c.get_accessibility() == Some(clang::Accessibility::Public);
+
+        match special_member_kind(record, &c) {
+            Some("copy") => copy = Some(copy.unwrap_or(true) && usable),
+            Some("move") => move_ = Some(move_.unwrap_or(true) && usable),
+            _ => {}
+        }
+    }
+
+    let copyable = copy.unwrap_or(move_.is_none());
+
+    // Without move operations, moving copies
+    let movable = move_.unwrap_or(copyable);
+
+    (copyable, movable)
+}
+
+/// `export` declarations and blocks of module interfaces, libclang doesn't expose them
+fn is_export_decl(node: &clang::Entity) -> bool {
+    node.get_kind() == clang::EntityKind::UnexposedDecl
+        && node
+            .get_range()
+            .and_then(|range| range.tokenize().first().map(|t| t.get_spelling() == "export"))
+            .unwrap_or(false)
+}
+
+/// Whether a declaration is exported, by itself or through an `export` block or namespace
+fn is_exported(node: &clang::Entity) -> bool {
+    let mut parent = node.get_lexical_parent();
+
+    while let Some(p) = parent {
+        if is_export_decl(&p) {
+            return true;
+        }
+
+        if p.get_kind() == clang::EntityKind::TranslationUnit {
+            break;
+        }
+
+        parent = p.get_lexical_parent();
+    }
+
+    false
+}
+
+/// The name of the module a module interface unit declares, `mylib` for `export module mylib;`
+fn module_name(source: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let name = line.trim().strip_prefix("export module ")?;
+        let name = name.split(';').next()?.trim();
+
+        Some(name.to_string()).filter(|n| !n.is_empty())
+    })
+}
+
+/// The `//!` or `/*! */` comment opening the body of the namespace `node`, with its offset in
+/// the file. Clang doesn't attach it to the namespace, but to its first declaration if any.
+fn namespace_header(node: &clang::Entity) -> Option<(String, usize)> {
+    let start = node.get_range()?.get_start().get_file_location();
+    let source = std::fs::read_to_string(start.file?.get_path()).ok()?;
+
+    let offset = start.offset as usize;
+    let body = offset + source.get(offset..)?.find('{')? + 1;
+    let (offset, raw) = comment::header_comment(&source[body..])?;
+
+    Some((raw.to_string(), body + offset))
+}
+
+/// Drop the comment of the declaration `name` of `ns` when it's `header`, the comment opening
+/// the namespace that clang attached to its first declaration
+fn drop_header_comment(ns: &mut Namespace, name: &str, header: &Comment) {
+    let comments = ns
+        .records
+        .iter_mut()
+        .map(|r| (&r.name, &mut r.comment))
+        .chain(ns.functions.iter_mut().map(|f| (&f.name, &mut f.comment)))
+        .chain(ns.namespaces.iter_mut().map(|n| (&n.name, &mut n.comment)))
+        .chain(ns.enums.iter_mut().map(|e| (&e.name, &mut e.comment)))
+        .chain(ns.aliases.iter_mut().map(|a| (&a.name, &mut a.comment)))
+        .chain(ns.variables.iter_mut().map(|v| (&v.name, &mut v.comment)))
+        .chain(ns.concepts.iter_mut().map(|c| (&c.name, &mut c.comment)));
+
+    for (_, comment) in comments.filter(|(n, _)| *n == name) {
+        if comment
+            .as_ref()
+            .is_some_and(|c| c.brief == header.brief && c.description == header.description)
+        {
+            *comment = None;
+        }
+    }
+}
+
+/// The groups `@defgroup` defines in a file
+fn defined_groups(file: &str, aliases: &BTreeMap<String, String>) -> Vec<Group> {
+    let Ok(source) = std::fs::read_to_string(file) else {
+        return Vec::new();
+    };
+
+    comment::defined_groups(&source, aliases)
+        .into_iter()
+        .map(|(name, title, comment)| Group {
+            name,
+            title,
+            comment: Some(comment),
+            members: Vec::new(),
+        })
+        .collect()
+}
+
+/// Split a shell command line into arguments, honouring quotes and backslash escapes
+fn split_command(command: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('\'')) => current.push(c),
+            ('\\', _) => {
+                current.extend(chars.next());
+                in_argument = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => current.push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_argument = true;
+            }
+            (c, None) if c.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            (c, None) => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+
+    if in_argument {
+        arguments.push(current);
+    }
+
+    arguments
+}
+
+/// The compiler arguments of a compilation database entry, without the compiler, the output
+/// and the file itself. Relative include paths are made absolute since they are relative to
+/// the entry's directory, not to the directory cppdoc runs in.
+fn compile_command_arguments(entry: &serde_json::Value, directory: &Path) -> Vec<String> {
+    let arguments = match (entry["arguments"].as_array(), entry["command"].as_str()) {
+        (Some(arguments), _) => arguments
+            .iter()
+            .filter_map(|a| a.as_str().map(str::to_string))
+            .collect(),
+        (None, Some(command)) => split_command(command),
+        (None, None) => Vec::new(),
+    };
+
+    let file = entry["file"].as_str().unwrap_or_default();
+    let absolute = |path: &str| directory.join(path).to_string_lossy().into_owned();
+
+    let mut out = Vec::new();
+    let mut arguments = arguments.into_iter().skip(1);
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-c" => {}
+            "-o" => {
+                arguments.next();
+            }
+            _ if argument == file || directory.join(&argument) == directory.join(file) => {}
+            "-I" | "-isystem" | "-iquote" | "-include" => {
+                out.push(argument);
+                out.extend(arguments.next().map(|path| absolute(&path)));
+            }
+            _ => {
+                let prefix = ["-isystem", "-iquote", "-I"]
+                    .into_iter()
+                    .find(|prefix| argument.starts_with(prefix) && argument.len() > prefix.len());
+
+                match prefix {
+                    Some(prefix) => {
+                        out.push(format!("{}{}", prefix, absolute(&argument[prefix.len()..])))
+                    }
+                    None => out.push(argument),
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// A canonical path relative to the working directory when it's below it, like the input files
+fn relative_to_working_dir(path: PathBuf) -> PathBuf {
+    std::env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or(path)
+}
+
+/// Name of a file's entry in the parse cache, a hash of everything its output depends on: its
+/// path and content, the arguments it's parsed with and the configuration. Headers it includes
+/// aren't part of it. A SHA-256, stable across Rust versions unlike `DefaultHasher`.
+fn cache_key(file: &str, source: &[u8], arguments: &[String], config: &config::Config) -> String {
+    let arguments = serde_json::to_vec(arguments).unwrap_or_default();
+    let settings = serde_json::to_vec(&(&config.input, &config.output, &config.comments))
+        .unwrap_or_default();
+
+    let parts: [&[u8]; 5] = [
+        env!("CARGO_PKG_VERSION").as_bytes(),
+        file.as_bytes(),
+        source,
+        &arguments,
+        &settings,
+    ];
+
+    let mut hasher = Sha256::new();
+
+    // Every part is prefixed with its length, so that they can't run into each other
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+
+    format!("{:x}.json", hasher.finalize())
+}
+
+/// A type without its top-level `const`: `int` for `const int`, `const char *` for
+/// `const char *const`
+fn without_top_level_const(type_: &str) -> String {
+    if let Some(pointer) = type_.strip_suffix("const").filter(|t| t.ends_with('*')) {
+        return pointer.to_string();
+    }
+
+    type_.strip_prefix("const ").unwrap_or(type_).to_string()
+}
+
+/// Clang arguments of `input.std`, `input.target`, `input.sysroot` and `input.isystem`
+fn target_arguments(input: &config::Input) -> Vec<String> {
+    let mut arguments = Vec::new();
+
+    if let Some(ref std) = input.std {
+        arguments.push(format!("-std={std}"));
+    }
+
+    if let Some(ref target) = input.target {
+        arguments.push(format!("--target={target}"));
+    }
+
+    if let Some(ref sysroot) = input.sysroot {
+        arguments.push(format!("--sysroot={sysroot}"));
+    }
+
+    for dir in input.isystem.iter().flatten() {
+        arguments.push("-isystem".to_string());
+        arguments.push(dir.clone());
+    }
+
+    arguments
+}
+
+/// Read a compilation database into the arguments of each file, keyed by canonical path
+fn load_compile_commands(path: &str) -> HashMap<PathBuf, Vec<String>> {
+    let entries = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| {
+            serde_json::from_str::<Vec<serde_json::Value>>(&text).map_err(|e| e.to_string())
+        });
+
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            report_warning(&format!("Error reading compilation database “{path}”: {e}"));
+            return HashMap::new();
+        }
+    };
+
+    // Relative directories are taken relative to the database itself
+    let base = Path::new(path).parent().unwrap_or(Path::new("."));
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let directory = base.join(entry["directory"].as_str().unwrap_or("."));
+            let directory = directory.canonicalize().unwrap_or(directory);
+            let file = directory.join(entry["file"].as_str()?);
+            let file = file.canonicalize().unwrap_or(file);
+
+            Some((file, compile_command_arguments(entry, &directory)))
+        })
+        .collect()
+}
+
+/// Records and enums without a name, `typedef struct { ... } foo_t;` in C. Depending on the
+/// version, libclang gives them no name or one like `(unnamed struct at file.h:3:9)`.
+fn is_unnamed(node: &clang::Entity) -> bool {
+    match node.get_name() {
+        Some(name) => {
+            name.is_empty() || name.starts_with("(anonymous") || name.starts_with("(unnamed")
+        }
+        None => true,
+    }
+}
+
+/// The name of a constructor or destructor without the template arguments libclang spells them
+/// with in class templates, `Options` for `Options<T>`, so that the ones of a specialization
+/// merge with those of the primary template
+fn without_template_arguments(name: &str) -> String {
+    name.split('<').next().unwrap_or(name).to_string()
+}
+
+/// `extern "C"` declarations, in block or single declaration form. Older libclang versions
+/// don't expose linkage specifications and report them as unexposed declarations.
+fn is_linkage_spec(node: &clang::Entity) -> bool {
+    match node.get_kind() {
+        clang::EntityKind::LinkageSpec => true,
+        clang::EntityKind::UnexposedDecl => node
+            .get_range()
+            .map(|range| {
+                let tokens = range.tokenize();
+                tokens.len() > 1
+                    && tokens[0].get_spelling() == "extern"
+                    && tokens[1].get_spelling() == "\"C\""
+            })
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Concept definitions, `template <...> concept Name = ...;`. libclang 18+ reports them as
+/// `CXCursor_ConceptDecl`, which the clang crate doesn't know yet and maps to `NotImplemented`,
+/// older versions as unexposed declarations.
+fn is_concept(node: &clang::Entity) -> bool {
+    matches!(
+        node.get_kind(),
+        clang::EntityKind::NotImplemented | clang::EntityKind::UnexposedDecl
+    ) && concept_name(node).is_some()
+}
+
+/// The name following the `concept` keyword of a concept definition
+fn concept_name(node: &clang::Entity) -> Option<String> {
+    let tokens = node.get_range()?.tokenize();
+    let end = tokens.iter().position(|t| t.get_spelling() == "=")?;
+    let pos = tokens[..end].iter().position(|t| t.get_spelling() == "concept")?;
+
+    tokens[..end].get(pos + 1).map(|t| t.get_spelling())
+}
+
+/// The attributes of a declaration as written in the source (`nodiscard`, `maybe_unused`,
+/// `visibility("default")`...). libclang exposes them as children in the attribute range of
+/// cursor kinds (`CXCursor_FirstAttr` = 400 up to 499).
+fn parse_attributes(node: &clang::Entity) -> Vec<String> {
+    node.get_children()
+        .iter()
+        .filter(|c| (400..500).contains(&(c.get_kind() as i32)))
+        // `override` and `final` are exposed as attributes but are specifiers
+        .filter(|c| {
+            c.get_kind() != clang::EntityKind::OverrideAttr
+                && c.get_kind() != clang::EntityKind::FinalAttr
+        })
+        .filter_map(|c| c.get_range())
+        .map(|r| tokens_to_string(&r.tokenize()))
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+pub struct Parser<'a> {
+    clang: &'a clang::Clang,
+    index: clang::Index<'a>,
+
+    /// Document protected members along with the public ones (`output.include_protected`)
+    include_protected: bool,
+
+    /// Merge inline namespaces into their parent (`output.collapse_inline_namespaces`)
+    collapse_inline_namespaces: bool,
+
+    /// Names or qualified names of the namespaces left out (`input.exclude_namespaces`)
+    exclude_namespaces: Vec<String>,
+
+    /// Document the content of anonymous namespaces (`input.include_anonymous_namespaces`)
+    include_anonymous_namespaces: bool,
+
+    /// Only document what module interfaces export (`output.exported_only`)
+    exported_only: bool,
+
+    /// Module declared by the file being parsed
+    module: Option<String>,
+
+    /// Arguments of the files listed in `input.compile_commands`, read on the first parse
+    compile_commands: Option<HashMap<PathBuf, Vec<String>>>,
+
+    /// Directories whose headers are documented when included (`input.include_paths_to_document`)
+    include_paths: Vec<PathBuf>,
+
+    /// Directory that source locations are relative to (`input.source_root`)
+    source_root: Option<PathBuf>,
+
+    /// Prefix removed from source locations to make include paths (`output.include_prefix_strip`)
+    include_prefix_strip: Option<String>,
+
+    /// Qualified names left out (`output.exclude_symbols`), compiled on the first parse
+    exclude_symbols: Option<Vec<regex::Regex>>,
+
+    /// Directory of the parse cache, `None` to always parse
+    cache_dir: Option<PathBuf>,
+
+    /// Files whose output came from the cache
+    cache_hits: usize,
+
+    /// `--print-clang-args`
+    print_arguments: bool,
+
+    /// Macros of the configuration being parsed (`input.configurations`)
+    defines: Vec<String>,
+
+    /// Commands expanded in every comment (`[comments.aliases]`)
+    aliases: BTreeMap<String, String>,
+
+    /// Error and warning diagnostics of the parsed files
+    errors: usize,
+    warnings: usize,
+}
+
+fn access_name(access: clang::Accessibility) -> String {
+    match access {
+        clang::Accessibility::Private => "private".to_string(),
+        clang::Accessibility::Protected => "protected".to_string(),
+        clang::Accessibility::Public => "public".to_string(),
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(clang: &'a clang::Clang) -> Self {
+        let index = clang::Index::new(clang, false, false);
+        Parser {
+            clang,
+            index,
+            include_protected: false,
+            collapse_inline_namespaces: true,
+            exclude_namespaces: Vec::new(),
+            include_anonymous_namespaces: false,
+            exported_only: false,
+            module: None,
+            compile_commands: None,
+            include_paths: Vec::new(),
+            source_root: None,
+            include_prefix_strip: None,
+            exclude_symbols: None,
+            cache_dir: None,
+            cache_hits: 0,
+            print_arguments: false,
+            defines: Vec::new(),
+            aliases: BTreeMap::new(),
+            errors: 0,
+            warnings: 0,
+        }
+    }
+
+    /// The path of the header declaring `node`, relative to the working directory like the input
+    /// files, when it's under one of the `input.include_paths_to_document`
+    fn documented_header(&self, node: &clang::Entity) -> Option<String> {
+        if self.include_paths.is_empty() || node.is_in_system_header() {
+            return None;
+        }
+
+        let path = node.get_location()?.get_spelling_location().file?.get_path();
+        let path = path.canonicalize().unwrap_or(path);
+
+        if !self.include_paths.iter().any(|dir| path.starts_with(dir)) {
+            return None;
+        }
+
+        Some(relative_to_working_dir(path).to_string_lossy().into_owned())
+    }
+
+    /// Where `node` is declared, with the file relative to `input.source_root`. The definition is
+    /// preferred over forward declarations.
+    fn location(&self, node: &clang::Entity) -> Option<SourceLocation> {
+        let node = node.get_definition().unwrap_or(*node);
+        let location = node.get_location()?.get_spelling_location();
+        let path = location.file?.get_path();
+        let path = relative_to_working_dir(path.canonicalize().unwrap_or(path));
+
+        let path = match &self.source_root {
+            Some(root) => path.strip_prefix(root).map(Path::to_path_buf).unwrap_or(path),
+            None => path,
+        };
+
+        Some(SourceLocation {
+            file: path.to_string_lossy().into_owned(),
+            line: location.line,
+        })
+    }
+
+    /// The path to `#include` for `node`: its location without `output.include_prefix_strip`
+    fn include_path(&self, node: &clang::Entity) -> Option<String> {
+        let file = self.location(node)?.file;
+
+        match &self.include_prefix_strip {
+            Some(prefix) => Some(file.strip_prefix(prefix.as_str()).unwrap_or(&file).to_string()),
+            None => Some(file),
+        }
+    }
+
+    /// Number of error and warning diagnostics of the files parsed so far
+    pub fn diagnostic_counts(&self) -> (usize, usize) {
+        (self.errors, self.warnings)
+    }
+
+    /// Store the output of each file in `dir`, and reuse it while the file, its arguments and
+    /// the configuration are unchanged
+    pub fn set_cache_dir(&mut self, dir: Option<PathBuf>) {
+        self.cache_dir = dir;
+    }
+
+    /// Define these macros, `NAME` or `NAME=value`, in the files parsed next
+    pub fn set_defines(&mut self, defines: Vec<String>) {
+        self.defines = defines;
+    }
+
+    /// Print the arguments of each file to stderr before parsing it
+    pub fn set_print_arguments(&mut self, print: bool) {
+        self.print_arguments = print;
+    }
+
+    /// Number of files whose output was read from the cache
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    fn parse_comment(&self, raw: String) -> Comment {
+        comment::parse_comment(raw, &self.aliases)
+    }
+
+    /// Qualified name of a declaration as documented, `a::Record::method`: anonymous namespaces,
+    /// `extern "C"` blocks and (when collapsed) inline namespaces aren't part of it
+    fn qualified_name(&self, node: &clang::Entity) -> Option<String> {
+        let mut parts = vec![node.get_name().filter(|n| !n.is_empty())?];
+        let mut parent = node.get_semantic_parent();
+
+        while let Some(p) = parent {
+            if p.get_kind() == clang::EntityKind::TranslationUnit {
+                break;
+            }
+
+            let skipped = p.is_anonymous()
+                || (p.is_inline_namespace() && self.collapse_inline_namespaces);
+
+            if let Some(name) = p.get_name().filter(|n| !n.is_empty() && !skipped) {
+                parts.push(name);
+            }
+
+            parent = p.get_semantic_parent();
+        }
+
+        parts.reverse();
+        Some(parts.join("::"))
+    }
+
+    /// Declarations matching one of `output.exclude_symbols`
+    fn is_excluded_symbol(&self, node: &clang::Entity) -> bool {
+        let patterns = self.exclude_symbols.as_deref().unwrap_or_default();
+
+        if patterns.is_empty() {
+            return false;
+        }
+
+        self.qualified_name(node)
+            .is_some_and(|name| patterns.iter().any(|r| r.is_match(&name)))
+    }
+
+    /// Entities of module interfaces that aren't exported are hidden with `output.exported_only`
+    fn is_hidden(&self, node: &clang::Entity) -> bool {
+        self.exported_only && self.module.is_some() && !is_exported(node)
+    }
+
+    fn is_excluded_namespace(&self, name: &str, qualified_name: &str) -> bool {
+        self.exclude_namespaces
+            .iter()
+            .any(|excluded| excluded == name || excluded == qualified_name)
+    }
+
+    /// Whether a record member is part of the documented interface
+    fn is_documented_member(&self, node: &clang::Entity) -> bool {
+        match node.get_accessibility() {
+            Some(clang::Accessibility::Public) => true,
+            Some(clang::Accessibility::Protected) => self.include_protected,
+            Some(clang::Accessibility::Private) => false,
+            // C has no access control, every member of a struct is part of the interface
+            None => true,
+        }
+    }
+
+    fn parse_template(&self, node: clang::Entity) -> Template {
+        Template {
+            parameters: node
+                .get_children()
+                .iter()
+                .filter(|c| {
+                    c.get_kind() == clang::EntityKind::TemplateTypeParameter
+                        || c.get_kind() == clang::EntityKind::NonTypeTemplateParameter
+                        || c.get_kind() == clang::EntityKind::TemplateTemplateParameter
+                })
+                .map(|c| TemplateParameter {
+                    name: c.get_name().unwrap_or_default(),
+                    type_: match c.get_kind() {
+                        clang::EntityKind::TemplateTypeParameter => "typename".to_string(),
+                        clang::EntityKind::NonTypeTemplateParameter => c
+                            .get_type()
+                            .map(|t| t.get_display_name())
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        _ => "template".to_string(),
+                    },
+                    is_pack: is_pack(c),
+                    default_value: Self::parse_default_value(c),
+                    comment: None,
+                })
+                .collect(),
+            requires: node.get_range().and_then(|r| {
+                let tokens = r.tokenize();
+
+                // Don't look into the body, members may have their own `requires` clauses
+                let end = tokens
+                    .iter()
+                    .position(|t| t.get_spelling() == "{" || t.get_spelling() == ";")
+                    .unwrap_or(tokens.len());
+
+                parse_requires_clause(&tokens[..end])
+            }),
+        }
+    }
+
+    fn parse_concept(&self, node: clang::Entity) -> Concept {
+        let tokens = node
+            .get_range()
+            .map(|r| r.tokenize())
+            .unwrap_or_default();
+
+        let constraint = tokens
+            .iter()
+            .position(|t| t.get_spelling() == "=")
+            .map(|pos| tokens_to_string(&tokens[pos + 1..]))
+            .unwrap_or_default();
+
+        let mut template = self.parse_template(node);
+        template.requires = None;
+
+        Concept {
+            name: node
+                .get_name()
+                .or_else(|| concept_name(&node))
+                .unwrap_or_default(),
+            template,
+            constraint: constraint.trim_end_matches(';').trim_end().to_string(),
+            comment: node.get_comment().map(|c| self.parse_comment(c)),
+            namespace: None,
+            deprecated: parse_deprecated(&node),
+            location: self.location(&node),
+            include_path: self.include_path(&node),
+            available_in: Vec::new(),
+        }
+    }
+
+    /// The tokens following the `=` of a (template) parameter declaration, if it has a default
+    /// argument
+    fn parse_default_value(node: &clang::Entity) -> Option<String> {
+        let tokens = node.get_range()?.tokenize();
+
+        // Only an `=` outside of parentheses/brackets starts the default, not one in the type
+        let mut depth = 0;
+        let pos = tokens.iter().position(|t| {
+            match t.get_spelling().as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                "=" => return depth == 0,
+                _ => {}
+            }
+
+            false
+        })?;
+
+        Some(tokens_to_string(&tokens[pos + 1..])).filter(|v| !v.is_empty())
+    }
+
+    /// The default member initializer of a field: `3` for `int retries = 3`, and the braces
+    /// included for `std::string host{"localhost"}`
+    fn parse_field_initializer(node: &clang::Entity) -> Option<String> {
+        let tokens = node.get_range()?.tokenize();
+        let name = node.get_name()?;
+
+        let after_name = tokens.iter().position(|t| t.get_spelling() == name)? + 1;
+        let pos = after_name
+            + tokens[after_name..]
+                .iter()
+                .position(|t| t.get_spelling() == "=" || t.get_spelling() == "{")?;
+
+        let initializer = if tokens[pos].get_spelling() == "=" {
+            &tokens[pos + 1..]
+        } else {
+            &tokens[pos..]
+        };
+
+        Some(tokens_to_string(initializer)).filter(|v| !v.is_empty())
+    }
+
+    /// The exception specification starting with `keyword` (`noexcept` or `throw`) as written,
+    /// looked for outside of the parameters and before the body
+    fn parse_exception_spec(node: &clang::Entity, keyword: &str) -> Option<String> {
+        let tokens = node.get_range()?.tokenize();
+        let mut depth = 0;
+
+        let start = tokens.iter().position(|t| {
+            match t.get_spelling().as_str() {
+                "(" | "[" => depth += 1,
+                ")" | "]" => depth -= 1,
+                spelling => return depth == 0 && (spelling == keyword || spelling == "{"),
+            }
+
+            false
+        })?;
+
+        if tokens[start].get_spelling() == "{" {
+            return None;
+        }
+
+        let mut end = start + 1;
+
+        if tokens.get(end).is_some_and(|t| t.get_spelling() == "(") {
+            let mut depth = 0;
+
+            for token in &tokens[start + 1..] {
+                end += 1;
+
+                match token.get_spelling().as_str() {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ => {}
+                }
+
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+
+        Some(tokens_to_string(&tokens[start..end]))
+    }
+
+    /// The condition of a `noexcept(expr)` specification, clang doesn't expose it directly
+    fn parse_noexcept_expr(node: &clang::Entity) -> Option<String> {
+        let tokens = node.get_range()?.tokenize();
+        let start = tokens.iter().position(|t| t.get_spelling() == "noexcept")? + 1;
+
+        if tokens.get(start)?.get_spelling() != "(" {
+            return None;
+        }
+
+        let mut depth = 0;
+
+        for (i, token) in tokens[start..].iter().enumerate() {
+            match token.get_spelling().as_str() {
+                "(" => depth += 1,
+                ")" => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return Some(tokens_to_string(&tokens[start + 1..start + i]));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn parse_function(&self, node: clang::Entity) -> Function {
+        let mut ret = Function {
+            name: node.get_name().unwrap_or_else(|| {
+                report_warning(&format!("{}: function without a name", position(&node)));
+                "(unnamed function)".to_string()
+            }),
+            return_type: node
+                .get_result_type()
+                .map(|t| t.get_display_name())
+                .unwrap_or_else(|| {
+                    report_warning(&format!(
+                        "{}: unknown return type of “{}”",
+                        position(&node),
+                        node.get_display_name().unwrap_or_default()
+                    ));
+                    "unknown".to_string()
+                }),
+            parameters: Vec::new(),
+            comment: None,
+            props: FunctionProps {
+                const_: node.is_const_method(),
+                static_: node.is_static_method(),
+                virtual_: node.is_virtual_method(),
+                noexcept_: false,
+                noexcept_expr: None,
+                exception_spec: None,
+                deleted: false,
+                defaulted: node.is_defaulted(),
+                constexpr_: false,
+                consteval_: false,
+                inline_: false,
+                explicit_: false,
+                ref_qualifier: node
+                    .get_type()
+                    .and_then(|t| t.get_ref_qualifier())
+                    .map(|q| match q {
+                        clang::RefQualifier::LValue => "&".to_string(),
+                        clang::RefQualifier::RValue => "&&".to_string(),
+                    }),
+                override_: has_child(&node, clang::EntityKind::OverrideAttr),
+                final_: has_child(&node, clang::EntityKind::FinalAttr),
+                pure_virtual: node.is_pure_virtual_method(),
+            },
+            namespace: None,
+            template: None,
+            overloads: None,
+            anchor: None,
+            access: node.get_accessibility().map(access_name),
+            deprecated: parse_deprecated(&node),
+            attributes: parse_attributes(&node),
+            extern_c: node
+                .get_lexical_parent()
+                .map(|p| is_linkage_spec(&p))
+                .unwrap_or(false),
+            exported: is_exported(&node),
+            udl_suffix: None,
+            location: self.location(&node),
+            include_path: None,
+            available_in: Vec::new(),
+        };
+
+        // Literal operators are spelled `operator""_kb` (or `operator"" _kb`) by clang
+        if let Some(suffix) = ret.name.strip_prefix("operator\"\"") {
+            ret.udl_suffix = Some(suffix.trim().to_string());
+        }
+
+        match node.get_exception_specification() {
+            Some(clang::ExceptionSpecification::BasicNoexcept)
+            | Some(clang::ExceptionSpecification::DynamicNone)
+            | Some(clang::ExceptionSpecification::NoThrow) => ret.props.noexcept_ = true,
+            Some(clang::ExceptionSpecification::ComputedNoexcept) => {
+                ret.props.noexcept_ = true;
+                ret.props.noexcept_expr = Self::parse_noexcept_expr(&node);
+            }
+            _ => {}
+        }
+
+        ret.props.exception_spec = match node.get_exception_specification() {
+            Some(clang::ExceptionSpecification::Dynamic)
+            | Some(clang::ExceptionSpecification::DynamicNone) => {
+                Self::parse_exception_spec(&node, "throw")
+            }
+            Some(clang::ExceptionSpecification::BasicNoexcept)
+            | Some(clang::ExceptionSpecification::ComputedNoexcept) => {
+                Self::parse_exception_spec(&node, "noexcept")
+            }
+            _ => None,
+        };
+
+        // clang has no cursor property for the specifiers, so look at the tokens: they come
+        // before the parameter list
+        if let Some(range) = node.get_range() {
+            let tokens = range.tokenize();
+
+            for token in tokens.iter().take_while(|t| t.get_spelling() != "(") {
+                match token.get_spelling().as_str() {
+                    "constexpr" => ret.props.constexpr_ = true,
+                    "consteval" => ret.props.consteval_ = true,
+                    "inline" => ret.props.inline_ = true,
+                    "explicit" => ret.props.explicit_ = true,
+                    _ => {}
+                }
+            }
+        }
+
+        ret.props.deleted = is_deleted(&node);
+
+        if let Some(c) = node.get_comment() { ret.comment = Some(self.parse_comment(c)); }
+
+        for c in node
+            .get_children()
+            .iter()
+            .filter(|c| c.get_kind() == clang::EntityKind::ParmDecl)
+        {
+            let mut field = Field {
+                name: c.get_name().unwrap_or_default(),
+                type_: c
+                    .get_type()
+                    .map(|t| t.get_display_name())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                comment: None,
+                struct_: None,
+                default_value: Self::parse_default_value(c),
+                bit_width: None,
+                access: None,
+                available_in: Vec::new(),
+            };
+
+            // `Args &&... args`, the pack expansion isn't always part of the type's name
+            if is_pack(c) && !field.type_.ends_with("...") {
+                field.type_.push_str("...");
+            }
+
+            ret.parameters.push(field);
+        }
+
+        if node.get_kind() == clang::EntityKind::FunctionTemplate {
+            ret.template = Some(self.parse_template(node));
+        }
+
+        ret
+    }
+
+    /// Parse a record declared in `scope`, the qualified name of the enclosing namespace or record
+    fn parse_record(&self, node: clang::Entity, scope: &str) -> Record {
+        self.parse_record_as(node, scope, node.get_name().unwrap_or_default())
+    }
+
+    /// `parse_record` for a record documented under another name than its own, like the unnamed
+    /// struct of `typedef struct { ... } foo_t;`, its members being qualified with `name`
+    fn parse_record_as(&self, node: clang::Entity, scope: &str, name: String) -> Record {
+        let mut ret = Record {
+            name,
+            fields: Vec::new(),
+            comment: None,
+            kind: match node.get_kind() {
+                clang::EntityKind::StructDecl => "struct".to_string(),
+                clang::EntityKind::ClassDecl => "class".to_string(),
+                clang::EntityKind::ClassTemplate => "class".to_string(),
+                clang::EntityKind::UnionDecl => "union".to_string(),
+                // Partial specializations and the like, documented as the class they refine
+                _ => "class".to_string(),
+            },
+            namespace: None,
+            ctor: Vec::new(),
+            dtor: None,
+            methods: Vec::new(),
+            conversions: Vec::new(),
+            static_fields: Vec::new(),
+            friends: Vec::new(),
+            inherited_ctors: None,
+            using_declarations: Vec::new(),
+            inherited: Vec::new(),
+            deprecated: parse_deprecated(&node),
+            attributes: parse_attributes(&node),
+            forward_declaration: !node.is_definition(),
+            final_: has_child(&node, clang::EntityKind::FinalAttr),
+            abstract_: node.is_abstract_record(),
+            exported: is_exported(&node),
+            copyable: true,
+            movable: true,
+            template: None,
+            nested: None,
+            bases: Vec::new(),
+            location: self.location(&node),
+            include_path: None,
+            available_in: Vec::new(),
+        };
+
+        // Members are qualified with the full name of the record, for the links of their types
+        let qualified = qualified_name(scope, &ret.name);
+
+        if let Some(c) = node.get_comment() { ret.comment = Some(self.parse_comment(c)); }
+
+        if node.get_kind() == clang::EntityKind::ClassTemplate {
+            ret.template = Some(self.parse_template(node));
+        }
+
+        for c in node.get_children().iter() {
+            if c.get_kind() != clang::EntityKind::BaseSpecifier && self.is_excluded_symbol(c) {
+                continue;
+            }
 
-        for c in node.get_children().iter() {
             match c.get_kind() {
-                clang::EntityKind::FieldDecl => if let Some(clang::Accessibility::Public) = c.get_accessibility() {
+                clang::EntityKind::BaseSpecifier => {
+                    ret.bases.push(BaseClass {
+                        name: c
+                            .get_type()
+                            .map(|t| t.get_display_name())
+                            .unwrap_or_else(|| c.get_display_name().unwrap_or_default()),
+                        access: c
+                            .get_accessibility()
+                            .map(access_name)
+                            .unwrap_or_else(|| "public".to_string()),
+                        virtual_: c.is_virtual_base(),
+                    });
+                }
+
+                clang::EntityKind::FieldDecl if self.is_documented_member(c) => {
                     let mut field = Field {
                         name: c.get_name().unwrap_or_default(),
-                        type_: c.get_type().unwrap().get_display_name(),
-                        comment: c.get_comment().map(comment::parse_comment),
+                        type_: c
+                            .get_type()
+                            .map(|t| t.get_display_name())
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        comment: c.get_comment().map(|c| self.parse_comment(c)),
                         struct_: None,
+                        default_value: Self::parse_field_initializer(c),
+                        bit_width: c.get_bit_field_width().map(|w| w as u32),
+                        access: c.get_accessibility().map(access_name),
+                        available_in: Vec::new(),
                     };
 
                     // NOTE: We assume that unnamed struct types always have "(unnamed struct" in their
                     if field.type_.contains("(unnamed struct") {
-                        let ret_struct = self.parse_record(
-                            *c.get_children()
-                                .iter()
-                                .find(|c| c.get_kind() == clang::EntityKind::StructDecl)
-                                .unwrap(),
-                        );
+                        if let Some(declaration) = find_child(c, clang::EntityKind::StructDecl) {
+                            let ret_struct = self.parse_record(declaration, &qualified);
 
-                        field.type_ = "struct".to_string();
-                        field.struct_ = Some(NestedField::Record(ret_struct));
+                            field.type_ = "struct".to_string();
+                            field.struct_ = Some(NestedField::Record(ret_struct));
+                        }
                     }
 
                     if field.type_.contains("(unnamed union") {
-                        let ret_struct = self.parse_record(
-                            *c.get_children()
-                                .iter()
-                                .find(|c| c.get_kind() == clang::EntityKind::UnionDecl)
-                                .unwrap(),
-                        );
+                        if let Some(declaration) = find_child(c, clang::EntityKind::UnionDecl) {
+                            let ret_struct = self.parse_record(declaration, &qualified);
 
-                        field.type_ = "union".to_string();
-                        field.struct_ = Some(NestedField::Record(ret_struct));
+                            field.type_ = "union".to_string();
+                            field.struct_ = Some(NestedField::Record(ret_struct));
+                        }
                     }
 
                     if field.type_.contains("(unnamed enum") {
-                        let ret_enum = self.parse_enum(
-                            *c.get_children()
-                                .iter()
-                                .find(|c| c.get_kind() == clang::EntityKind::EnumDecl)
-                                .unwrap(),
-                        );
+                        if let Some(declaration) = find_child(c, clang::EntityKind::EnumDecl) {
+                            let ret_enum = self.parse_enum(declaration);
 
-                        field.type_ = "enum".to_string();
-                        field.struct_ = Some(NestedField::Enum(ret_enum));
+                            field.type_ = "enum".to_string();
+                            field.struct_ = Some(NestedField::Enum(ret_enum));
+                        }
                     }
 
                     ret.fields.push(field);
-                },
+                }
+
+                clang::EntityKind::VarDecl if self.is_documented_member(c) => {
+                    let mut variable = self.parse_variable(*c);
+                    variable.static_ = true;
+                    variable.namespace = Some(qualified.clone());
+
+                    ret.static_fields.push(variable);
+                }
+
+                clang::EntityKind::UsingDeclaration if self.is_documented_member(c) => {
+                    let tokens = c.get_range().map(|r| r.tokenize()).unwrap_or_default();
+                    let target = tokens
+                        .iter()
+                        .skip_while(|t| t.get_spelling() == "using" || t.get_spelling() == "typename")
+                        .take_while(|t| t.get_spelling() != ";")
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    let target = tokens_to_string(&target);
+
+                    let Some((base, member)) = target.rsplit_once("::") else {
+                        continue;
+                    };
+
+                    // `using Base::Base;`, or `using Base<T>::Base;` for templates
+                    let base_name = base.split('<').next().unwrap_or_default();
+                    let base_name = base_name.rsplit("::").next().unwrap_or_default();
 
-                clang::EntityKind::Constructor => {
+                    if member == base_name {
+                        ret.inherited_ctors = Some(base.to_string());
+                    } else {
+                        ret.using_declarations.push(Alias {
+                            namespace: None,
+                            name: member.to_string(),
+                            type_: target.clone(),
+                            comment: c.get_comment().map(|c| self.parse_comment(c)),
+                            deprecated: parse_deprecated(c),
+                            location: self.location(c),
+                            include_path: None,
+                            available_in: Vec::new(),
+                        });
+                    }
+                }
+
+                clang::EntityKind::FriendDecl => {
+                    // Friend classes aren't documented, only friend functions
+                    let declaration = c.get_children().into_iter().find(|f| {
+                        f.get_kind() == clang::EntityKind::FunctionDecl
+                            || f.get_kind() == clang::EntityKind::FunctionTemplate
+                    });
+
+                    if let Some(declaration) = declaration {
+                        let mut function = self.parse_function(declaration);
+                        function.namespace = Some(qualified.clone());
+                        function.access = None;
+
+                        if function.comment.is_none() {
+                            function.comment = c.get_comment().map(|c| self.parse_comment(c));
+                        }
+
+                        if let Some(existing) =
+                            ret.friends.iter_mut().find(|f| f.name == function.name)
+                        {
+                            push_overload(existing, function);
+                        } else {
+                            ret.friends.push(function);
+                        }
+                    }
+                }
+
+                clang::EntityKind::Constructor if self.is_documented_member(c) => {
                     let mut function = self.parse_function(*c);
+                    function.name = without_template_arguments(&function.name);
                     function.return_type = "".to_string();
+                    function.namespace = Some(qualified.clone());
 
-                    ret.ctor.push(function);
+                    add_function(&mut ret.ctor, function);
                 }
 
-                clang::EntityKind::Method | clang::EntityKind::FunctionTemplate => {
-                    if let Some(clang::Accessibility::Public) = c.get_accessibility() {
-                        let mut function = self.parse_function(*c);
-                        function.namespace = Some(ret.name.clone());
+                clang::EntityKind::Destructor if self.is_documented_member(c) => {
+                    let mut function = self.parse_function(*c);
+                    function.name = without_template_arguments(&function.name);
+                    function.return_type = "".to_string();
+                    function.namespace = Some(qualified.clone());
 
-                        ret.methods.push(function);
-                    }
+                    ret.dtor = Some(function);
+                }
+
+                clang::EntityKind::ConversionFunction if self.is_documented_member(c) => {
+                    let mut function = self.parse_function(*c);
+                    function.namespace = Some(qualified.clone());
+
+                    ret.conversions.push(function);
+                }
+
+                clang::EntityKind::Method | clang::EntityKind::FunctionTemplate
+                    if self.is_documented_member(c) =>
+                {
+                    let mut function = self.parse_function(*c);
+                    function.namespace = Some(qualified.clone());
+
+                    add_function(&mut ret.methods, function);
                 }
 
                 clang::EntityKind::StructDecl
                 | clang::EntityKind::ClassDecl
                 | clang::EntityKind::UnionDecl
                 | clang::EntityKind::ClassTemplate => {
-                    let mut record = self.parse_record(*c);
+                    let mut record = self.parse_record(*c, &qualified);
 
                     if !record.name.starts_with("(anonymous")
                         && !record.name.starts_with("(unnamed")
                     {
-                        record.namespace = Some(ret.name.clone());
+                        record.namespace = Some(qualified.clone());
 
-                        if ret.nested.is_none() {
-                            ret.nested = Some(Vec::new());
-                        } else if let Some(nested) = ret.nested.as_mut() {
-                            nested.push(NestedField::Record(record));
-                        }
+                        ret.nested
+                            .get_or_insert_with(Vec::new)
+                            .push(NestedField::Record(record));
                     }
                 }
 
@@ -311,15 +2719,11 @@ impl<'a> Parser<'a> {
 
                     if !enum_.name.starts_with("(anonymous") && !enum_.name.starts_with("(unnamed")
                     {
-                        enum_.namespace = Some(ret.name.clone());
-
-                        if ret.nested.is_none() {
-                            ret.nested = Some(Vec::new());
-                        }
+                        enum_.namespace = Some(qualified.clone());
 
-                        if let Some(nested) = ret.nested.as_mut() {
-                            nested.push(NestedField::Enum(enum_));
-                        }
+                        ret.nested
+                            .get_or_insert_with(Vec::new)
+                            .push(NestedField::Enum(enum_));
                     }
                 }
 
@@ -327,24 +2731,155 @@ impl<'a> Parser<'a> {
             }
         }
 
+        // clang may not tell for class templates, a pure virtual method is enough to know
+        ret.abstract_ |= ret
+            .methods
+            .iter()
+            .flat_map(|m| std::iter::once(m).chain(m.overloads.iter().flatten()))
+            .any(|m| m.props.pure_virtual);
+
+        set_member_anchors(&mut ret);
+
+        (ret.copyable, ret.movable) = copy_and_move(&node, &ret.name);
+
         ret
     }
 
+    fn parse_variable(&self, node: clang::Entity) -> Variable {
+        let name = node.get_name().unwrap_or_default();
+        let tokens = node
+            .get_range()
+            .map(|r| r.tokenize())
+            .unwrap_or_default();
+
+        // Specifiers come before the name, the initializer (if any) after it
+        let name_pos = tokens.iter().position(|t| t.get_spelling() == name);
+        let specifiers = tokens[..name_pos.unwrap_or(0)]
+            .iter()
+            .map(|t| t.get_spelling())
+            .collect::<Vec<_>>();
+
+        let initializer = name_pos
+            .map(|pos| &tokens[pos + 1..])
+            .map(|rest| match rest.first() {
+                Some(t) if t.get_spelling() == "=" => &rest[1..],
+                _ => rest,
+            })
+            .filter(|rest| !rest.is_empty())
+            .map(tokens_to_string);
+
+        let type_ = node
+            .get_type()
+            .map(|t| t.get_display_name())
+            .unwrap_or_else(|| {
+                // Variable templates have no type, it's between the template header and the name
+                let mut start = 0;
+
+                if tokens.first().is_some_and(|t| t.get_spelling() == "template") {
+                    let mut depth = 0;
+
+                    for (i, token) in tokens.iter().enumerate().skip(1) {
+                        match token.get_spelling().as_str() {
+                            "<" => depth += 1,
+                            ">" => depth -= 1,
+                            ">>" => depth -= 2,
+                            _ => {}
+                        }
+
+                        if depth <= 0 {
+                            start = i + 1;
+                            break;
+                        }
+                    }
+                }
+
+                let type_tokens = tokens[start.min(name_pos.unwrap_or(0))..name_pos.unwrap_or(0)]
+                    .iter()
+                    .filter(|t| {
+                        !["constexpr", "inline", "static", "extern"]
+                            .contains(&t.get_spelling().as_str())
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                tokens_to_string(&type_tokens)
+            });
+
+        let constexpr_ = specifiers.iter().any(|s| s == "constexpr");
+
+        // The `const` implied by `constexpr` is shown once, as the keyword
+        let type_ = if constexpr_ {
+            without_top_level_const(&type_)
+        } else {
+            type_
+        };
+
+        Variable {
+            name,
+            type_,
+            initializer,
+            constexpr_,
+            static_: node.get_storage_class() == Some(clang::StorageClass::Static),
+            inline_: specifiers.iter().any(|s| s == "inline"),
+            comment: node.get_comment().map(|c| self.parse_comment(c)),
+            namespace: None,
+            template: None,
+            deprecated: parse_deprecated(&node),
+            location: self.location(&node),
+            include_path: None,
+            available_in: Vec::new(),
+        }
+    }
+
     fn parse_enum(&self, node: clang::Entity) -> Enum {
         let mut ret = Enum {
-            name: node.get_name().unwrap(),
+            name: node.get_name().unwrap_or_default(),
             comment: None,
             namespace: None,
             values: Vec::new(),
+            scoped: node.is_scoped(),
+            underlying_type: None,
+            deprecated: parse_deprecated(&node),
+            exported: is_exported(&node),
+            location: self.location(&node),
+            include_path: None,
+            available_in: Vec::new(),
         };
 
-        if let Some(c) = node.get_comment() { ret.comment = Some(comment::parse_comment(c)); }
+        // clang always reports an underlying type, only keep it when it was written explicitly
+        let explicit = node.get_range().is_some_and(|r| {
+            r.tokenize()
+                .iter()
+                .take_while(|t| t.get_spelling() != "{")
+                .any(|t| t.get_spelling() == ":")
+        });
+
+        if explicit {
+            ret.underlying_type = node
+                .get_enum_underlying_type()
+                .map(|t| t.get_display_name());
+        }
+
+        if let Some(c) = node.get_comment() { ret.comment = Some(self.parse_comment(c)); }
+
+        let unsigned = node
+            .get_enum_underlying_type()
+            .is_some_and(|t| t.is_unsigned_integer());
 
         for c in node.get_children().iter() {
             if c.get_kind() == clang::EntityKind::EnumConstantDecl {
                 let value = EnumValue {
                     name: c.get_name().unwrap_or_default(),
-                    comment: c.get_comment().map(comment::parse_comment),
+                    comment: c.get_comment().map(|c| self.parse_comment(c)),
+                    value: c.get_enum_constant_value().map(|(signed, unsigned_value)| {
+                        if unsigned {
+                            unsigned_value.to_string()
+                        } else {
+                            signed.to_string()
+                        }
+                    }),
+                    // The initializer expression is the only child of the constant
+                    explicit: !c.get_children().is_empty(),
                 };
 
                 ret.values.push(value);
@@ -366,13 +2901,127 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Add the comments of the entities that `node` (in `scope`) holds in excluded namespaces
+    /// to `out`, `excluded` telling whether `scope` is itself excluded
+    fn collect_hidden_comments(
+        &self,
+        node: clang::Entity,
+        scope: &str,
+        excluded: bool,
+        out: &mut BTreeMap<String, Comment>,
+    ) {
+        let Some(name) = node.get_name() else {
+            return;
+        };
+
+        let qualified = qualified_name(scope, &name);
+
+        let excluded = match node.get_kind() {
+            clang::EntityKind::Namespace => {
+                excluded || self.is_excluded_namespace(&name, &qualified)
+            }
+            clang::EntityKind::ClassDecl
+            | clang::EntityKind::StructDecl
+            | clang::EntityKind::UnionDecl
+            | clang::EntityKind::ClassTemplate => excluded,
+            _ => {
+                if let Some(comment) = node.get_comment().filter(|_| excluded) {
+                    out.entry(qualified)
+                        .or_insert_with(|| self.parse_comment(comment));
+                }
+
+                return;
+            }
+        };
+
+        if let Some(comment) = node.get_comment().filter(|_| excluded) {
+            out.entry(qualified.clone())
+                .or_insert_with(|| self.parse_comment(comment));
+        }
+
+        for child in node.get_children() {
+            self.collect_hidden_comments(child, &qualified, excluded, out);
+        }
+    }
+
+    /// Add `record`, a record of the namespace `current_namespace_name`, to `ns` and its nested
+    /// types to the index
+    fn add_record(
+        ns: &mut Namespace,
+        index: &mut Index,
+        mut record: Record,
+        current_namespace_name: &str,
+        absolute_name: String,
+    ) {
+        // If a record already exists, it must be some kind of template specialization/overloading,
+        // We don't really support template specialization/overloading, so we just ignore it and
+        // merge all methods.
+        if let Some(existing) = ns.records.iter_mut().find(|r| r.name == record.name) {
+            merge_record(existing, record);
+            return;
+        }
+
+        if let Some(nest) = &mut record.nested {
+            for nested in nest {
+                match nested {
+                    NestedField::Record(r) => {
+                        let current_namespace_name = if current_namespace_name.is_empty() {
+                            record.name.clone()
+                        } else {
+                            format!("{}::{}", current_namespace_name, record.name)
+                        };
+
+                        r.namespace = Some(current_namespace_name.to_string());
+
+                        index.insert(
+                            Self::get_name_for_namespace(
+                                r.name.as_str(),
+                                record.name.as_str(),
+                                &current_namespace_name,
+                            ),
+                            "record",
+                            r.location.clone(),
+                        );
+                    }
+                    NestedField::Enum(e) => {
+                        let current_namespace_name = if current_namespace_name.is_empty() {
+                            record.name.clone()
+                        } else {
+                            format!("{}::{}", current_namespace_name, record.name)
+                        };
+
+                        e.namespace = Some(current_namespace_name.to_string());
+
+                        index.insert(
+                            Self::get_name_for_namespace(
+                                e.name.as_str(),
+                                record.name.as_str(),
+                                &current_namespace_name,
+                            ),
+                            "enum",
+                            e.location.clone(),
+                        );
+                    }
+                }
+            }
+        }
+
+        index.insert(absolute_name, "record", record.location.clone());
+        ns.records.push(record);
+    }
+
     fn parse_node(
         &self,
         node: clang::Entity,
         ns: &mut Namespace,
-        index: &mut HashMap<String, String>,
+        index: &mut Index,
         current_namespace_name: &str,
     ) {
+        // Excluded records and namespaces take their content with them, none of it is indexed
+        if self.is_excluded_symbol(&node) {
+            return;
+        }
+
         let absolute_name = Self::get_name_for_namespace(
             node.get_name().unwrap_or_default().as_str(),
             ns.name.as_str(),
@@ -381,120 +3030,179 @@ impl<'a> Parser<'a> {
 
         match node.get_kind() {
             clang::EntityKind::FunctionDecl | clang::EntityKind::FunctionTemplate => {
-                let mut function = self.parse_function(node);
-                function.namespace = Some(current_namespace_name.to_string());
-
-                if let Some(existing) = ns.functions.iter_mut().find(|f| f.name == function.name) {
-                    if existing.overloads.is_none() {
-                        existing.overloads = Some(Vec::new());
-                    }
-
-                    existing.overloads.as_mut().unwrap().push(function);
+                if self.is_hidden(&node) {
                     return;
                 }
 
+                let mut function = self.parse_function(node);
+                function.namespace = Some(current_namespace_name.to_string());
+                function.include_path = self.include_path(&node);
+
                 if function.name.contains("deduction guide") {
                     return;
                 }
 
-                index.insert(absolute_name, "function".to_string());
+                let location = function.location.clone();
 
-                ns.functions.push(function);
+                if add_function(&mut ns.functions, function) {
+                    index.insert(absolute_name, "function", location);
+                }
             }
 
             clang::EntityKind::StructDecl
             | clang::EntityKind::ClassDecl
             | clang::EntityKind::UnionDecl
             | clang::EntityKind::ClassTemplate => {
-                let mut record = self.parse_record(node);
-                record.namespace = Some(current_namespace_name.to_string());
-
-                // If a record already exists, it must be some kind of template specialization/overloading,
-                // We don't really support template specialization/overloading, so we just ignore it and merge all methods.
-                if let Some(existing) = ns.records.iter_mut().find(|r| r.name == record.name) {
-                    existing.methods.append(&mut record.methods);
+                // Unnamed records are documented under the name of their typedef, if any
+                if is_unnamed(&node) || self.is_hidden(&node) {
                     return;
                 }
 
-                if let Some(nest) = &mut record.nested {
-                    for nested in nest {
-                        match nested {
-                            NestedField::Record(r) => {
-                                let current_namespace_name = if current_namespace_name.is_empty() {
-                                    record.name.clone()
-                                } else {
-                                    format!("{}::{}", current_namespace_name, record.name)
-                                };
-
-                                r.namespace = Some(current_namespace_name.to_string());
-
-                                index.insert(
-                                    Self::get_name_for_namespace(
-                                        r.name.as_str(),
-                                        record.name.as_str(),
-                                        &current_namespace_name,
-                                    ),
-                                    "record".to_string(),
-                                );
-                            }
-                            NestedField::Enum(e) => {
-                                let current_namespace_name = if current_namespace_name.is_empty() {
-                                    record.name.clone()
-                                } else {
-                                    format!("{}::{}", current_namespace_name, record.name)
-                                };
-
-                                e.namespace = Some(current_namespace_name.to_string());
-
-                                index.insert(
-                                    Self::get_name_for_namespace(
-                                        e.name.as_str(),
-                                        record.name.as_str(),
-                                        &current_namespace_name,
-                                    ),
-                                    "enum".to_string(),
-                                );
-                            }
-                        }
-                    }
+                // Forward declarations are left to the definition when the translation unit has it
+                if !node.is_definition() && node.get_definition().is_some() {
+                    return;
                 }
 
-                index.insert(absolute_name, "record".to_string());
-                ns.records.push(record);
+                let mut record = self.parse_record(node, current_namespace_name);
+                record.namespace = Some(current_namespace_name.to_string());
+                record.include_path = self.include_path(&node);
+
+                Self::add_record(ns, index, record, current_namespace_name, absolute_name);
             }
 
             clang::EntityKind::EnumDecl => {
+                if is_unnamed(&node) || self.is_hidden(&node) {
+                    return;
+                }
+
                 let mut enum_ = self.parse_enum(node);
                 enum_.namespace = Some(current_namespace_name.to_string());
+                enum_.include_path = self.include_path(&node);
 
-                index.insert(absolute_name, "enum".to_string());
+                index.insert(absolute_name, "enum", enum_.location.clone());
                 ns.enums.push(enum_);
             }
 
             clang::EntityKind::Namespace => {
-                let name = node.get_name().unwrap();
+                // Anonymous namespaces have internal linkage, their content isn't part of the API.
+                // When documented anyway, it's accessible from (and listed in) the parent.
+                if node.is_anonymous() {
+                    if self.include_anonymous_namespaces {
+                        for cursor in node.get_children() {
+                            self.parse_node(cursor, ns, index, current_namespace_name);
+                        }
+                    }
+
+                    return;
+                }
+
+                let Some(name) = node.get_name() else {
+                    report_warning(&format!(
+                        "{}: skipping a namespace without a name",
+                        position(&node)
+                    ));
+                    return;
+                };
+
+                // Implementation details (`detail`, `impl`...) are neither documented nor indexed,
+                // so that nothing links to them
+                if self.is_excluded_namespace(&name, &absolute_name) {
+                    return;
+                }
+
+                // The content of inline namespaces (used for ABI versioning) goes in the parent
+                // namespace, as that's how users refer to it. The fully qualified names are
+                // registered too, so that links using them still resolve.
+                if node.is_inline_namespace() && self.collapse_inline_namespaces {
+                    index.insert(absolute_name, "inline namespace", self.location(&node));
+
+                    let known = index.names().cloned().collect::<HashSet<_>>();
+
+                    for cursor in node.get_children() {
+                        self.parse_node(cursor, ns, index, current_namespace_name);
+                    }
+
+                    let prefix = if current_namespace_name.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{}::", current_namespace_name)
+                    };
+
+                    let added = index
+                        .iter()
+                        .filter(|(key, _)| !known.contains(*key))
+                        .map(|(key, entry)| (key.clone(), entry.clone()))
+                        .collect::<Vec<_>>();
+
+                    for (key, entry) in added {
+                        if let Some(rest) = key.strip_prefix(&prefix) {
+                            index.insert(
+                                format!("{}{}::{}", prefix, name, rest),
+                                &entry.kind,
+                                entry.location,
+                            );
+                        }
+                    }
+
+                    return;
+                }
+
+                // `namespace mylib { //! The library`
+                let header = namespace_header(&node)
+                    .map(|(raw, offset)| (self.parse_comment(raw), offset));
+
                 let mut real_ns = Namespace {
-                    name: node.get_name().unwrap(),
-                    comment: node.get_comment().map(comment::parse_comment),
+                    name: name.clone(),
+                    comment: node.get_comment().map(|c| self.parse_comment(c)),
                     records: Vec::new(),
                     functions: Vec::new(),
                     namespaces: Vec::new(),
                     enums: Vec::new(),
                     aliases: Vec::new(),
                     namespace: Some(current_namespace_name.to_string()),
+                    variables: Vec::new(),
+                    concepts: Vec::new(),
+                    inline_: node.is_inline_namespace(),
+                    modules: Vec::new(),
+                    namespace_aliases: Vec::new(),
+                    location: self.location(&node),
                 };
 
+                // The declaration clang attached the header to, which keeps its own comment
+                let header_owner = header.as_ref().and_then(|(header, offset)| {
+                    let owner = node.get_children().into_iter().find(|c| {
+                        c.get_comment_range().is_some_and(|r| {
+                            r.get_start().get_file_location().offset as usize == *offset
+                        })
+                    })?;
+
+                    Some((owner.get_name()?, header.clone()))
+                });
+
+                if let Some((header, _)) = header {
+                    merge_namespace_comment(&mut real_ns.comment, Some(header));
+                }
+
                 let mut already_exists = false;
 
                 let new_ns =
                     if let Some(existing_ns) = ns.namespaces.iter_mut().find(|n| n.name == name) {
+                        // Any of the declarations can carry the documentation of the namespace
+                        merge_namespace_comment(&mut existing_ns.comment, real_ns.comment.take());
+
                         already_exists = true;
                         existing_ns
                     } else {
                         &mut real_ns
                     };
 
-                index.insert(absolute_name, "namespace".to_string());
+                if let Some(module) = &self.module {
+                    if !new_ns.modules.contains(module) {
+                        new_ns.modules.push(module.clone());
+                    }
+                }
+
+                index.insert(absolute_name, "namespace", self.location(&node));
 
                 for cursor in node.get_children() {
                     if !current_namespace_name.is_empty() {
@@ -509,54 +3217,222 @@ impl<'a> Parser<'a> {
                     }
                 }
 
+                if let Some((owner, header)) = header_owner {
+                    drop_header_comment(new_ns, &owner, &header);
+                }
+
                 if !already_exists {
                     ns.namespaces.push(real_ns);
                 }
             }
 
-            clang::EntityKind::TypeAliasDecl => {
-                let mut type_ = String::new();
-                let mut templated = false;
+            clang::EntityKind::NamespaceAlias => {
+                let Some(name) = node.get_name() else {
+                    return;
+                };
 
-                let child_count = node.get_children().len();
+                // `namespace fs = std::filesystem;` references `std`, then `filesystem`
+                let references = node
+                    .get_children()
+                    .into_iter()
+                    .filter(|c| c.get_kind() == clang::EntityKind::NamespaceRef)
+                    .collect::<Vec<_>>();
+
+                let target = references
+                    .last()
+                    .and_then(|r| r.get_reference())
+                    .and_then(|target| self.qualified_name(&target))
+                    .unwrap_or_else(|| {
+                        references
+                            .iter()
+                            .filter_map(|r| r.get_name())
+                            .collect::<Vec<_>>()
+                            .join("::")
+                    });
+
+                // A redeclaration of the same alias
+                if ns.namespace_aliases.iter().any(|a| a.name == name) {
+                    return;
+                }
 
-                // Wow this sucks
-                for (i, c) in node.get_children().iter().enumerate() {
-                    if c.get_kind() == clang::EntityKind::TypeRef {
-                        if let Some(t) = c.get_typedef_underlying_type() {
-                            type_.push_str(&t.get_display_name());
-                        } else {
-                            let display_name = c.get_display_name().unwrap();
-                            let display_name = display_name.trim_start_matches("struct ");
-                            type_.push_str(display_name);
-                        }
-                    } else if c.get_kind() == clang::EntityKind::TemplateRef {
-                        templated = true;
-                        type_ = c.get_display_name().unwrap();
-                        type_.push('<');
-                    }
+                index.insert(absolute_name, "namespace alias", self.location(&node));
 
-                    if templated && i < child_count - 1 && i != 0 {
-                        type_.push(',');
-                    }
+                ns.namespace_aliases.push(NamespaceAlias {
+                    name,
+                    namespace: Some(current_namespace_name.to_string()),
+                    target,
+                    comment: node.get_comment().map(|c| self.parse_comment(c)),
+                    location: self.location(&node),
+                });
+            }
+
+            // The declarations of `extern "C"` and `export` blocks belong to the enclosing
+            // namespace
+            clang::EntityKind::LinkageSpec | clang::EntityKind::UnexposedDecl
+                if is_linkage_spec(&node) || is_export_decl(&node) =>
+            {
+                for cursor in node.get_children() {
+                    self.parse_node(cursor, ns, index, current_namespace_name);
+                }
+            }
+
+            // `ConceptDecl` on libclang 18+, an unexposed declaration before
+            clang::EntityKind::NotImplemented | clang::EntityKind::UnexposedDecl
+                if is_concept(&node) =>
+            {
+                let mut concept = self.parse_concept(node);
+                concept.namespace = Some(current_namespace_name.to_string());
+
+                // Unexposed declarations have no name, `parse_concept` takes the one after
+                // `concept` then
+                let absolute_name = Self::get_name_for_namespace(
+                    &concept.name,
+                    ns.name.as_str(),
+                    current_namespace_name,
+                );
+
+                index.insert(absolute_name, "concept", concept.location.clone());
+                ns.concepts.push(concept);
+            }
+
+            // libclang exposes variable templates as unexposed declarations with template
+            // parameters, their specializations as plain variables
+            clang::EntityKind::VarDecl | clang::EntityKind::UnexposedDecl => {
+                if self.is_hidden(&node) {
+                    return;
+                }
+
+                let is_template = node.get_children().iter().any(|c| {
+                    c.get_kind() == clang::EntityKind::TemplateTypeParameter
+                        || c.get_kind() == clang::EntityKind::NonTypeTemplateParameter
+                        || c.get_kind() == clang::EntityKind::TemplateTemplateParameter
+                });
+
+                if node.get_kind() == clang::EntityKind::UnexposedDecl && !is_template {
+                    return;
                 }
 
-                if templated {
-                    type_.push('>');
+                let mut variable = self.parse_variable(node);
+                variable.namespace = Some(current_namespace_name.to_string());
+                variable.include_path = self.include_path(&node);
+
+                if is_template {
+                    variable.template = Some(self.parse_template(node));
                 }
 
-                if type_.is_empty() {
-                    type_ = "unknown".to_string();
+                // Only the primary template is documented, not its specializations
+                if ns.variables.iter().any(|v| v.name == variable.name) {
+                    return;
                 }
 
+                index.insert(absolute_name, "variable", variable.location.clone());
+                ns.variables.push(variable);
+            }
+
+            clang::EntityKind::TypeAliasDecl => {
+                let type_ = node
+                    .get_typedef_underlying_type()
+                    .map(|t| t.get_display_name())
+                    .filter(|t| !t.is_empty())
+                    .or_else(|| aliased_type_as_written(&node))
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let Some(name) = node.get_name() else {
+                    report_warning(&format!(
+                        "{}: skipping an alias without a name",
+                        position(&node)
+                    ));
+                    return;
+                };
+
                 let alias = Alias {
                     namespace: Some(current_namespace_name.to_string()),
-                    name: node.get_name().unwrap(),
+                    name,
                     type_,
-                    comment: node.get_comment().map(comment::parse_comment),
+                    comment: node.get_comment().map(|c| self.parse_comment(c)),
+                    deprecated: parse_deprecated(&node),
+                    location: self.location(&node),
+                    include_path: self.include_path(&node),
+                    available_in: Vec::new(),
+                };
+
+                index.insert(absolute_name, "alias", alias.location.clone());
+                ns.aliases.push(alias);
+            }
+
+            clang::EntityKind::TypedefDecl => {
+                if self.is_hidden(&node) {
+                    return;
+                }
+
+                let name = node.get_name().unwrap_or_default();
+
+                // `typedef struct { ... } foo_t;` is how C names its types, document a record
+                // (or enum) named `foo_t` rather than an alias of an unnamed type
+                let declaration = node
+                    .get_typedef_underlying_type()
+                    .and_then(|t| t.get_declaration())
+                    .filter(is_unnamed);
+
+                if let Some(declaration) = declaration {
+                    let comment = node
+                        .get_comment()
+                        .or_else(|| declaration.get_comment())
+                        .map(|c| self.parse_comment(c));
+
+                    match declaration.get_kind() {
+                        clang::EntityKind::StructDecl | clang::EntityKind::UnionDecl => {
+                            let mut record =
+                                self.parse_record_as(declaration, current_namespace_name, name);
+                            record.comment = comment;
+                            record.namespace = Some(current_namespace_name.to_string());
+                            record.include_path = self.include_path(&declaration);
+
+                            Self::add_record(
+                                ns,
+                                index,
+                                record,
+                                current_namespace_name,
+                                absolute_name,
+                            );
+                            return;
+                        }
+                        clang::EntityKind::EnumDecl => {
+                            let mut enum_ = self.parse_enum(declaration);
+                            enum_.name = name;
+                            enum_.comment = comment;
+                            enum_.namespace = Some(current_namespace_name.to_string());
+                            enum_.include_path = self.include_path(&declaration);
+
+                            index.insert(absolute_name, "enum", enum_.location.clone());
+                            ns.enums.push(enum_);
+                            return;
+                        }
+                        _ => {}
+                    }
+                }
+
+                let type_ = node
+                    .get_typedef_underlying_type()
+                    .map(|t| t.get_display_name())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let alias = Alias {
+                    namespace: Some(current_namespace_name.to_string()),
+                    name,
+                    type_: type_
+                        .trim_start_matches("struct ")
+                        .trim_start_matches("union ")
+                        .trim_start_matches("enum ")
+                        .to_string(),
+                    comment: node.get_comment().map(|c| self.parse_comment(c)),
+                    deprecated: parse_deprecated(&node),
+                    location: self.location(&node),
+                    include_path: self.include_path(&node),
+                    available_in: Vec::new(),
                 };
 
-                index.insert(absolute_name, "alias".to_string());
+                index.insert(absolute_name, "alias", alias.location.clone());
                 ns.aliases.push(alias);
             }
 
@@ -565,17 +3441,203 @@ impl<'a> Parser<'a> {
     }
 
     pub fn parse(&mut self, config: &config::Config, file: &str, out: &mut Output) {
-        let tu = self
-            .index
-            .parser(file)
-            .arguments(&config.input.compiler_arguments)
-            .parse()
-            .unwrap();
+        self.include_protected = config.output.include_protected.unwrap_or(false);
+        self.aliases = config.aliases().clone();
+        self.collapse_inline_namespaces = config
+            .output
+            .collapse_inline_namespaces
+            .unwrap_or(true);
+        self.exclude_namespaces = config
+            .input
+            .exclude_namespaces
+            .clone()
+            .unwrap_or_else(|| vec!["detail".to_string(), "impl".to_string()]);
+        self.include_anonymous_namespaces = config
+            .input
+            .include_anonymous_namespaces
+            .unwrap_or(false);
+        self.exported_only = config.output.exported_only.unwrap_or(false);
+        self.exclude_symbols.get_or_insert_with(|| {
+            config
+                .output
+                .exclude_symbols
+                .iter()
+                .flatten()
+                .filter_map(|pattern| match regex::Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        report_warning(&format!(
+                            "Invalid output.exclude_symbols pattern “{pattern}”: {e}"
+                        ));
+                        None
+                    }
+                })
+                .collect()
+        });
+
+        self.source_root = config.input.source_root.as_ref().map(PathBuf::from);
+        self.include_prefix_strip = config.output.include_prefix_strip.clone();
+        self.include_paths = config
+            .input
+            .include_paths_to_document
+            .iter()
+            .flatten()
+            .map(|dir| Path::new(dir).canonicalize().unwrap_or(PathBuf::from(dir)))
+            .collect();
+        self.module = std::fs::read_to_string(file)
+            .ok()
+            .and_then(|source| module_name(&source));
+
+        // The language arguments come first so that the configured ones can override them
+        let mut arguments = match config.input.language.as_deref() {
+            Some("c") => vec!["-xc".to_string(), "-std=c11".to_string()],
+            _ if self.module.is_some() => vec!["-xc++-module".to_string(), "-std=c++20".to_string()],
+            _ => Vec::new(),
+        };
+
+        // Headers are parsed as main files, where clang warns about `#pragma once`
+        arguments.push("-Wno-pragma-once-outside-header".to_string());
+
+        let database = config.input.compile_commands.as_deref().map(|path| {
+            &*self
+                .compile_commands
+                .get_or_insert_with(|| load_compile_commands(path))
+        });
+
+        match database {
+            Some(database) => {
+                let path = Path::new(file);
+                let path = path.canonicalize().unwrap_or(path.to_path_buf());
+
+                match database.get(&path) {
+                    Some(file_arguments) => arguments.extend(file_arguments.iter().cloned()),
+                    None => {
+                        report_warning(&format!(
+                            "“{file}” is not in the compilation database, using input.compiler_arguments"
+                        ));
+                        arguments.extend(config.input.compiler_arguments.iter().cloned());
+                    }
+                }
+            }
+            None => arguments.extend(config.input.compiler_arguments.iter().cloned()),
+        }
+
+        // The dedicated keys come last, clang keeps the last -std or --target it is given
+        arguments.extend(target_arguments(&config.input));
+        arguments.extend(self.defines.iter().map(|define| format!("-D{define}")));
+
+        if self.print_arguments {
+            eprintln!("{file}: {}", arguments.join(" "));
+        }
+
+        let cache_file = self.cache_dir.as_ref().and_then(|dir| {
+            let source = std::fs::read(file).ok()?;
+            Some(dir.join(cache_key(file, &source, &arguments, config)))
+        });
+
+        let cached = cache_file
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str::<Output>(&json).ok());
+
+        if let Some(cached) = cached {
+            self.cache_hits += 1;
+            self.module = None;
+            merge_outputs(out, cached);
+            return;
+        }
+
+        let tu = match self.index.parser(file).arguments(&arguments).parse() {
+            Ok(tu) => tu,
+            Err(e) => {
+                report_error(&format!("Unable to parse “{file}”: {e}"));
+
+                // libclang gives no translation unit, so no diagnostics, when the arguments
+                // themselves are wrong (an unknown target, a -std of the other language): parse
+                // again with an index printing them, to show why
+                let _ = clang::Index::new(self.clang, false, true)
+                    .parser(file)
+                    .arguments(&arguments)
+                    .parse();
+
+                self.errors += 1;
+                self.module = None;
+                return;
+            }
+        };
+
+        // A missing include path or define doesn't stop libclang, it leaves out whatever it
+        // couldn't parse, so the diagnostics are the only sign that the docs are incomplete
+        let mut has_errors = false;
+
+        for diagnostic in tu.get_diagnostics() {
+            let location = diagnostic.get_location().get_spelling_location();
+            let message = format!(
+                "{}:{}:{}: {}",
+                location
+                    .file
+                    .map(|f| f.get_path().display().to_string())
+                    .unwrap_or_else(|| file.to_string()),
+                location.line,
+                location.column,
+                diagnostic.get_text()
+            );
+
+            match diagnostic.get_severity() {
+                clang::diagnostic::Severity::Error | clang::diagnostic::Severity::Fatal => {
+                    report_error(&message);
+                    self.errors += 1;
+                    has_errors = true;
+                }
+                clang::diagnostic::Severity::Warning => {
+                    report_warning(&message);
+                    self.warnings += 1;
+                }
+                _ => {}
+            }
+        }
 
+        // The file is parsed on its own, so that its output can be cached
+        let mut output = Output::default();
+
+        for cursor in tu.get_entity().get_children() {
+            if cursor.is_in_main_file() {
+                self.parse_node(cursor, &mut output.root, &mut output.index, "");
+            } else if let Some(header) = self.documented_header(&cursor) {
+                let header = output.headers.entry(header).or_default();
+                self.parse_node(cursor, &mut header.root, &mut header.index, "");
+            }
+        }
+
+        // Implementation details aren't documented, but `@copydoc` can name them
         for cursor in tu.get_entity().get_children() {
             if cursor.is_in_main_file() {
-                self.parse_node(cursor, &mut out.root, &mut out.index, "");
+                self.collect_hidden_comments(cursor, "", false, &mut output.hidden_comments);
+            }
+        }
+
+        output.files.insert(file.to_string(), declared_names(&output.index));
+        output.groups = defined_groups(file, &self.aliases);
+
+        for (path, header) in &mut output.headers {
+            header.files.insert(path.clone(), declared_names(&header.index));
+            header.groups = defined_groups(path, &self.aliases);
+        }
+
+        if let Some(module) = self.module.take() {
+            output.root.modules.push(module);
+        }
+
+        // Files with errors are parsed again next time, to report them again
+        if let Some(path) = cache_file.filter(|_| !has_errors) {
+            let written = std::fs::create_dir_all(self.cache_dir.as_ref().unwrap())
+                .and_then(|_| std::fs::write(&path, serde_json::to_string(&output).unwrap()));
+
+            if let Err(e) = written {
+                report_warning(&format!("Error writing parse cache “{}”: {e}", path.display()));
             }
         }
+
+        merge_outputs(out, output);
     }
 }
@@ -0,0 +1,299 @@
+use crate::config::Config;
+use crate::copydoc::for_each_comment;
+use crate::index::Index;
+use crate::parser::{Comment, Namespace};
+use crate::render;
+use crate::report::report_warning;
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// An anchor defined with `@anchor`, where it links to and who defines it
+struct Anchor {
+    url: String,
+    owner: String,
+}
+
+/// The anchors of the comments and the extra pages, by name
+pub struct Anchors(HashMap<String, Anchor>);
+
+/// `@anchor name` and `@ref name "text"` commands, written with `@` or `\`
+fn commands() -> &'static Regex {
+    static COMMANDS: OnceLock<Regex> = OnceLock::new();
+
+    COMMANDS.get_or_init(|| {
+        Regex::new(
+            r#"[@\\](anchor|ref)[ \t]+((?:::)?[A-Za-z_][\w:-]*(?:\(\))?)(?:[ \t]+"([^"\n]*)")?"#,
+        )
+        .unwrap()
+    })
+}
+
+/// The `@anchor` and `@ref` commands of `text`, with their name and text, leaving out the
+/// `@` of email addresses and the like
+fn find_commands(text: &str) -> Vec<(regex::Captures<'_>, &str, &str)> {
+    commands()
+        .captures_iter(text)
+        .filter(|c| !text[..c.get(0).unwrap().start()].ends_with(|c: char| c.is_alphanumeric()))
+        .map(|c| {
+            let command = c.get(1).unwrap().as_str();
+            let name = c.get(2).unwrap().as_str();
+            (c, command, name)
+        })
+        .collect()
+}
+
+/// URL of the page documenting `name`, the one of the record or namespace holding it for the
+/// members without their own page
+fn page_url(name: &str, index: &Index, config: &Config) -> String {
+    let mut name = name;
+
+    loop {
+        if let Some(url) = render::page_url(name, index, config) {
+            return url;
+        }
+
+        match name.rsplit_once("::") {
+            Some((scope, _)) => name = scope,
+            None => return format!("{}/index.html", config.output.base_url),
+        }
+    }
+}
+
+impl Anchors {
+    /// Register the anchors of `text`, a comment of or a page `owner` at `url`
+    fn add(&mut self, text: &str, owner: &str, url: &str) {
+        render::map_prose(text, &mut |prose| {
+            for (_, command, name) in find_commands(prose) {
+                if command != "anchor" {
+                    continue;
+                }
+
+                match self.0.get(name) {
+                    Some(anchor) => report_warning(&format!(
+                        "The anchor “{name}” of “{owner}” is already defined by “{}”",
+                        anchor.owner
+                    )),
+                    None => {
+                        self.0.insert(
+                            name.to_string(),
+                            Anchor {
+                                url: format!("{url}#{name}"),
+                                owner: owner.to_string(),
+                            },
+                        );
+                    }
+                }
+            }
+
+            String::new()
+        });
+    }
+}
+
+/// Collect the anchors that the comments of `root` (whose qualified name is `scope`) and the
+/// extra pages (their name, URL and source) define with `@anchor`, warning about the duplicates
+pub fn collect_anchors(
+    root: &mut Namespace,
+    scope: &str,
+    pages: &[(String, String, &str)],
+    index: &Index,
+    config: &Config,
+) -> Anchors {
+    let mut anchors = Anchors(HashMap::new());
+
+    if let Some(ref comment) = root.comment {
+        anchors.add(&comment.description, scope, &page_url(scope, index, config));
+    }
+
+    for_each_comment(root, scope, &mut |name, comment| {
+        let url = page_url(name, index, config);
+
+        anchors.add(&comment.description, name, &url);
+
+        for (_, text) in &comment.notes {
+            anchors.add(text, name, &url);
+        }
+    });
+
+    for (name, url, source) in pages {
+        anchors.add(source, name, url);
+    }
+
+    anchors
+}
+
+/// Replace the `@anchor`s of `text`, written in `scope`, by HTML anchors (or nothing without
+/// `anchor_ids`) and its `@ref`s by markdown links to the symbols or anchors they name, adding
+/// the others to `unresolved`
+fn replace_commands(
+    text: &str,
+    scope: &str,
+    anchor_ids: bool,
+    anchors: &Anchors,
+    index: &Index,
+    config: &Config,
+    unresolved: &mut Vec<String>,
+) -> String {
+    render::map_prose(text, &mut |prose| {
+        let mut out = String::new();
+        let mut last = 0;
+
+        for (captures, command, name) in find_commands(prose) {
+            let whole = captures.get(0).unwrap();
+            out.push_str(&prose[last..whole.start()]);
+            last = whole.end();
+
+            if command == "anchor" {
+                if anchor_ids {
+                    out.push_str(&format!("<a id=\"{name}\"></a>"));
+                }
+
+                // The text after a name isn't part of an anchor
+                out.push_str(&prose[captures.get(2).unwrap().end()..whole.end()]);
+                continue;
+            }
+
+            let text = captures.get(3).map(|t| t.as_str());
+
+            let link = render::symbol_link(name, scope, index, config)
+                .map(|link| {
+                    (
+                        link,
+                        text.map_or_else(|| format!("`{name}`"), str::to_string),
+                    )
+                })
+                .or_else(|| {
+                    let anchor = anchors.0.get(name)?;
+                    Some((anchor.url.clone(), text.unwrap_or(name).to_string()))
+                });
+
+            match link {
+                Some((url, text)) => out.push_str(&format!("[{text}]({url})")),
+                None => {
+                    if !unresolved.iter().any(|n| n == name) {
+                        unresolved.push(name.to_string());
+                    }
+                    out.push_str(&text.map_or_else(|| format!("`{name}`"), str::to_string));
+                }
+            }
+        }
+
+        out.push_str(&prose[last..]);
+        out
+    })
+}
+
+/// Warn about the `@ref`s of `owner` that name neither a symbol nor an anchor
+fn report_unresolved(owner: &str, unresolved: &[String]) {
+    for name in unresolved {
+        report_warning(&format!(
+            "“{owner}” refers to “{name}” with @ref, which is neither a documented symbol nor \
+             an anchor"
+        ));
+    }
+}
+
+/// Replace the `@anchor`s of `text`, written by `owner` in `scope`, by HTML anchors and its
+/// `@ref`s by markdown links to the symbols or anchors they name, warning about the others
+pub fn resolve_refs(
+    text: &str,
+    owner: &str,
+    scope: &str,
+    anchors: &Anchors,
+    index: &Index,
+    config: &Config,
+) -> String {
+    let mut unresolved = Vec::new();
+    let text = replace_commands(text, scope, true, anchors, index, config, &mut unresolved);
+    report_unresolved(owner, &unresolved);
+    text
+}
+
+/// Resolve the `@anchor`s and `@ref`s of the brief, description and admonitions of a comment of
+/// `name`. The anchors of the brief, a copy of the first sentence most of the time, are dropped.
+fn resolve_comment(
+    name: &str,
+    comment: &mut Comment,
+    anchors: &Anchors,
+    index: &Index,
+    config: &Config,
+) {
+    let scope = name.rsplit_once("::").map_or("", |(scope, _)| scope);
+    let mut unresolved = Vec::new();
+    let mut replace = |text: &str, anchor_ids| {
+        replace_commands(
+            text,
+            scope,
+            anchor_ids,
+            anchors,
+            index,
+            config,
+            &mut unresolved,
+        )
+    };
+
+    comment.brief = replace(&comment.brief, false);
+    comment.description = replace(&comment.description, true);
+
+    for (_, text) in &mut comment.notes {
+        *text = replace(text, true);
+    }
+
+    report_unresolved(name, &unresolved);
+}
+
+/// Resolve the `@anchor`s and `@ref`s of the comments of `root`, whose qualified name is `scope`
+pub fn resolve_comment_refs(
+    root: &mut Namespace,
+    scope: &str,
+    anchors: &Anchors,
+    index: &Index,
+    config: &Config,
+) {
+    if let Some(ref mut comment) = root.comment {
+        resolve_comment(scope, comment, anchors, index, config);
+    }
+
+    for_each_comment(root, scope, &mut |name, comment| {
+        resolve_comment(name, comment, anchors, index, config);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+
+    #[test]
+    fn refs_link_symbols_and_anchors_outside_of_code() {
+        let config = test_config("");
+
+        let mut index = Index::default();
+        index.insert("net".to_string(), "namespace", None);
+        index.insert("net::Socket".to_string(), "record", None);
+
+        let mut anchors = Anchors(HashMap::new());
+        anchors.add(
+            "@anchor threads\n\n`@anchor code`",
+            "net::Socket",
+            "/net/record.Socket.html",
+        );
+        assert!(anchors.0.contains_key("threads"));
+        assert!(!anchors.0.contains_key("code"));
+
+        let resolve = |text| resolve_refs(text, "net::connect", "net", &anchors, &index, &config);
+
+        assert_eq!(
+            resolve("See @ref Socket and @ref threads \"the threads\"."),
+            "See [`Socket`](/net/record.Socket.html) and \
+             [the threads](/net/record.Socket.html#threads)."
+        );
+        assert_eq!(resolve("@anchor here\nText"), "<a id=\"here\"></a>\nText");
+        assert_eq!(resolve("Mail me@ref desk"), "Mail me@ref desk");
+        let code = "`@ref Socket`\n```\n@ref Socket\n```\n";
+        assert_eq!(resolve(code), code);
+        assert_eq!(resolve("@ref Missing"), "`Missing`");
+    }
+}
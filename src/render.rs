@@ -1,14 +1,18 @@
 use crate::config::Config;
 use crate::doctest;
+use crate::index::Index;
 use crate::parser;
 use crate::report::report_warning;
 
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 use pulldown_cmark::{CodeBlockKind, Event, Tag, TagEnd};
 use pygmentize::HtmlFormatter;
+use regex::Regex;
 
 #[derive(Debug, Serialize)]
 pub struct Page {
@@ -17,18 +21,107 @@ pub struct Page {
     pub path: PathBuf,
 }
 
-pub fn get_path_for_name(name: &str, index: &HashMap<String, String>) -> Option<String> {
-    let kind = index.get(name)?;
+/// File name for a symbol, safe on every platform: the characters of operators are spelled out
+/// so that `operator<<` is written as `operator-lt-lt` and `operator[]` as
+/// `operator-lbracket-rbracket`, literal operators like `operator""_kb` as `operator-udl-_kb`
+pub fn sanitize_symbol_filename(name: &str) -> String {
+    if let Some(suffix) = name.strip_prefix("operator\"\"") {
+        return format!("operator-udl-{}", sanitize_symbol_filename(suffix.trim()));
+    }
+
+    let mut ret = String::new();
+
+    for c in name.chars() {
+        let slug = match c {
+            '<' => "lt",
+            '>' => "gt",
+            '=' => "eq",
+            '!' => "not",
+            '+' => "plus",
+            '-' => "minus",
+            '*' => "star",
+            '/' => "slash",
+            '%' => "percent",
+            '^' => "caret",
+            '&' => "amp",
+            '|' => "pipe",
+            '~' => "tilde",
+            ',' => "comma",
+            '(' => "lparen",
+            ')' => "rparen",
+            '[' => "lbracket",
+            ']' => "rbracket",
+            '"' => "quot",
+            ':' => "colon",
+            '?' => "question",
+            '\\' => "backslash",
+            c if c.is_whitespace() => "",
+            c => {
+                ret.push(c);
+                continue;
+            }
+        };
+
+        if !ret.ends_with('-') {
+            ret.push('-');
+        }
+
+        ret.push_str(slug);
+    }
 
+    ret
+}
+
+/// Escape text (names, types, source code...) for display in HTML, e.g. `operator&lt;&lt;`
+pub fn escape_html(name: &str) -> String {
+    name.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Remove the collapsed inline namespaces from a qualified name: `lib::v2::Widget` is
+/// documented as `lib::Widget`
+pub fn collapse_inline_namespaces(name: &str, index: &Index) -> String {
+    let mut ret = Vec::new();
+    let mut qualified = String::new();
+
+    for (i, part) in name.split("::").enumerate() {
+        if i > 0 {
+            qualified.push_str("::");
+        }
+        qualified.push_str(part);
+
+        if index.get(&qualified).map(String::as_str) != Some("inline namespace") {
+            ret.push(part);
+        }
+    }
+
+    ret.join("::")
+}
+
+pub fn get_path_for_name(name: &str, index: &Index) -> Option<String> {
+    let name = &collapse_inline_namespaces(name, index);
+
+    get_path_for_entity(name, index.get(name)?)
+}
+
+/// Path of the page of the entity `name` of the given kind, for names that refer to several
+pub fn get_path_for_entity(name: &str, kind: &str) -> Option<String> {
     if kind == "namespace" {
         return Some(name.replace("::", "/"));
     }
 
+    // Namespace aliases have no page of their own, links go to the aliased namespace
+    if kind == "namespace alias" {
+        return None;
+    }
+
     let name = name.trim_start_matches("::");
 
     if name.contains("::") {
         let parts = name.split("::");
-        let basename = parts.clone().last()?.replace("/", "slash");
+        let basename = sanitize_symbol_filename(parts.clone().last()?);
 
         let path = parts
             .clone()
@@ -39,18 +132,198 @@ pub fn get_path_for_name(name: &str, index: &HashMap<String, String>) -> Option<
         return Some(format!("{}/{}.{}", path, kind, basename));
     }
 
-    Some(format!("{}.{}", kind, name.replace("/", "slash")))
+    Some(format!("{}.{}", kind, sanitize_symbol_filename(name)))
+}
+
+/// Resolve a record name as written (`Base`, `::detail::Base<T>`) from `scope` to the
+/// qualified name of a documented record, looking in the enclosing scopes like the compiler
+pub fn resolve_record(name: &str, scope: &str, index: &Index) -> Option<String> {
+    let name = name.split('<').next().unwrap_or_default().trim();
+
+    index.resolve_with(name, scope, |candidate| {
+        index.get(candidate).map(String::as_str) == Some("record")
+    })
 }
 
 pub fn get_namespace_path(name: &str) -> String {
     name.replace("::", "/")
 }
 
+/// Sort the members of `namespace` and of its nested namespaces, so that listings don't depend on
+/// the order files are parsed in. `order` is "alphabetical" (by name) or "source" (by file and
+/// line, members without a location last).
+pub fn sort_members(ns: &mut parser::Namespace, order: &str) {
+    let key = |name: &str, location: &Option<parser::SourceLocation>| match location {
+        _ if order != "source" => (false, name.to_string(), 0),
+        Some(location) => (false, location.file.clone(), location.line),
+        None => (true, String::new(), 0),
+    };
+
+    ns.records.sort_by_key(|r| key(&r.name, &r.location));
+    ns.functions.sort_by_key(|f| key(&f.name, &f.location));
+    ns.enums.sort_by_key(|e| key(&e.name, &e.location));
+    ns.aliases.sort_by_key(|a| key(&a.name, &a.location));
+    ns.variables.sort_by_key(|v| key(&v.name, &v.location));
+    ns.concepts.sort_by_key(|c| key(&c.name, &c.location));
+    ns.namespace_aliases.sort_by_key(|a| key(&a.name, &a.location));
+    ns.namespaces.sort_by_key(|n| key(&n.name, &n.location));
+
+    for child in &mut ns.namespaces {
+        sort_members(child, order);
+    }
+}
+
+/// Attach to every record the public methods it inherits from its documented public bases,
+/// grouped by base class. Methods hidden by a method of the same name in the derived class (or
+/// in a closer base) are left out.
+pub fn attach_inherited(root: &mut parser::Namespace, index: &Index) {
+    type Records = HashMap<String, (Vec<String>, Vec<parser::Function>)>;
+
+    fn collect_record(
+        record: &parser::Record,
+        scope: &str,
+        index: &Index,
+        out: &mut Records,
+    ) {
+        let name = parser::qualified_name(scope, &record.name);
+
+        let bases = record
+            .bases
+            .iter()
+            .filter(|base| base.access == "public")
+            .filter_map(|base| resolve_record(&base.name, scope, index))
+            .collect();
+
+        let methods = record
+            .methods
+            .iter()
+            .filter(|m| m.access.as_deref() != Some("protected"))
+            .cloned()
+            .collect();
+
+        for nested in record.nested.iter().flatten() {
+            if let parser::NestedField::Record(nested) = nested {
+                collect_record(nested, &name, index, out);
+            }
+        }
+
+        out.insert(name, (bases, methods));
+    }
+
+    fn collect(
+        namespace: &parser::Namespace,
+        scope: &str,
+        index: &Index,
+        out: &mut Records,
+    ) {
+        for record in &namespace.records {
+            collect_record(record, scope, index, out);
+        }
+
+        for ns in &namespace.namespaces {
+            collect(ns, &parser::qualified_name(scope, &ns.name), index, out);
+        }
+    }
+
+    fn attach_record(record: &mut parser::Record, scope: &str, records: &Records) {
+        let name = parser::qualified_name(scope, &record.name);
+
+        let mut hidden = record
+            .methods
+            .iter()
+            .map(|m| m.name.clone())
+            .collect::<HashSet<_>>();
+
+        // Breadth first, so that the closest base wins when two of them declare the same method.
+        // Visited bases are skipped: diamonds list the shared base once, cycles end.
+        let mut visited = HashSet::from([name.clone()]);
+        let mut queue = records
+            .get(&name)
+            .map(|(bases, _)| bases.iter().cloned().collect::<VecDeque<_>>())
+            .unwrap_or_default();
+
+        record.inherited.clear();
+
+        while let Some(base) = queue.pop_front() {
+            if !visited.insert(base.clone()) {
+                continue;
+            }
+
+            let Some((bases, methods)) = records.get(&base) else {
+                continue;
+            };
+
+            let inherited = methods
+                .iter()
+                .filter(|m| !hidden.contains(&m.name))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            hidden.extend(methods.iter().map(|m| m.name.clone()));
+
+            if !inherited.is_empty() {
+                record.inherited.push((base, inherited));
+            }
+
+            queue.extend(bases.iter().cloned());
+        }
+
+        for nested in record.nested.iter_mut().flatten() {
+            if let parser::NestedField::Record(nested) = nested {
+                attach_record(nested, &name, records);
+            }
+        }
+    }
+
+    fn attach(namespace: &mut parser::Namespace, scope: &str, records: &Records) {
+        for record in &mut namespace.records {
+            attach_record(record, scope, records);
+        }
+
+        for ns in &mut namespace.namespaces {
+            let scope = parser::qualified_name(scope, &ns.name);
+            attach(ns, &scope, records);
+        }
+    }
+
+    let scope = parser::qualified_name(root.namespace.as_deref(), &root.name);
+
+    let mut records = Records::new();
+    collect(root, &scope, index, &mut records);
+    attach(root, &scope, &records);
+}
+
 pub fn process_markdown(
     input: &str,
-    index: &HashMap<String, String>,
+    index: &Index,
     doctests: &mut Vec<doctest::Doctest>,
     config: &Config,
+) -> Page {
+    render_markdown(input, index, doctests, config, false)
+}
+
+/// The extensions of markdown, math with `output.math`
+fn markdown_options(config: &Config) -> pulldown_cmark::Options {
+    let mut options = pulldown_cmark::Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | pulldown_cmark::Options::ENABLE_TABLES
+        | pulldown_cmark::Options::ENABLE_STRIKETHROUGH
+        | pulldown_cmark::Options::ENABLE_TASKLISTS;
+
+    if config.output.math.is_some() {
+        options |= pulldown_cmark::Options::ENABLE_MATH;
+    }
+
+    options
+}
+
+/// Render `input` to a page, dropping the links whose URL `is_safe_url` rejects (keeping their
+/// text) with `safe_links`
+fn render_markdown(
+    input: &str,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+    safe_links: bool,
 ) -> Page {
     let mut code = String::new();
     let mut in_code_block = false;
@@ -58,11 +331,16 @@ pub fn process_markdown(
     let mut in_metadata = false;
     let mut metadata = String::new();
     let mut title = String::new();
+    let mut in_dropped_link = false;
+    let mut in_link = false;
+    let autolink = config.output.autolink.unwrap_or(false);
+    let math = config.output.math.is_some();
 
-    let parser = pulldown_cmark::Parser::new_ext(
+    // Text is merged for autolinking, so that a name isn't cut where an emphasis could start
+    let parser = pulldown_cmark::TextMergeStream::new(pulldown_cmark::Parser::new_ext(
         input,
-        pulldown_cmark::Options::ENABLE_YAML_STYLE_METADATA_BLOCKS,
-    )
+        markdown_options(config),
+    ))
     .filter_map(|event| match event {
         // -- Add support for mermaid code blocks and syntax highlighting --
         Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
@@ -75,6 +353,10 @@ pub fn process_markdown(
                 Some(Event::Html(
                     format!("<div class=\"mermaid\">{}</div>", code).into(),
                 ))
+            } else if code_lang == "math" && math {
+                let html = format!("<div class=\"math\">{}</div>", escape_html(code.trim_end()));
+                code.clear();
+                Some(Event::Html(html.into()))
             } else {
                 if code_lang == "cpp" || code_lang == "c++" || code_lang.is_empty() {
                     let doctest = doctest::Doctest::new(code.clone(), true);
@@ -116,11 +398,40 @@ pub fn process_markdown(
             } else if in_metadata {
                 metadata.push_str(&text);
                 None
+            } else if autolink && !in_link && (text.contains("::") || text.contains("()")) {
+                Some(Event::Html(link_symbols(&text, "", false, index, config).into()))
             } else {
                 Some(Event::Text(text))
             }
         }
 
+        // -- Link the qualified names written in inline code, `mylib::Socket` --
+        Event::Code(code) if autolink && !in_link && is_symbol(&code) => {
+            match symbol_link(&code, "", index, config) {
+                Some(link) => Some(Event::Html(
+                    format!("<a href=\"{link}\"><code>{}</code></a>", escape_html(&code)).into(),
+                )),
+                None => Some(Event::Code(code)),
+            }
+        }
+
+        // -- Math, typeset by KaTeX in the browser --
+        Event::InlineMath(tex) => Some(Event::Html(
+            format!("<span class=\"math\">{}</span>", escape_html(&tex)).into(),
+        )),
+        Event::DisplayMath(tex) => Some(Event::Html(
+            format!("<div class=\"math\">{}</div>", escape_html(&tex)).into(),
+        )),
+
+        // -- Task list checkboxes, which show the state without being clickable --
+        Event::TaskListMarker(checked) => Some(Event::Html(
+            format!(
+                "<input type=\"checkbox\" class=\"task\" disabled{}> ",
+                if checked { " checked" } else { "" }
+            )
+            .into(),
+        )),
+
         // -- Metadata --
         Event::Start(Tag::MetadataBlock(_)) => {
             in_metadata = true;
@@ -147,16 +458,41 @@ pub fn process_markdown(
             title,
             id,
         }) => {
+            in_link = true;
+
+            // `[x](javascript:…)` in a comment would be as live as a raw `<a href>`
+            if safe_links && !dest_url.starts_with("::") && !is_safe_url(&dest_url) {
+                report_warning(&format!("Dropping the link to “{dest_url}”, not a web page"));
+                in_dropped_link = true;
+                return None;
+            }
+
             if let pulldown_cmark::CowStr::Borrowed(url) = dest_url {
                 if url.starts_with("::") {
                     let url = url.trim_start_matches("::");
-                    let real = get_path_for_name(url, index);
+
+                    // `::File#method-open-2` links to one overload of `File::open`
+                    let (name, anchor) = match url.split_once('#') {
+                        Some((name, anchor)) => (name, format!("#{anchor}")),
+                        None => (url, String::new()),
+                    };
+
+                    let real = get_path_for_name(name, index);
 
                     if let Some(real) = real {
                         return Some(Event::Html(
-                            format!("<a href=\"{}/{}.html\">", config.output.base_url, real).into(),
+                            format!(
+                                "<a href=\"{}/{}.html{}\">",
+                                config.output.base_url, real, anchor
+                            )
+                            .into(),
                         ));
                     }
+
+                    // The symbol isn't documented (or was filtered out), keep the text only
+                    report_warning(&format!("Unresolved documentation link to “{url}”"));
+                    in_dropped_link = true;
+                    return None;
                 }
             }
 
@@ -168,9 +504,32 @@ pub fn process_markdown(
             }))
         }
 
-        Event::End(TagEnd::Link {}) => Some(Event::Html("</a>".into())),
+        Event::End(TagEnd::Link) => {
+            in_link = false;
+
+            if in_dropped_link {
+                in_dropped_link = false;
+                None
+            } else {
+                Some(Event::Html("</a>".into()))
+            }
+        }
 
         _ => Some(event),
+    })
+    // -- Wrap tables, for the wide ones to scroll on narrow screens --
+    .flat_map(|event| {
+        let (before, after) = match event {
+            Event::Start(Tag::Table(_)) => (Some("<div class=\"table-wrapper\">"), None),
+            Event::End(TagEnd::Table) => (None, Some("</div>")),
+            _ => (None, None),
+        };
+
+        before
+            .map(|html| Event::Html(html.into()))
+            .into_iter()
+            .chain(Some(event))
+            .chain(after.map(|html| Event::Html(html.into())))
     });
 
     let mut html_output = String::new();
@@ -182,42 +541,902 @@ pub fn process_markdown(
     }
 }
 
+/// Anchor of every method and constructor on its record page, by qualified name (the first
+/// overload's for overloaded ones), for `@see` entries naming members
+pub fn method_anchors(root: &parser::Namespace) -> HashMap<String, String> {
+    fn walk_record(record: &parser::Record, out: &mut HashMap<String, String>) {
+        for method in record.methods.iter().chain(&record.ctor) {
+            if let (Some(scope), Some(anchor)) = (&method.namespace, &method.anchor) {
+                out.entry(format!("{scope}::{}", method.name))
+                    .or_insert_with(|| anchor.clone());
+            }
+        }
+
+        for nested in record.nested.iter().flatten() {
+            if let parser::NestedField::Record(nested) = nested {
+                walk_record(nested, out);
+            }
+        }
+    }
+
+    fn walk(ns: &parser::Namespace, out: &mut HashMap<String, String>) {
+        for record in &ns.records {
+            walk_record(record, out);
+        }
+
+        for child in &ns.namespaces {
+            walk(child, out);
+        }
+    }
+
+    let mut anchors = HashMap::new();
+    walk(root, &mut anchors);
+
+    anchors
+}
+
+/// Link to the method or constructor `name` as written in `scope`, looking in the enclosing
+/// scopes like `Index::resolve`
+fn method_link(name: &str, scope: &str, index: &Index, config: &Config) -> Option<String> {
+    let method = index.resolve_with(name, scope, |candidate| {
+        index.method_anchor(candidate).is_some()
+    })?;
+
+    let anchor = index.method_anchor(&method)?;
+    let (record, _) = method.rsplit_once("::")?;
+    let path = get_path_for_name(record, index)?;
+
+    Some(format!("{}/{}.html#{}", config.output.base_url, path, anchor))
+}
+
+/// URL of the page of the entity `name`, the index page of a namespace
+pub fn page_url(name: &str, index: &Index, config: &Config) -> Option<String> {
+    let path = get_path_for_name(name, index)?;
+
+    Some(match index.get(name).map(String::as_str) {
+        Some("namespace" | "inline namespace") => {
+            format!("{}/{}/index.html", config.output.base_url, path)
+        }
+        _ => format!("{}/{}.html", config.output.base_url, path),
+    })
+}
+
+/// Link to the symbol `name` (`close()`, `mylib::Socket`, `::mylib::connect`) as written in
+/// `scope`, a method's being its anchor on the record page
+pub fn symbol_link(name: &str, scope: &str, index: &Index, config: &Config) -> Option<String> {
+    let name = name.trim_end_matches("()");
+
+    index
+        .resolve(name, scope)
+        .and_then(|name| get_path_for_name(&name, index))
+        .map(|path| format!("{}/{}.html", config.output.base_url, path))
+        .or_else(|| method_link(name, scope, index, config))
+}
+
+/// Whether `text` is a single qualified name or call, `mylib::Socket` or `connect()`
+fn is_symbol(text: &str) -> bool {
+    let name = text.strip_suffix("()").unwrap_or(text);
+    let qualified = name.trim_start_matches("::");
+
+    (name.len() < text.len() || qualified.contains("::") || qualified.len() < name.len())
+        && qualified.split("::").all(|part| {
+            part.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
+/// Escape `text` for HTML, linking the symbols it names that are documented, in `<code>` when
+/// `code` is set. Only qualified names and calls are looked up, `Connection::open()` but not
+/// `Connection`, plain words being too likely to match something unrelated.
+fn link_symbols(text: &str, scope: &str, code: bool, index: &Index, config: &Config) -> String {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut out = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        // Identifiers joined by `::`, maybe starting with one, then maybe `()`
+        let mut len = 0;
+
+        loop {
+            let tail = &rest[len..];
+            let separator = if tail.starts_with("::") { 2 } else { 0 };
+
+            if separator == 0 && len > 0 {
+                break;
+            }
+
+            let identifier = tail[separator..]
+                .find(|c: char| !is_identifier(c))
+                .unwrap_or(tail.len() - separator);
+
+            if identifier == 0 {
+                break;
+            }
+
+            len += separator + identifier;
+        }
+
+        if len == 0 {
+            out.push_str(&escape_html(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        if rest[len..].starts_with("()") {
+            len += 2;
+        }
+
+        let (symbol, tail) = rest.split_at(len);
+
+        let link = Some(symbol)
+            .filter(|s| is_symbol(s))
+            .and_then(|s| symbol_link(s, scope, index, config));
+
+        match link {
+            Some(link) if code => out.push_str(&format!(
+                "<a href=\"{link}\"><code>{}</code></a>",
+                escape_html(symbol)
+            )),
+            Some(link) => out.push_str(&format!("<a href=\"{link}\">{}</a>", escape_html(symbol))),
+            None => out.push_str(&escape_html(symbol)),
+        }
+
+        rest = tail;
+    }
+
+    out
+}
+
+/// Merge the `@deprecated` of `comment` into the attribute's `deprecated`, which keeps its
+/// message when it has one, then link the symbols of the comment's message
+fn process_deprecation(
+    comment: &mut parser::Comment,
+    deprecated: &mut Option<String>,
+    scope: &str,
+    index: &Index,
+    config: &Config,
+) {
+    let Some(message) = &mut comment.deprecated else {
+        return;
+    };
+
+    if deprecated.as_deref().unwrap_or_default().is_empty() {
+        *deprecated = Some(message.clone());
+    }
+
+    *message = link_symbols(message, scope, true, index, config);
+}
+
+/// Render a line of markdown, without the paragraph around it
+fn process_inline_markdown(
+    text: &str,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) -> String {
+    let html = process_comment_markdown(text, index, doctests, config).content;
+    let html = html.trim();
+
+    html.strip_prefix("<p>")
+        .and_then(|html| html.strip_suffix("</p>"))
+        .unwrap_or(html)
+        .to_string()
+}
+
+/// The fence opening or closing a code block on `line`: 3 or more backticks or tildes indented by
+/// at most 3 spaces, the info string of backticks having none
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let rest = line.trim_start_matches(' ');
+
+    if line.len() - rest.len() > 3 {
+        return None;
+    }
+
+    let c = rest.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = rest.len() - rest.trim_start_matches(c).len();
+
+    (len >= 3 && !(c == '`' && rest[len..].contains('`'))).then_some((c, len))
+}
+
+/// Push the code spans of `line` (starting at `offset` in the text) and the prose between them: a
+/// run of backticks opens a span closed by the next run of the same length on the line, one
+/// without such a run is text, and so is an escaped backtick
+fn push_code_spans(line: &str, offset: usize, segments: &mut Vec<(Range<usize>, bool)>) {
+    let bytes = line.as_bytes();
+    let run = |i: usize| bytes[i..].iter().take_while(|&&b| b == b'`').count();
+
+    let mut prose = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1).is_some_and(u8::is_ascii_punctuation) => i += 2,
+            b'`' => {
+                let len = run(i);
+                let mut j = i + len;
+
+                while j < bytes.len() && !(bytes[j] == b'`' && run(j) == len) {
+                    j += if bytes[j] == b'`' { run(j) } else { 1 };
+                }
+
+                if j < bytes.len() {
+                    push_segment(segments, offset + prose..offset + i, false);
+                    push_segment(segments, offset + i..offset + j + len, true);
+                    prose = j + len;
+                    i = prose;
+                } else {
+                    i += len;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    push_segment(segments, offset + prose..offset + bytes.len(), false);
+}
+
+/// Push a segment of `code_segments`, merged with the last one when they're both code or prose
+fn push_segment(segments: &mut Vec<(Range<usize>, bool)>, range: Range<usize>, code: bool) {
+    match segments.last_mut() {
+        _ if range.is_empty() => {}
+        Some((last, last_code)) if *last_code == code => last.end = range.end,
+        _ => segments.push((range, code)),
+    }
+}
+
+/// Split `text` in its code (`true`) and the prose around it (`false`), finding the code blocks
+/// and code spans like CommonMark does: a fence is closed by a fence of the same character at
+/// least as long, and an indented one is just text
+pub fn code_segments(text: &str) -> Vec<(Range<usize>, bool)> {
+    let mut segments = Vec::new();
+    let mut fence = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let range = offset..offset + line.len();
+
+        match fence {
+            Some((c, len)) => {
+                // Closed by a fence at least as long, without an info string
+                if code_fence(line).is_some_and(|(closing, n)| closing == c && n >= len)
+                    && line.trim().trim_start_matches(c).is_empty()
+                {
+                    fence = None;
+                }
+                push_segment(&mut segments, range, true);
+            }
+            None => match code_fence(line) {
+                Some(opening) => {
+                    fence = Some(opening);
+                    push_segment(&mut segments, range, true);
+                }
+                None => push_code_spans(line, offset, &mut segments),
+            },
+        }
+
+        offset += line.len();
+    }
+
+    segments
+}
+
+/// Call `f` with the parts of `text` outside of its code blocks and code spans, replacing them
+/// by what it returns
+pub fn map_prose(text: &str, f: &mut dyn FnMut(&str) -> String) -> String {
+    code_segments(text)
+        .into_iter()
+        .map(|(range, code)| if code { text[range].to_string() } else { f(&text[range]) })
+        .collect()
+}
+
+/// The tags that `output.comment_html = "sanitize"` keeps, with the parts of a table
+pub const SANITIZED_TAGS: &[&str] = &[
+    "b", "i", "code", "a", "br", "table", "thead", "tbody", "tr", "th", "td",
+];
+
+/// Decode the character references of an attribute value, `&#x61;` being `a` for the browser
+fn decode_entities(value: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+
+    let entity = ENTITY
+        .get_or_init(|| Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[A-Za-z]+);?").unwrap());
+
+    entity
+        .replace_all(value, |captures: &regex::Captures| {
+            let name = &captures[1];
+
+            let c = match name.strip_prefix('#') {
+                Some(number) => match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => number.parse().ok().and_then(char::from_u32),
+                },
+                None => match name.to_lowercase().as_str() {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "colon" => Some(':'),
+                    "tab" => Some('\t'),
+                    "newline" => Some('\n'),
+                    _ => None,
+                },
+            };
+
+            c.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Whether a link of a comment is safe to keep: an http(s) URL or a relative one
+fn is_safe_url(url: &str) -> bool {
+    // Browsers ignore the whitespace and control characters in a scheme, `java\tscript:`
+    let url = decode_entities(url)
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>();
+
+    match url.find([':', '/', '?', '#']) {
+        Some(i) if url[i..].starts_with(':') => {
+            matches!(url[..i].to_lowercase().as_str(), "http" | "https")
+        }
+        _ => true,
+    }
+}
+
+/// Whether `output.comment_html = "sanitize"` keeps a tag: one of `SANITIZED_TAGS` whose only
+/// attribute is the safe `href` of a link. The attributes are read like a browser would, so
+/// `href="x"onclick="…"` is two of them.
+fn is_sanitized_tag(name: &str, attributes: &str, closing: bool) -> bool {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+
+    let attribute = ATTRIBUTE.get_or_init(|| {
+        Regex::new(concat!(
+            r#"^[\s/]*([^\s"'<>/=]+)"#,
+            r#"(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#,
+        ))
+        .unwrap()
+    });
+
+    let name = name.to_lowercase();
+
+    if !SANITIZED_TAGS.contains(&name.as_str()) {
+        return false;
+    }
+
+    let mut rest = attributes;
+
+    while !rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/').is_empty() {
+        let Some(captures) = attribute.captures(rest) else {
+            return false;
+        };
+
+        let value = (2..=4).find_map(|i| captures.get(i)).map_or("", |v| v.as_str());
+
+        let href = name == "a" && captures[1].eq_ignore_ascii_case("href");
+
+        if closing || !href || !is_safe_url(value) {
+            return false;
+        }
+
+        rest = &rest[captures.get(0).unwrap().end()..];
+    }
+
+    true
+}
+
+/// Escape the raw HTML of the markdown of a comment following `output.comment_html`: all of it
+/// with "escape", what `is_sanitized_tag` rejects with "sanitize". Autolinks to http(s) URLs,
+/// the anchors of `@anchor`, the figures of `@image` and math are kept.
+fn clean_comment_html(text: &str, config: &Config) -> String {
+    static HTML: OnceLock<Regex> = OnceLock::new();
+    static MATH: OnceLock<Regex> = OnceLock::new();
+
+    let sanitize = match config.output.comment_html.as_deref().unwrap_or("escape") {
+        "allow" => return text.to_string(),
+        mode => mode == "sanitize",
+    };
+
+    let html = HTML.get_or_init(|| {
+        Regex::new(concat!(
+            // Anchors of `@anchor` and figures of `@image`
+            r#"<a id="[\w:-]+"></a>"#,
+            r#"|<figure class="image"><img src="[^"<>]*" alt="[^"<>]*">"#,
+            r"(?:<figcaption>[^<>]*</figcaption>)?</figure>",
+            // Autolinks, only to web pages: `<javascript:…>` would be a link too
+            r"|(?i:<https?://[^\s<>]+>)",
+            // Tags with their name and attributes, then any other `<`
+            r"|<(/?)([A-Za-z][A-Za-z0-9-]*)(\s[^<>]*?)?/?>|<",
+        ))
+        .unwrap()
+    });
+
+    let clean = |prose: &str| {
+        html.replace_all(prose, |captures: &regex::Captures| {
+            let whole = &captures[0];
+
+            let kept = match captures.get(2) {
+                Some(tag) => {
+                    let attributes = captures.get(3).map_or("", |a| a.as_str());
+                    sanitize && is_sanitized_tag(tag.as_str(), attributes, &captures[1] == "/")
+                }
+                None => whole.len() > 1,
+            };
+
+            if kept {
+                whole.to_string()
+            } else {
+                whole.replacen('<', "&lt;", 1)
+            }
+        })
+        .into_owned()
+    };
+
+    // The TeX typeset with `output.math` isn't HTML, `$a < b$` is kept as it is
+    let math = config.output.math.is_some().then(|| {
+        MATH.get_or_init(|| Regex::new(r"\$\$.+?\$\$|\$[^\s$](?:[^$\n]*[^\s$])?\$").unwrap())
+    });
+
+    // The pieces of the text, cleaned or kept raw
+    let mut pieces = Vec::new();
+
+    for (range, code) in code_segments(text) {
+        let part = &text[range];
+
+        if code {
+            pieces.push((part.to_string(), true));
+            continue;
+        }
+
+        let mut last = 0;
+
+        for tex in math.iter().flat_map(|math| math.find_iter(part)) {
+            pieces.push((clean(&part[last..tex.start()]), false));
+            pieces.push((tex.as_str().to_string(), true));
+            last = tex.end();
+        }
+
+        pieces.push((clean(&part[last..]), false));
+    }
+
+    // Only the tags markdown reads as code or math stay raw, `\$<b>$` or a backtick in a kept tag
+    // starts neither: the other pieces are cleaned until the text reads the same
+    loop {
+        let cleaned = pieces.iter().map(|(piece, _)| piece.as_str()).collect::<String>();
+
+        let verbatim = pulldown_cmark::Parser::new_ext(&cleaned, markdown_options(config))
+            .into_offset_iter()
+            .filter_map(|(event, range)| match event {
+                Event::Code(_) | Event::InlineMath(_) | Event::DisplayMath(_) => Some(range),
+                Event::Start(Tag::CodeBlock(_)) => Some(range),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut offset = 0;
+        let mut changed = false;
+
+        for (piece, raw) in &mut pieces {
+            let start = offset;
+            offset += piece.len();
+
+            let read_verbatim = piece
+                .match_indices('<')
+                .all(|(i, _)| verbatim.iter().any(|range| range.contains(&(start + i))));
+
+            if *raw && !read_verbatim {
+                *piece = clean(piece);
+                *raw = false;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return cleaned;
+        }
+    }
+}
+
+/// `process_markdown` for the markdown of a comment, its raw HTML escaped or sanitized first and
+/// its links to other schemes than http(s) dropped (unless `output.comment_html = "allow"`)
+fn process_comment_markdown(
+    input: &str,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) -> Page {
+    let safe_links = config.output.comment_html.as_deref() != Some("allow");
+
+    render_markdown(&clean_comment_html(input, config), index, doctests, config, safe_links)
+}
+
+/// Escape the `<` of `text` outside of its code spans, for markdown to keep them as text rather
+/// than read HTML tags: `size < N`, `std::vector<int>`...
+fn escape_angle_brackets(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_code = false;
+
+    for c in text.chars() {
+        match c {
+            '`' => {
+                in_code = !in_code;
+                out.push(c);
+            }
+            '<' if !in_code => out.push_str("&lt;"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Render the `@pre` and `@post` conditions of `comment` as lists, before its description
+fn process_conditions(
+    comment: &mut parser::Comment,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    let mut html = String::new();
+
+    let sections = [
+        ("preconditions", "Preconditions", &mut comment.preconditions),
+        ("postconditions", "Postconditions", &mut comment.postconditions),
+    ];
+
+    for (class, title, conditions) in sections {
+        if conditions.is_empty() {
+            continue;
+        }
+
+        let mut items = String::new();
+
+        for condition in conditions.iter_mut() {
+            *condition =
+                process_inline_markdown(&escape_angle_brackets(condition), index, doctests, config);
+            items.push_str(&format!("<li>{condition}</li>"));
+        }
+
+        html.push_str(&format!("<div class=\"{class}\"><h3>{title}</h3><ul>{items}</ul></div>"));
+    }
+
+    comment.description.insert_str(0, &html);
+}
+
+/// Render the `@see` entries of a comment written in `scope`: markdown links as markdown,
+/// symbol names (`close()`, `mylib::Socket`, `::mylib::connect`) as links to their page
+fn process_see_also(
+    comment: &mut parser::Comment,
+    scope: &str,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    for entry in &mut comment.see_also {
+        if entry.starts_with('[') {
+            *entry = process_inline_markdown(entry, index, doctests, config);
+            continue;
+        }
+
+        *entry = match symbol_link(entry, scope, index, config) {
+            Some(link) => format!("<a href=\"{link}\"><code>{}</code></a>", escape_html(entry)),
+            None => {
+                if config.output.strict_see_also.unwrap_or(false) {
+                    report_warning(&format!(
+                        "“@see {entry}” in “{scope}” names no documented symbol"
+                    ));
+                }
+
+                format!("<code>{}</code>", escape_html(entry))
+            }
+        };
+    }
+}
+
+/// Render the admonitions of `comment` as callouts, after its description. The `@internal`
+/// sections are dropped, unless `output.show_internal` is set.
+fn process_notes(
+    comment: &mut parser::Comment,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    if !config.output.show_internal.unwrap_or(false) {
+        comment.notes.retain(|(kind, _)| *kind != parser::NoteKind::Internal);
+    }
+
+    for (kind, text) in &mut comment.notes {
+        *text = process_comment_markdown(text, index, doctests, config).content;
+
+        comment.description.push_str(&format!(
+            "<div class=\"admonition {}\"><p class=\"admonition-title\">{}</p>{}</div>",
+            kind.name(),
+            kind.title(),
+            text
+        ));
+    }
+}
+
+/// Anchor of an example file on the examples index page, `example-tcp_echo-cpp`
+pub fn example_id(file: &str) -> String {
+    let file = file
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' })
+        .collect::<String>();
+
+    format!("example-{file}")
+}
+
+/// Highlight the code of an example file, registering it as a doctest (which has its own `main`)
+/// when `doctests` is given
+pub fn highlight_example(
+    file: &str,
+    code: &str,
+    index: &Index,
+    doctests: Option<&mut Vec<doctest::Doctest>>,
+    config: &Config,
+) -> String {
+    let language = match doctests {
+        Some(_) => "nomain",
+        None if file.ends_with(".c") => "c",
+        None => "cpp",
+    };
+
+    let block = format!("```{language}\n{}\n```", code.trim_end());
+
+    match doctests {
+        Some(doctests) => process_markdown(&block, index, doctests, config).content,
+        None => process_markdown(&block, index, &mut Vec::new(), config).content,
+    }
+}
+
+/// Render the example files of `comment` in an "Examples" section, after its description
+fn process_examples(
+    comment: &mut parser::Comment,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    let examples = comment
+        .examples
+        .iter()
+        .filter_map(|example| Some((example, example.code.as_deref()?)))
+        .map(|(example, code)| {
+            let doctests = example.doctest.then_some(&mut *doctests);
+
+            format!(
+                "<div class=\"example\"><p class=\"example-title\">\
+                 <a href=\"{}/examples.html#{}\"><code>{}</code></a></p>{}</div>",
+                config.output.base_url,
+                example_id(&example.file),
+                escape_html(&example.file),
+                highlight_example(&example.file, code, index, doctests, config)
+            )
+        })
+        .collect::<String>();
+
+    if !examples.is_empty() {
+        comment.description.push_str(&format!("<h2 id=\"examples\">Examples</h2>{examples}"));
+    }
+}
+
+/// Give the template parameters of `entity` the descriptions of the `@tparam`s naming them,
+/// `Args` or `Args...` for a parameter pack
+fn document_template_parameters(
+    comment: &mut parser::Comment,
+    template: Option<&mut parser::Template>,
+    entity: &str,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    let parameters = template.map(|t| t.parameters.as_mut_slice()).unwrap_or_default();
+
+    for (name, description) in &mut comment.tparams {
+        *description = process_comment_markdown(description, index, doctests, config).content;
+
+        let unpacked = name.trim_end_matches("...");
+
+        match parameters.iter_mut().find(|p| p.name == unpacked) {
+            Some(parameter) => {
+                parameter.comment = Some(parser::Comment {
+                    description: description.clone(),
+                    ..Default::default()
+                })
+            }
+            None => report_warning(&format!(
+                "“{entity}” documents a template parameter “{name}” it doesn't have"
+            )),
+        }
+    }
+}
+
 pub fn process_function(
     func: &mut parser::Function,
-    index: &HashMap<String, String>,
+    index: &Index,
     doctests: &mut Vec<doctest::Doctest>,
     config: &Config,
 ) {
     if let Some(ref mut comment) = &mut func.comment {
-        comment.brief = process_markdown(&comment.brief, index, doctests, config).content;
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
         comment.description =
-            process_markdown(&comment.description, index, doctests, config).content;
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_conditions(comment, index, doctests, config);
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+
+        let qualified = parser::qualified_name(func.namespace.as_deref(), &func.name);
+
+        document_template_parameters(
+            comment,
+            func.template.as_mut(),
+            &qualified,
+            index,
+            doctests,
+            config,
+        );
+
+        // `@param` descriptions become the comments of the parameters they name
+        for (name, description) in &mut comment.params {
+            *description = process_comment_markdown(description, index, doctests, config).content;
+
+            match func.parameters.iter_mut().find(|p| p.name == *name) {
+                Some(parameter) => {
+                    parameter.comment = Some(parser::Comment {
+                        description: description.clone(),
+                        ..Default::default()
+                    })
+                }
+                None => report_warning(&format!(
+                    "“{qualified}” documents a parameter “{name}” it doesn't have"
+                )),
+            }
+        }
+
+        let scope = func.namespace.as_deref().unwrap_or_default();
+        process_see_also(comment, scope, index, doctests, config);
+        process_deprecation(comment, &mut func.deprecated, scope, index, config);
+
+        if let Some(returns) = &mut comment.returns {
+            *returns = process_comment_markdown(returns, index, doctests, config).content;
+        }
+
+        for (_, description) in &mut comment.throws {
+            *description = process_comment_markdown(description, index, doctests, config).content;
+        }
+
+        // `@retval Status::Timeout` links to the `Status` enum when it's documented
+        for (value, description) in &mut comment.retvals {
+            *description = process_comment_markdown(description, index, doctests, config).content;
+
+            let link = value
+                .trim_start_matches("::")
+                .rsplit_once("::")
+                .and_then(|(enum_, _)| index.resolve(enum_, scope))
+                .filter(|name| index.get(name).is_some_and(|kind| kind == "enum"))
+                .and_then(|name| get_path_for_name(&name, index));
+
+            *value = match link {
+                Some(path) => format!(
+                    "<a href=\"{}/{}.html\"><code>{}</code></a>",
+                    config.output.base_url,
+                    path,
+                    escape_html(value)
+                ),
+                None => format!("<code>{}</code>", escape_html(value)),
+            };
+        }
+    }
+
+    for overload in func.overloads.iter_mut().flatten() {
+        process_function(overload, index, doctests, config);
     }
 }
 
 pub fn process_enum(
     enm: &mut parser::Enum,
-    index: &HashMap<String, String>,
+    index: &Index,
     doctests: &mut Vec<doctest::Doctest>,
     config: &Config,
 ) {
     if let Some(ref mut comment) = &mut enm.comment {
-        comment.brief = process_markdown(&comment.brief, index, doctests, config).content;
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
+        comment.description =
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+
+        let scope = enm.namespace.as_deref().unwrap_or_default();
+        process_see_also(comment, scope, index, doctests, config);
+        process_deprecation(comment, &mut enm.deprecated, scope, index, config);
+    }
+}
+
+pub fn process_alias(
+    alias: &mut parser::Alias,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    if let Some(ref mut comment) = &mut alias.comment {
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
+        comment.description =
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+
+        let scope = alias.namespace.as_deref().unwrap_or_default();
+        process_see_also(comment, scope, index, doctests, config);
+        process_deprecation(comment, &mut alias.deprecated, scope, index, config);
+    }
+}
+
+pub fn process_variable(
+    variable: &mut parser::Variable,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    if let Some(ref mut comment) = &mut variable.comment {
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
+        comment.description =
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+
+        let scope = variable.namespace.as_deref().unwrap_or_default();
+        process_see_also(comment, scope, index, doctests, config);
+        process_deprecation(comment, &mut variable.deprecated, scope, index, config);
+    }
+}
+
+pub fn process_concept(
+    concept: &mut parser::Concept,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    if let Some(ref mut comment) = &mut concept.comment {
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
         comment.description =
-            process_markdown(&comment.description, index, doctests, config).content;
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+
+        let scope = concept.namespace.as_deref().unwrap_or_default();
+        process_see_also(comment, scope, index, doctests, config);
+        process_deprecation(comment, &mut concept.deprecated, scope, index, config);
     }
 }
 
 pub fn process_record(
     record: &mut parser::Record,
-    index: &HashMap<String, String>,
+    index: &Index,
     doctests: &mut Vec<doctest::Doctest>,
     config: &Config,
 ) {
     if let Some(ref mut comment) = &mut record.comment {
-        comment.brief = process_markdown(&comment.brief, index, doctests, config).content;
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
         comment.description =
-            process_markdown(&comment.description, index, doctests, config).content;
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+
+        let qualified = parser::qualified_name(record.namespace.as_deref(), &record.name);
+
+        document_template_parameters(
+            comment,
+            record.template.as_mut(),
+            &qualified,
+            index,
+            doctests,
+            config,
+        );
+
+        // Members are named as in the record's own scope
+        process_see_also(comment, &qualified, index, doctests, config);
+        process_deprecation(comment, &mut record.deprecated, &qualified, index, config);
     }
 
     for method in &mut record.methods {
@@ -227,18 +1446,36 @@ pub fn process_record(
     for ctor in &mut record.ctor {
         process_function(ctor, index, doctests, config);
     }
+
+    for conversion in &mut record.conversions {
+        process_function(conversion, index, doctests, config);
+    }
+
+    for variable in &mut record.static_fields {
+        process_variable(variable, index, doctests, config);
+    }
+
+    for friend in &mut record.friends {
+        process_function(friend, index, doctests, config);
+    }
+
+    if let Some(ref mut dtor) = record.dtor {
+        process_function(dtor, index, doctests, config);
+    }
 }
 
 pub fn process_namespace(
     namespace: &mut parser::Namespace,
-    index: &HashMap<String, String>,
+    index: &Index,
     doctests: &mut Vec<doctest::Doctest>,
     config: &Config,
 ) {
     if let Some(ref mut comment) = &mut namespace.comment {
-        comment.brief = process_markdown(&comment.brief, index, doctests, config).content;
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
         comment.description =
-            process_markdown(&comment.description, index, doctests, config).content;
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
     }
 
     for func in &mut namespace.functions {
@@ -253,7 +1490,434 @@ pub fn process_namespace(
         process_enum(enm, index, doctests, config);
     }
 
+    for alias in &mut namespace.aliases {
+        process_alias(alias, index, doctests, config);
+    }
+
+    for variable in &mut namespace.variables {
+        process_variable(variable, index, doctests, config);
+    }
+
+    for concept in &mut namespace.concepts {
+        process_concept(concept, index, doctests, config);
+    }
+
     for ns in &mut namespace.namespaces {
         process_namespace(ns, index, doctests, config);
     }
 }
+
+pub fn process_group(
+    group: &mut parser::Group,
+    index: &Index,
+    doctests: &mut Vec<doctest::Doctest>,
+    config: &Config,
+) {
+    if let Some(ref mut comment) = &mut group.comment {
+        comment.brief = process_comment_markdown(&comment.brief, index, doctests, config).content;
+        comment.description =
+            process_comment_markdown(&comment.description, index, doctests, config).content;
+        process_notes(comment, index, doctests, config);
+        process_examples(comment, index, doctests, config);
+    }
+}
+
+/// Remove the namespaces of `namespace` (whose qualified name is `scope`) that have nothing
+/// documented, directly or in their own namespaces, along with their index entries
+pub fn prune_empty_namespaces(namespace: &mut parser::Namespace, scope: &str, index: &mut Index) {
+    let mut pruned = Vec::new();
+
+    namespace.namespaces.retain_mut(|ns| {
+        let name = parser::qualified_name(scope, &ns.name);
+
+        prune_empty_namespaces(ns, &name, index);
+
+        let empty = ns.records.is_empty()
+            && ns.functions.is_empty()
+            && ns.enums.is_empty()
+            && ns.aliases.is_empty()
+            && ns.variables.is_empty()
+            && ns.concepts.is_empty()
+            && ns.namespace_aliases.is_empty()
+            && ns.namespaces.is_empty();
+
+        if empty {
+            pruned.push(name);
+        }
+
+        !empty
+    });
+
+    for name in pruned {
+        let prefix = format!("{}::", name);
+        index.retain(|key, _| *key != name && !key.starts_with(&prefix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+
+    const OPERATORS: &[&str] = &[
+        "+", "-", "*", "/", "%", "^", "&", "|", "~", "!", "=", "<", ">", "+=", "-=", "*=", "/=",
+        "%=", "^=", "&=", "|=", "<<", ">>", ">>=", "<<=", "==", "!=", "<=", ">=", "<=>", "&&",
+        "||", "++", "--", ",", "->*", "->", "()", "[]", " new", " new[]", " delete",
+        " delete[]", " co_await", "\"\"_km",
+    ];
+
+    #[test]
+    fn operator_file_names_are_safe_and_distinct() {
+        let mut seen = std::collections::HashSet::new();
+
+        for op in OPERATORS {
+            let name = format!("operator{}", op);
+            let filename = sanitize_symbol_filename(&name);
+
+            assert!(
+                filename
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+                "unsafe file name {filename:?} for {name:?}"
+            );
+            assert!(seen.insert(filename.clone()), "{name:?} collides on {filename:?}");
+        }
+
+        assert_eq!(sanitize_symbol_filename("operator<<"), "operator-lt-lt");
+        assert_eq!(sanitize_symbol_filename("operator\"\"_kb"), "operator-udl-_kb");
+        assert_eq!(sanitize_symbol_filename("operator\"\" _kb"), "operator-udl-_kb");
+        assert_eq!(sanitize_symbol_filename("distance"), "distance");
+    }
+
+    #[test]
+    fn operator_links_match_their_pages() {
+        for op in OPERATORS {
+            let name = format!("operator{}", op);
+            let mut index = Index::default();
+            index.insert("math".to_string(), "namespace", None);
+            index.insert(format!("math::{}", name), "function", None);
+            index.insert(name.clone(), "function", None);
+
+            let filename = sanitize_symbol_filename(&name);
+
+            assert_eq!(
+                get_path_for_name(&format!("math::{}", name), &index),
+                Some(format!("math/function.{}", filename))
+            );
+            assert_eq!(
+                get_path_for_name(&name, &index),
+                Some(format!("function.{}", filename))
+            );
+
+            let displayed = escape_html(&name);
+            assert!(!displayed.contains('<') && !displayed.contains('>') && !displayed.contains('"'));
+        }
+    }
+
+    fn basic() -> (parser::Namespace, Index) {
+        let dump: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/basic/expected/output.json"))
+                .unwrap();
+
+        (
+            serde_json::from_value(dump["root"].clone()).unwrap(),
+            serde_json::from_value(dump["index"].clone()).unwrap(),
+        )
+    }
+
+    fn method_names(record: &parser::Record) -> Vec<(&str, Vec<&str>)> {
+        record
+            .inherited
+            .iter()
+            .map(|(base, methods)| {
+                (base.as_str(), methods.iter().map(|m| m.name.as_str()).collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_inherit_the_public_methods_of_their_bases() {
+        let (mut root, mut index) = basic();
+
+        let shape = root.records.iter().find(|r| r.name == "Shape").unwrap().clone();
+
+        // Square overrides `area`, Tile goes through both Circle and Square to Shape
+        let mut square = shape.clone();
+        square.name = "Square".to_string();
+        square.methods.retain(|m| m.name == "area");
+        square.bases = vec![parser::BaseClass {
+            name: "Shape".to_string(),
+            access: "public".to_string(),
+            virtual_: false,
+        }];
+
+        let mut tile = square.clone();
+        tile.name = "Tile".to_string();
+        tile.methods.clear();
+        tile.bases = ["Square", "Circle"]
+            .iter()
+            .map(|base| parser::BaseClass {
+                name: base.to_string(),
+                access: "public".to_string(),
+                virtual_: false,
+            })
+            .collect();
+
+        // A cycle, which can only come from a bad dump, must not hang
+        let mut ping = tile.clone();
+        ping.name = "Ping".to_string();
+        ping.bases[0].name = "Ping".to_string();
+
+        for record in [square, tile, ping] {
+            index.insert(record.name.clone(), "record", None);
+            root.records.push(record);
+        }
+
+        attach_inherited(&mut root, &index);
+
+        let record = |name: &str| root.records.iter().find(|r| r.name == name).unwrap();
+
+        assert_eq!(
+            method_names(record("Circle")),
+            vec![("Shape", vec!["area", "unit", "operator=="])]
+        );
+        assert_eq!(
+            method_names(record("Square")),
+            vec![("Shape", vec!["unit", "operator=="])]
+        );
+        assert_eq!(
+            method_names(record("Tile")),
+            vec![("Square", vec!["area"]), ("Shape", vec!["unit", "operator=="])]
+        );
+        assert_eq!(
+            method_names(record("Ping")),
+            vec![("Shape", vec!["area", "unit", "operator=="])]
+        );
+        assert!(record("Shape").inherited.is_empty());
+    }
+
+    #[test]
+    fn members_are_sorted_by_name_or_location() {
+        let (mut root, _) = basic();
+        let names = |ns: &parser::Namespace| {
+            ns.records.iter().map(|r| r.name.clone()).collect::<Vec<_>>()
+        };
+
+        root.records.reverse();
+        root.records[0].location = None;
+
+        sort_members(&mut root, "alphabetical");
+        assert_eq!(names(&root), ["Circle", "Flags", "Options", "Point", "Shape"]);
+
+        // Options lost its location, it goes last
+        sort_members(&mut root, "source");
+        assert_eq!(names(&root), ["Point", "Shape", "Circle", "Flags", "Options"]);
+    }
+
+    #[test]
+    fn variables_and_namespace_aliases_are_sorted_too() {
+        let (mut root, _) = basic();
+
+        for (name, line) in [("zeta", 3), ("alpha", 7)] {
+            let location = serde_json::json!({"file": "basic.hpp", "line": line});
+
+            root.variables.push(
+                serde_json::from_value(serde_json::json!({
+                    "name": name, "type": "int", "constexpr": false, "static": false,
+                    "inline": false, "location": location.clone(),
+                }))
+                .unwrap(),
+            );
+            root.namespace_aliases.push(
+                serde_json::from_value(serde_json::json!({
+                    "name": name, "target": "std::filesystem", "location": location,
+                }))
+                .unwrap(),
+            );
+        }
+
+        let variables = |ns: &parser::Namespace| {
+            ns.variables.iter().map(|v| v.name.clone()).collect::<Vec<_>>()
+        };
+        let aliases = |ns: &parser::Namespace| {
+            ns.namespace_aliases.iter().map(|a| a.name.clone()).collect::<Vec<_>>()
+        };
+
+        sort_members(&mut root, "alphabetical");
+        assert_eq!(variables(&root), ["alpha", "zeta"]);
+        assert_eq!(aliases(&root), ["alpha", "zeta"]);
+
+        sort_members(&mut root, "source");
+        assert_eq!(variables(&root), ["zeta", "alpha"]);
+        assert_eq!(aliases(&root), ["zeta", "alpha"]);
+    }
+
+    #[test]
+    fn tables_are_rendered_in_a_wrapper() {
+        let page = process_markdown(
+            "| Mode | Blocking |\n|:-----|---------:|\n| `sync` | yes |\n",
+            &Index::default(),
+            &mut Vec::new(),
+            &test_config(""),
+        );
+
+        let html = page.content.replace('\n', "");
+
+        assert!(html.starts_with("<div class=\"table-wrapper\"><table>"));
+        assert!(html.ends_with("</table></div>"));
+        assert!(html.contains("<th style=\"text-align: right\">Blocking</th>"));
+        assert!(html.contains("<td style=\"text-align: left\"><code>sync</code></td>"));
+    }
+
+    #[test]
+    fn math_is_wrapped_for_katex_outside_of_code() {
+        let render = |markdown: &str, output: &str| {
+            let config = test_config(output);
+            process_markdown(markdown, &Index::default(), &mut Vec::new(), &config).content
+        };
+
+        let katex = "math = \"katex\"";
+
+        assert_eq!(
+            render("Power $x^2 < y$ of `$HOME`", katex),
+            "<p>Power <span class=\"math\">x^2 &lt; y</span> of <code>$HOME</code></p>\n"
+        );
+        assert!(render("$$\\sum_i a_i$$", katex).contains("<div class=\"math\">\\sum_i a_i</div>"));
+        assert!(
+            render("```math\nE = mc^2\n```", katex).contains("<div class=\"math\">E = mc^2</div>")
+        );
+        assert_eq!(render("Power $x^2$", ""), "<p>Power $x^2$</p>\n");
+
+        // The TeX of comments isn't taken for HTML, but what markdown doesn't read as TeX is
+        let clean = |html: &str| clean_comment_html(html, &test_config(katex));
+        assert_eq!(clean("$a <b> c$ <b>"), "$a <b> c$ &lt;b>");
+        assert_eq!(
+            clean("\\$<img src=x onerror=alert(1)>$"),
+            "\\$&lt;img src=x onerror=alert(1)>$"
+        );
+        assert_eq!(clean("`$` <b>$"), "`$` &lt;b>$");
+    }
+
+    #[test]
+    fn sanitized_html_keeps_safe_tags_only() {
+        let sanitize = test_config("comment_html = \"sanitize\"");
+        let clean = |html: &str| clean_comment_html(html, &sanitize);
+
+        assert_eq!(clean("<b>bold</b> <br/>"), "<b>bold</b> <br/>");
+        assert_eq!(
+            clean("<a href=\"https://example.com\">x</a> <a href=\"../guide.html\">y</a>"),
+            "<a href=\"https://example.com\">x</a> <a href=\"../guide.html\">y</a>"
+        );
+
+        // Event handlers, even without whitespace before them, and any other attribute
+        assert_eq!(
+            clean("<a href=\"x\"onclick=\"alert(1)\">x</a>"),
+            "&lt;a href=\"x\"onclick=\"alert(1)\">x</a>"
+        );
+        assert_eq!(clean("<b onmouseover=alert(1)>x</b>"), "&lt;b onmouseover=alert(1)>x</b>");
+        assert_eq!(clean("<b style=\"color: red\">x</b>"), "&lt;b style=\"color: red\">x</b>");
+
+        // Other schemes, once the character references are decoded
+        for href in [
+            "javascript:alert(1)",
+            "jav&#x61;script:alert(1)",
+            "&#106;avascript:alert(1)",
+            "java&Tab;script:alert(1)",
+            "JavaScript&colon;alert(1)",
+            "data:text/html,x",
+        ] {
+            let html = format!("<a href=\"{href}\">x</a>");
+            assert_eq!(clean(&html), format!("&lt;a href=\"{href}\">x</a>"), "{href}");
+        }
+
+        assert_eq!(clean("<a href='vbscript:x'>"), "&lt;a href='vbscript:x'>");
+        assert_eq!(clean("<a href=javascript:x>"), "&lt;a href=javascript:x>");
+        assert_eq!(clean("<script>x</script>"), "&lt;script>x&lt;/script>");
+
+        // Markdown links too, in every mode but "allow"
+        for mode in ["", "comment_html = \"sanitize\""] {
+            let config = test_config(mode);
+            let render = |markdown: &str| {
+                process_comment_markdown(markdown, &Index::default(), &mut Vec::new(), &config)
+                    .content
+            };
+
+            for markdown in [
+                "[x](javascript:alert(1))",
+                "[x](JAVA\tSCRIPT:alert(1))",
+                "[x][1]\n\n[1]: data:text/html,x",
+            ] {
+                let html = render(markdown);
+                assert!(!html.contains("href"), "{markdown}: {html}");
+                assert!(html.contains('x'), "{markdown}: {html}");
+            }
+
+            assert!(render("[x](https://example.com)").contains("href=\"https://example.com\""));
+            assert!(render("[x](../guide.html)").contains("href=\"../guide.html\""));
+        }
+    }
+
+    #[test]
+    fn autolinks_are_kept_for_web_pages_only() {
+        for mode in ["", "comment_html = \"sanitize\""] {
+            let config = test_config(mode);
+            let clean = |html: &str| clean_comment_html(html, &config);
+
+            assert_eq!(clean("See <https://example.com/a?b>"), "See <https://example.com/a?b>");
+            assert_eq!(clean("<javascript:alert(1)>"), "&lt;javascript:alert(1)>");
+            assert_eq!(clean("<data:text/html,x>"), "&lt;data:text/html,x>");
+            assert_eq!(clean("A std::vector<int>"), "A std::vector&lt;int>");
+        }
+
+        // Escaped, the link markdown would have made is just text
+        let html = process_comment_markdown(
+            "<javascript:alert(1)>",
+            &Index::default(),
+            &mut Vec::new(),
+            &test_config(""),
+        );
+        assert!(!html.content.contains("href"));
+    }
+
+    #[test]
+    fn code_segments_are_found_like_commonmark() {
+        let code = |text: &'static str| {
+            code_segments(text)
+                .into_iter()
+                .filter(|(_, code)| *code)
+                .map(|(range, _)| &text[range])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(code("a `b` c"), ["`b`"]);
+        assert_eq!(code("a `` b ` c `` d"), ["`` b ` c ``"]);
+        assert_eq!(code("\\`a` b`"), ["` b`"]);
+        assert!(code("a ` b").is_empty());
+        assert!(code("a ``b` c").is_empty());
+
+        assert_eq!(code("~~~\n<b>\n~~~\nx"), ["~~~\n<b>\n~~~\n"]);
+        assert_eq!(code("````\n```\n````\nx"), ["````\n```\n````\n"]);
+        assert_eq!(code("```\n~~~\nx"), ["```\n~~~\nx"]);
+        assert!(code("    ```\n<b>\n").is_empty());
+    }
+
+    #[test]
+    fn unmatched_backticks_dont_hide_html() {
+        for mode in ["", "comment_html = \"sanitize\""] {
+            let config = test_config(mode);
+            let clean = |html: &str| clean_comment_html(html, &config);
+
+            assert_eq!(
+                clean("Note ` <img src=x onerror=alert(1)>"),
+                "Note ` &lt;img src=x onerror=alert(1)>"
+            );
+            assert_eq!(
+                clean("``a ` b`` <script>x</script>"),
+                "``a ` b`` &lt;script>x&lt;/script>"
+            );
+            assert_eq!(clean("`<script>` <script>"), "`<script>` &lt;script>");
+            assert_eq!(clean("~~~\n<script>\n~~~\n<script>"), "~~~\n<script>\n~~~\n&lt;script>");
+            assert_eq!(clean("    ```\n<script>\n"), "    ```\n&lt;script>\n");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::config::Config;
+use crate::report::report_warning;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rhai::{Dynamic, Engine, FnAccess, Scope, AST};
+
+fn call(
+    engine: &Engine,
+    ast: &AST,
+    config: &Dynamic,
+    script: &str,
+    name: &str,
+    value: &tera::Value,
+) -> tera::Result<tera::Value> {
+    let input = match value {
+        tera::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    // Scripts get the configuration as a constant so they can read but never modify it
+    let mut scope = Scope::new();
+    scope.push_constant("config", config.clone());
+
+    engine
+        .call_fn::<String>(&mut scope, ast, name, (input,))
+        .map(tera::Value::String)
+        .map_err(|e| tera::Error::msg(format!("{script}: error in function “{name}”: {e}")))
+}
+
+/// Load the Rhai script at `path` and register each of its public single-argument functions
+/// both as a Tera filter (`{{ x | name }}`) and as a Tera function (`{{ name(value=x) }}`).
+pub fn register(tera: &mut tera::Tera, path: &str, config: &Config) -> Result<(), String> {
+    let engine = Engine::new();
+
+    let ast = engine
+        .compile_file(PathBuf::from(path))
+        .map_err(|e| format!("{path}: {e}"))?;
+
+    let config = rhai::serde::to_dynamic(config).map_err(|e| format!("{path}: {e}"))?;
+
+    let engine = Arc::new(engine);
+    let ast = Arc::new(ast);
+    let script = path.to_string();
+
+    let functions = ast
+        .iter_functions()
+        .filter(|f| f.access != FnAccess::Private)
+        .map(|f| (f.name.to_string(), f.params.len()))
+        .collect::<Vec<_>>();
+
+    for (name, arity) in functions {
+        if arity != 1 {
+            report_warning(&format!(
+                "{path}: skipping function “{name}”, template helpers must take exactly one argument"
+            ));
+            continue;
+        }
+
+        {
+            let (engine, ast, config, script, name) = (
+                engine.clone(),
+                ast.clone(),
+                config.clone(),
+                script.clone(),
+                name.clone(),
+            );
+
+            tera.register_filter(
+                &name.clone(),
+                move |value: &tera::Value,
+                      _: &HashMap<String, tera::Value>|
+                      -> tera::Result<tera::Value> {
+                    call(&engine, &ast, &config, &script, &name, value)
+                },
+            );
+        }
+
+        {
+            let (engine, ast, config, script, name) = (
+                engine.clone(),
+                ast.clone(),
+                config.clone(),
+                script.clone(),
+                name.clone(),
+            );
+
+            tera.register_function(
+                &name.clone(),
+                move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
+                    let value = args.get("value").ok_or_else(|| {
+                        tera::Error::msg(format!(
+                            "{script}: function “{name}” expects a `value` argument"
+                        ))
+                    })?;
+
+                    call(&engine, &ast, &config, &script, &name, value)
+                },
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use std::error::Error;
+
+    #[test]
+    fn failing_helpers_report_the_script_and_function() {
+        let path = std::env::temp_dir().join(format!("cppdoc-helpers-{}.rhai", std::process::id()));
+        std::fs::write(
+            &path,
+            "fn upper(text) { text.to_upper() }\nfn shout(text) { throw \"too quiet\"; }\n",
+        )
+        .unwrap();
+        let script = path.to_str().unwrap();
+
+        let config = test_config("");
+
+        let mut tera = tera::Tera::default();
+        register(&mut tera, script, &config).unwrap();
+
+        let context = tera::Context::new();
+        assert_eq!(tera.render_str("{{ 'a' | upper }}", &context).unwrap(), "A");
+        assert_eq!(tera.render_str("{{ upper(value='b') }}", &context).unwrap(), "B");
+
+        // Tera wraps the error of the filter, the message is at the end of the chain
+        let error = tera.render_str("{{ 'a' | shout }}", &context).unwrap_err();
+        let mut messages = vec![error.to_string()];
+        let mut source = error.source();
+
+        while let Some(e) = source {
+            messages.push(e.to_string());
+            source = e.source();
+        }
+
+        let message = messages.join(": ");
+        assert!(message.contains(&format!("{script}: error in function “shout”")), "{message}");
+        assert!(message.contains("too quiet"), "{message}");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
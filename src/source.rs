@@ -0,0 +1,195 @@
+use crate::config::Config;
+use crate::manifest;
+use crate::render;
+use crate::report::report_warning;
+
+use pygmentize::HtmlFormatter;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+
+/// Default `output.max_source_size`, in bytes
+const DEFAULT_MAX_SOURCE_SIZE: u64 = 1024 * 1024;
+
+/// Path of a parsed file relative to `input.source_root`, the way source locations name it
+pub fn relative_path(file: &str, config: &Config) -> String {
+    let path = Path::new(file);
+    let path = match config.input.source_root {
+        Some(ref root) => path.strip_prefix(root).unwrap_or(path),
+        None => path,
+    };
+
+    path.to_string_lossy().into_owned()
+}
+
+/// Path of the source page of a file, relative to the `src/` directory of the output, or `None`
+/// for files outside `input.source_root` (absolute or `../` paths) that would be written out
+/// of it
+fn page_path(path: &str) -> Option<String> {
+    let mut parts = Vec::new();
+
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    Some(format!("{}.html", parts.join("/")))
+}
+
+/// Put every line of highlighted code in a `<span id="L<line>">`. Tokens spanning several lines
+/// (block comments, raw strings) have their spans closed at the end of each line and reopened
+/// on the next one, so that the line spans stay well nested.
+pub fn anchor_lines(code: &str) -> String {
+    let mut open: Vec<String> = Vec::new();
+    let mut out = String::new();
+
+    for (i, line) in code.trim_end_matches('\n').split('\n').enumerate() {
+        out.push_str(&format!("<span id=\"L{}\" class=\"line\">", i + 1));
+        out.push_str(&open.concat());
+        out.push_str(line);
+
+        let mut rest = line;
+
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>') else {
+                break;
+            };
+
+            let tag = &rest[start..start + end + 1];
+
+            if tag.starts_with("</span") {
+                open.pop();
+            } else if tag.starts_with("<span") {
+                open.push(tag.to_string());
+            }
+
+            rest = &rest[start + end + 1..];
+        }
+
+        out.push_str(&"</span>".repeat(open.len()));
+        out.push_str("</span>\n");
+    }
+
+    out
+}
+
+/// Highlight a source file, with an anchor on every line
+fn highlight(source: &str, file: &str, config: &Config) -> String {
+    let language = match config.input.language.as_deref() {
+        Some("c") => "c",
+        _ => "cpp",
+    };
+
+    let code = match pygmentize::highlight(source, Some(language), &HtmlFormatter::new()) {
+        Ok(html) => {
+            // Only the content of the `<div class="highlight"><pre>` wrapper is split in lines
+            let start = html.find("<pre>").map(|i| i + "<pre>".len()).unwrap_or(0);
+            let end = html.rfind("</pre>").unwrap_or(html.len());
+
+            html[start..end].to_string()
+        }
+        Err(_) => {
+            report_warning(&format!("Unable to create syntax highlighting for “{file}”"));
+            render::escape_html(source)
+        }
+    };
+
+    format!(
+        "<div class=\"highlight source\"><pre>{}</pre></div>\n",
+        anchor_lines(&code)
+    )
+}
+
+/// Write a highlighted page, `src/<path>.html`, for every parsed file (`output.emit_source`).
+/// Files over `output.max_source_size` are skipped. Returns the paths, relative to
+/// `input.source_root`, that got a page.
+pub fn output_source_pages(
+    files: &BTreeMap<String, Vec<String>>,
+    config: &Config,
+    tera: &tera::Tera,
+    context: &mut tera::Context,
+    site: &crate::templates::Site,
+) -> HashSet<String> {
+    let max_size = config.output.max_source_size.unwrap_or(DEFAULT_MAX_SOURCE_SIZE);
+    let mut written = HashSet::new();
+
+    for file in files.keys() {
+        let size = std::fs::metadata(file).map(|m| m.len()).unwrap_or_default();
+
+        if size > max_size {
+            report_warning(&format!(
+                "Skipping the source page of “{file}”: it is {size} bytes, over the {max_size} bytes limit of output.max_source_size"
+            ));
+            continue;
+        }
+
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                report_warning(&format!("Error reading source file “{file}”: {e}"));
+                continue;
+            }
+        };
+
+        let path = relative_path(file, config);
+
+        let Some(page) = page_path(&path) else {
+            report_warning(&format!(
+                "Skipping the source page of “{file}”: it is outside input.source_root"
+            ));
+            continue;
+        };
+
+        context.insert("title", &path);
+        context.insert("content", &highlight(&source, file, config));
+
+        let html = match crate::templates::render(tera, "docpage", context, &path, site) {
+            Ok(html) => html,
+            Err(_) => continue,
+        };
+
+        let dest = Path::new(&config.output.path).join("src").join(page);
+
+        let result = std::fs::create_dir_all(dest.parent().unwrap())
+            .and_then(|_| manifest::write(&site.manifest, &dest, html, manifest::Category::Source));
+
+        match result {
+            Ok(()) => {
+                written.insert(path);
+            }
+            Err(e) => {
+                report_warning(&format!("Error writing source page “{}”: {e}", dest.display()))
+            }
+        }
+    }
+
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiline_tokens_stay_nested_in_line_anchors() {
+        let code = "<span class=\"cm\">/* a\nb */</span>\n<span class=\"kt\">int</span> x;\n";
+
+        assert_eq!(
+            anchor_lines(code),
+            "<span id=\"L1\" class=\"line\"><span class=\"cm\">/* a</span></span>\n\
+             <span id=\"L2\" class=\"line\"><span class=\"cm\">b */</span></span>\n\
+             <span id=\"L3\" class=\"line\"><span class=\"kt\">int</span> x;</span>\n"
+        );
+    }
+
+    #[test]
+    fn files_outside_the_source_root_get_no_page() {
+        assert_eq!(page_path("include/a.hpp").as_deref(), Some("include/a.hpp.html"));
+        assert_eq!(page_path("./include/a.hpp").as_deref(), Some("include/a.hpp.html"));
+        assert_eq!(page_path("/usr/include/stdio.h"), None);
+        assert_eq!(page_path("../vendor/lib.hpp"), None);
+        assert_eq!(page_path("include/../../lib.hpp"), None);
+    }
+}
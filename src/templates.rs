@@ -1,8 +1,13 @@
 use crate::config::Config;
+use crate::filter;
+use crate::index::Index;
+use crate::manifest;
 use crate::parser;
+use crate::parser::qualified_name;
 use crate::render;
+use crate::report::report_error;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use tera::Tera;
 
@@ -16,6 +21,213 @@ const FUNCTION_TEMPLATE: &str = include_str!("templates/function.html");
 const ENUM_TEMPLATE: &str = include_str!("templates/enum.html");
 const SEARCH_TEMPLATE: &str = include_str!("templates/search.html");
 const ALIAS_TEMPLATE: &str = include_str!("templates/alias.html");
+const CONCEPT_TEMPLATE: &str = include_str!("templates/concept.html");
+const VARIABLE_TEMPLATE: &str = include_str!("templates/variable.html");
+const MATH_TEMPLATE: &str = include_str!("templates/math.html");
+
+/// What the pages show about the rest of the documentation, gathered once the input is parsed
+/// and passed to every page along with the index
+#[derive(Default)]
+pub struct Site {
+    /// Files with a source page (`output.emit_source`), relative to `input.source_root`
+    pub source_pages: HashSet<String>,
+
+    /// Documented base classes of every documented record, by qualified name
+    pub base_classes: HashMap<String, Vec<String>>,
+
+    /// The `@defgroup` groups, for their pages and the Topics section of the root index page
+    pub groups: Vec<parser::Group>,
+
+    /// The entities naming each entity in their `@impl`, by qualified name
+    pub implementers: BTreeMap<String, Vec<String>>,
+
+    /// Title and link of the page of the innermost documented directory of every entity
+    pub dir_pages: HashMap<String, (String, String)>,
+
+    /// Entity whose rendering context should be dumped, and the file to dump it to (stdout if
+    /// `None`)
+    pub debug_context: Option<(String, Option<String>)>,
+
+    /// Files written so far, for `manifest.json` and `--prune`
+    pub manifest: manifest::Recorder,
+}
+
+/// Title of a group, its name when `@defgroup` gives none
+pub fn group_title(group: &parser::Group) -> &str {
+    if group.title.is_empty() {
+        &group.name
+    } else {
+        &group.title
+    }
+}
+
+/// Resolve the base classes of all records to documented records, for the inheritance chains
+pub fn base_classes(root: &parser::Namespace, index: &Index) -> HashMap<String, Vec<String>> {
+    fn walk_record(
+        record: &parser::Record,
+        scope: &str,
+        index: &Index,
+        out: &mut HashMap<String, Vec<String>>,
+    ) {
+        let name = qualified_name(scope, &record.name);
+
+        let bases = record
+            .bases
+            .iter()
+            .filter_map(|base| render::resolve_record(&base.name, scope, index))
+            .collect();
+
+        for nested in record.nested.iter().flatten() {
+            if let parser::NestedField::Record(nested) = nested {
+                walk_record(nested, &name, index, out);
+            }
+        }
+
+        out.insert(name, bases);
+    }
+
+    fn walk(
+        namespace: &parser::Namespace,
+        scope: &str,
+        index: &Index,
+        out: &mut HashMap<String, Vec<String>>,
+    ) {
+        for record in &namespace.records {
+            walk_record(record, scope, index, out);
+        }
+
+        for ns in &namespace.namespaces {
+            let scope = qualified_name(scope, &ns.name);
+            walk(ns, &scope, index, out);
+        }
+    }
+
+    let mut bases = HashMap::new();
+    walk(root, "", index, &mut bases);
+
+    bases
+}
+
+/// Aliased namespace of every namespace alias, by qualified name of the alias
+pub fn namespace_aliases(root: &parser::Namespace) -> HashMap<String, String> {
+    fn walk(namespace: &parser::Namespace, out: &mut HashMap<String, String>) {
+        for alias in &namespace.namespace_aliases {
+            out.insert(
+                qualified_name(alias.namespace.as_deref(), &alias.name),
+                alias.target.clone(),
+            );
+        }
+
+        for ns in &namespace.namespaces {
+            walk(ns, out);
+        }
+    }
+
+    let mut aliases = HashMap::new();
+    walk(root, &mut aliases);
+
+    aliases
+}
+
+/// Expand the namespace alias a qualified type name starts with, looking it up from `scope`
+/// outwards like the compiler: `v::Widget` is `mylib::v2::Widget` after
+/// `namespace v = mylib::v2;`
+fn expand_namespace_alias(name: &str, scope: &str, index: &Index) -> Option<String> {
+    // `::v::Widget` can only be a global alias
+    let (name, scope) = match name.strip_prefix("::") {
+        Some(name) => (name, ""),
+        None => (name, scope),
+    };
+
+    let (head, rest) = name.split_once("::")?;
+    let alias = index.resolve_with(head, scope, |candidate| {
+        index.namespace_alias_target(candidate).is_some()
+    })?;
+
+    Some(format!("{}::{}", index.namespace_alias_target(&alias)?, rest))
+}
+
+/// Every path from a root base class down to `name`, a diamond gives two paths
+fn inheritance_paths(name: &str, bases: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    fn walk(
+        name: &str,
+        bases: &HashMap<String, Vec<String>>,
+        path: &mut Vec<String>,
+        out: &mut Vec<Vec<String>>,
+    ) {
+        path.push(name.to_string());
+
+        // A base already on the path would loop forever (only possible with broken code)
+        let parents = bases
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter(|p| !path.contains(p))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if parents.is_empty() {
+            out.push(path.iter().rev().cloned().collect());
+        }
+
+        for parent in parents {
+            walk(&parent, bases, path, out);
+        }
+
+        path.pop();
+    }
+
+    let mut out = Vec::new();
+    walk(name, bases, &mut Vec::new(), &mut out);
+
+    // A record without documented bases has no chain to show
+    out.retain(|path| path.len() > 1);
+    out
+}
+
+/// The message of a failed rendering, with the causes of `error` on the following lines
+fn render_error(template: &str, entity: &str, error: &tera::Error) -> String {
+    let mut message = format!("Error rendering template “{template}” for “{entity}”: {error}");
+    let mut source = std::error::Error::source(error);
+
+    while let Some(cause) = source {
+        message.push_str(&format!("\n  caused by: {cause}"));
+        source = std::error::Error::source(cause);
+    }
+
+    message
+}
+
+/// Render `template` for the entity `entity`, reporting which template and entity failed along
+/// with the whole Tera error chain, since the top-level error alone is rarely helpful.
+pub fn render(
+    tera: &Tera,
+    template: &str,
+    context: &tera::Context,
+    entity: &str,
+    site: &Site,
+) -> Result<String, tera::Error> {
+    if let Some((name, output)) = &site.debug_context {
+        if name == entity {
+            let json = serde_json::to_string_pretty(&context.clone().into_json()).unwrap();
+
+            match output {
+                Some(path) => {
+                    match std::fs::write(path, json) {
+                        Ok(()) => site.manifest.keep(path),
+                        Err(e) => report_error(&format!(
+                            "Error writing context of “{entity}” to {path}: {e}"
+                        )),
+                    }
+                }
+                None => println!("{}", json),
+            }
+        }
+    }
+
+    tera.render(template, context)
+        .inspect_err(|e| report_error(&render_error(template, entity, e)))
+}
 
 fn cleanup_type(type_: &str) -> String {
     // Lmao
@@ -23,40 +235,210 @@ fn cleanup_type(type_: &str) -> String {
     type_.replace(" &", "</span>&").replace(" *", "</span>*")
 }
 
-fn tera_output_template(index: HashMap<String, String>, config: Config) -> impl tera::Function {
-    Box::new(
-        move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
-            let templ = args.get("template").unwrap().as_object().unwrap();
-            let namespace = args.get("namespace").unwrap().as_str().unwrap();
+/// Position of the parenthesis closing the one at `open`
+fn matching_paren(type_: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
 
-            let mut prefix = String::new();
-            prefix.push_str("<span class=\"k\">template</span> &lt;");
+    for (i, c) in type_[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
 
-            let params = templ.get("parameters").unwrap().as_array().unwrap();
-            let params_length = params.len();
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
 
-            for (i, param) in params.iter().enumerate() {
-                let type_ = param.get("type").unwrap().as_str().unwrap();
-                prefix.push_str(&format!(
-                    "{} {}",
-                    get_link_for_type(type_, namespace, &config, &index)
-                        .unwrap_or(format!("<span class=\"kt\">{}</span>", cleanup_type(type_))),
-                    param.get("name").unwrap().as_str().unwrap()
-                ));
+    None
+}
+
+/// Link a type, or highlight it when it isn't documented
+fn linked_type(
+    type_: &str,
+    namespace: &str,
+    config: &Config,
+    index: &Index,
+) -> String {
+    let type_ = type_.trim();
+
+    get_link_for_type(type_, namespace, config, index).unwrap_or(format!(
+        "<span class=\"kt\">{}</span>",
+        cleanup_type(&render::escape_html(type_))
+    ))
+}
+
+/// A type with the name of the declared entity at its place: `char buffer[256]`,
+/// `void (*callback)(int, void *)` or `int (Widget::*getter)() const`
+fn declaration(
+    type_: &str,
+    name: &str,
+    namespace: &str,
+    config: &Config,
+    index: &Index,
+) -> String {
+    // Pointers (or references) to functions and arrays: `R (*)(Args...)`, `T (*)[N]`. The
+    // parentheses of template arguments (`std::function<void(int)>`) don't count.
+    let mut depth = 0;
+    let open = type_.char_indices().find_map(|(i, c)| {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            '(' if depth == 0 => return Some(i),
+            _ => {}
+        }
+
+        None
+    });
+
+    let declarator = open.and_then(|open| {
+        let close = matching_paren(type_, open)?;
+        let inner = &type_[open + 1..close];
+
+        inner.contains(['*', '&']).then_some((open, close, inner))
+    });
+
+    if let Some((open, close, inner)) = declarator {
+        let tail = &type_[close + 1..];
+
+        // The name goes right after the last `*`/`&`, before the size of arrays of pointers
+        let at = inner.rfind(['*', '&']).map(|i| i + 1).unwrap_or(0);
+        let (pointer, array) = inner.split_at(at);
+
+        // Pointers to members, `Widget::*`
+        let pointer = match pointer.rfind("::") {
+            Some(i) => format!(
+                "{}{}",
+                linked_type(&pointer[..i], namespace, config, index),
+                render::escape_html(&pointer[i..])
+            ),
+            None => render::escape_html(pointer),
+        };
 
-                if i < params_length - 1 {
-                    prefix.push_str(", ");
+        let params = if tail.starts_with('(') {
+            matching_paren(tail, 0).map(|end| (&tail[1..end], &tail[end + 1..]))
+        } else {
+            None
+        };
+
+        let tail = match params {
+            Some((params, qualifiers)) => {
+                let mut rendered = Vec::new();
+                let mut depth = 0;
+                let mut start = 0;
+
+                for (i, c) in params.char_indices() {
+                    match c {
+                        '<' | '(' | '[' => depth += 1,
+                        '>' | ')' | ']' => depth -= 1,
+                        ',' if depth == 0 => {
+                            rendered.push(linked_type(&params[start..i], namespace, config, index));
+                            start = i + 1;
+                        }
+                        _ => {}
+                    }
+                }
+
+                if !params[start..].trim().is_empty() {
+                    rendered.push(linked_type(&params[start..], namespace, config, index));
                 }
+
+                format!("({}){}", rendered.join(", "), render::escape_html(qualifiers))
             }
+            None => render::escape_html(tail),
+        };
+
+        return format!(
+            "{} ({}{}{}){}",
+            linked_type(&type_[..open], namespace, config, index),
+            pointer,
+            name,
+            render::escape_html(array),
+            tail
+        );
+    }
+
+    // Arrays, the size goes after the name: `char buffer[256]`
+    if type_.ends_with(']') {
+        if let Some(open) = type_.find('[') {
+            return format!(
+                "{} {}{}",
+                linked_type(&type_[..open], namespace, config, index),
+                name,
+                render::escape_html(&type_[open..])
+            );
+        }
+    }
+
+    format!("{} {}", linked_type(type_, namespace, config, index), name)
+}
+
+/// ` : 3` after the name of a bit-field
+fn bit_width_suffix(bit_width: Option<u64>) -> String {
+    match bit_width {
+        Some(width) => format!(" : <span class=\"m\">{}</span>", width),
+        None => String::new(),
+    }
+}
+
+/// ` = 3` after a field with a default member initializer, cut when it is too long
+fn field_initializer(default_value: Option<&str>, config: &Config) -> String {
+    let Some(default_value) = default_value else {
+        return String::new();
+    };
+
+    let max_length = config.output.max_initializer_length.unwrap_or(40);
+
+    let value = if default_value.chars().count() > max_length {
+        format!("{}…", default_value.chars().take(max_length).collect::<String>())
+    } else {
+        default_value.to_string()
+    };
+
+    format!(" = {}", render::escape_html(&value))
+}
+
+/// ` = std::allocator<T>` after a template parameter, linked when the default is a type
+fn template_default(
+    type_: &str,
+    default_value: Option<&str>,
+    namespace: &str,
+    config: &Config,
+    index: &Index,
+) -> String {
+    let Some(default_value) = default_value else {
+        return String::new();
+    };
+
+    let escaped = render::escape_html(default_value);
+
+    if type_ != "typename" && type_ != "template" {
+        return format!(" = {}", escaped);
+    }
+
+    format!(
+        " = {}",
+        get_link_for_type(default_value, namespace, config, index)
+            .unwrap_or(format!("<span class=\"kt\">{}</span>", cleanup_type(&escaped)))
+    )
+}
 
-            prefix.push_str("&gt; ");
+/// `output_template(template, namespace)`, the `template_prefix` of a template in a template
+fn tera_output_template(index: Index, config: Config) -> impl tera::Function {
+    Box::new(
+        move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
+            let templ: parser::Template = tera::from_value(args.get("template").unwrap().clone())?;
+            let namespace = args.get("namespace").unwrap().as_str().unwrap();
 
-            Ok(tera::to_value(prefix).unwrap())
+            Ok(tera::to_value(template_prefix(&templ, namespace, &config, &index)).unwrap())
         },
     )
 }
 
-fn tera_get_link_for_namespace(index: HashMap<String, String>) -> impl tera::Function {
+fn tera_get_link_for_namespace(index: Index) -> impl tera::Function {
     Box::new(
         move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
             let namespace = args.get("namespace").unwrap().as_str().unwrap();
@@ -113,7 +495,7 @@ fn tera_get_link_for_namespace(index: HashMap<String, String>) -> impl tera::Fun
     )
 }
 
-fn tera_output_struct(index: HashMap<String, String>, config: Config) -> impl tera::Function {
+fn tera_output_struct(index: Index, config: Config) -> impl tera::Function {
     Box::new(
         move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
             let struct_ = args.get("struct").unwrap().as_object().unwrap();
@@ -139,15 +521,15 @@ fn tera_output_struct(index: HashMap<String, String>, config: Config) -> impl te
                     let field = field.as_object().unwrap();
                     let type_ = field.get("type").unwrap().as_str().unwrap();
                     let name = field.get("name").unwrap().as_str().unwrap();
+                    let bit_width = field.get("bit_width").and_then(|w| w.as_u64());
+                    let default_value = field.get("default_value").and_then(|d| d.as_str());
 
                     listing.push_str("  ");
                     listing.push_str(&format!(
-                        "{} {};",
-                        get_link_for_type(type_, namespace, &config, &index).unwrap_or(format!(
-                            "<span class=\"kt\">{}</span>",
-                            cleanup_type(type_)
-                        )),
-                        name
+                        "{}{}{};",
+                        declaration(type_, name, namespace, &config, &index),
+                        bit_width_suffix(bit_width),
+                        field_initializer(default_value, &config)
                     ));
 
                     if i < fields_count - 1 {
@@ -202,7 +584,90 @@ fn tera_output_struct(index: HashMap<String, String>, config: Config) -> impl te
     )
 }
 
-pub fn init(index: &HashMap<String, String>, config: &Config) -> Tera {
+/// ` noexcept(noexcept(T::swap))` or ` throw(std::bad_alloc)` after a parameter list, with the
+/// names it mentions linked when they are documented
+fn exception_spec(
+    spec: &str,
+    namespace: &str,
+    config: &Config,
+    index: &Index,
+) -> String {
+    fn flush(
+        word: &mut String,
+        ret: &mut String,
+        namespace: &str,
+        config: &Config,
+        index: &Index,
+    ) {
+        if word.is_empty() {
+            return;
+        }
+
+        match word.as_str() {
+            "noexcept" | "throw" | "sizeof" | "true" | "false" => {
+                ret.push_str(&format!("<span class=\"k\">{}</span>", word))
+            }
+            _ => ret.push_str(
+                &get_link_for_type(word, namespace, config, index)
+                    .unwrap_or_else(|| render::escape_html(word)),
+            ),
+        }
+
+        word.clear();
+    }
+
+    let mut ret = String::from(" ");
+    let mut word = String::new();
+
+    for c in spec.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut ret, namespace, config, index);
+            ret.push_str(&render::escape_html(&c.to_string()));
+        }
+    }
+
+    flush(&mut word, &mut ret, namespace, config, index);
+
+    ret
+}
+
+fn tera_output_exception_spec(index: Index, config: Config) -> impl tera::Function {
+    Box::new(
+        move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
+            let spec = args.get("spec").unwrap().as_str().unwrap();
+            let namespace = args.get("namespace").unwrap().as_str().unwrap();
+
+            Ok(tera::to_value(exception_spec(spec, namespace, &config, &index)).unwrap())
+        },
+    )
+}
+
+fn tera_output_default_value(index: Index, config: Config) -> impl tera::Function {
+    Box::new(
+        move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
+            let value = args.get("value").unwrap().as_str().unwrap();
+            let namespace = args.get("namespace").unwrap().as_str().unwrap();
+
+            // Defaults naming another entity (a constant, an enum...) link to it
+            let is_name = value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+                && !value.starts_with(|c: char| c.is_ascii_digit());
+
+            if is_name {
+                if let Some(link) = get_link_for_type(value, namespace, &config, &index) {
+                    return Ok(tera::to_value(format!(" = {}", link)).unwrap());
+                }
+            }
+
+            Ok(tera::to_value(format!(" = {}", render::escape_html(value))).unwrap())
+        },
+    )
+}
+
+pub fn init(index: &Index, config: &Config) -> Tera {
     let mut tera = Tera::default();
     tera.add_raw_templates(vec![
         ("macros", MACROS),
@@ -215,6 +680,9 @@ pub fn init(index: &HashMap<String, String>, config: &Config) -> Tera {
         ("enum", ENUM_TEMPLATE),
         ("search", SEARCH_TEMPLATE),
         ("alias", ALIAS_TEMPLATE),
+        ("concept", CONCEPT_TEMPLATE),
+        ("variable", VARIABLE_TEMPLATE),
+        ("math", MATH_TEMPLATE),
     ])
     .unwrap();
 
@@ -230,18 +698,313 @@ pub fn init(index: &HashMap<String, String>, config: &Config) -> Tera {
         "output_struct",
         tera_output_struct(index.clone(), config.clone()),
     );
+    tera.register_function(
+        "output_default_value",
+        tera_output_default_value(index.clone(), config.clone()),
+    );
+    tera.register_function(
+        "output_exception_spec",
+        tera_output_exception_spec(index.clone(), config.clone()),
+    );
     tera.register_function(
         "get_link_for_namespace",
         tera_get_link_for_namespace(index.clone()),
     );
 
+    // `Config::new` refuses a script without the `scripting` feature
+    #[cfg(feature = "scripting")]
+    if let Some(ref script) = config.output.template_script {
+        if let Err(e) = crate::script::register(&mut tera, script, config) {
+            crate::report::report_error(&format!("Error loading template script: {}", e));
+            std::process::exit(1);
+        }
+    }
+
     tera
 }
 
+/// The "Defined in" text of an entity, `include/mylib/socket.hpp:42`
+fn defined_in(location: &Option<parser::SourceLocation>) -> Option<String> {
+    location.as_ref().map(|l| format!("{}:{}", l.file, l.line))
+}
+
+/// Link of the "Defined in" line, to the line on the source page of the file
+fn source_link(
+    location: &Option<parser::SourceLocation>,
+    config: &Config,
+    site: &Site,
+) -> Option<String> {
+    let location = location.as_ref()?;
+
+    site.source_pages.contains(&location.file).then(|| {
+        format!("{}/src/{}.html#L{}", config.output.base_url, location.file, location.line)
+    })
+}
+
+/// Link to the page of the directory declaring the entity `name`, when it's documented
+fn directory_link(name: &str, site: &Site) -> Option<String> {
+    let (title, link) = site.dir_pages.get(name)?;
+
+    Some(format!("<a href=\"{link}\"><code>{}</code></a>", render::escape_html(title)))
+}
+
+/// The copyable `#include <mylib/buffer.hpp>` line at the top of the pages of namespace-level
+/// entities
+fn include_line(include_path: &Option<String>) -> Option<String> {
+    include_path
+        .as_ref()
+        .map(|path| format!("#include <{path}>"))
+}
+
+/// The "Only available when MYLIB_ENABLE_TLS is defined" note of the entities that only some
+/// `input.configurations` declare
+fn availability(available_in: &[String], config: &Config) -> Option<String> {
+    if available_in.is_empty() {
+        return None;
+    }
+
+    let conditions = available_in
+        .iter()
+        .map(|name| {
+            let defines = config
+                .input
+                .configurations
+                .iter()
+                .flatten()
+                .find(|c| &c.name == name)
+                .map(|c| {
+                    c.defines
+                        .iter()
+                        .map(|d| d.split_once('=').map_or(d.as_str(), |(macro_, _)| macro_))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            match defines.len() {
+                0 => format!("in the {name} configuration"),
+                1 => format!("when {} is defined", defines[0]),
+                _ => format!("when {} are defined", defines.join(" and ")),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Some(format!("Only available {}", conditions.join(" or ")))
+}
+
+/// Message of the deprecation banner of an entity, as HTML: its `@deprecated` one with the
+/// symbols it names linked, else the one of its `[[deprecated]]` attribute
+fn deprecation(deprecated: &Option<String>, comment: &Option<parser::Comment>) -> Option<String> {
+    let deprecated = deprecated.as_ref()?;
+
+    match comment.as_ref().and_then(|c| c.deprecated.as_ref()) {
+        Some(message) if !message.is_empty() => Some(message.clone()),
+        _ => Some(render::escape_html(deprecated)),
+    }
+}
+
+/// The `@since` badge of an entity, styled as new when its version is newer than
+/// `output.highlight_since` or, without one, when it's `project.current_version`
+fn since_badge(comment: &Option<parser::Comment>, config: &Config) -> Option<String> {
+    let since = comment.as_ref()?.since.as_deref()?;
+    let scheme = config.project.version_scheme.as_deref().unwrap_or("semver");
+
+    let new = match (&config.output.highlight_since, &config.project.current_version) {
+        (Some(threshold), _) => filter::compare_versions(since, threshold, scheme).is_gt(),
+        (None, Some(current)) => filter::compare_versions(since, current, scheme).is_ge(),
+        (None, None) => false,
+    };
+
+    Some(format!(
+        "<span class=\"since{}\" title=\"Since {1}\">{1}</span>",
+        if new { " new" } else { "" },
+        render::escape_html(since)
+    ))
+}
+
+/// The availability note of a record member only some `input.configurations` declare, as a
+/// comment after its line of the listing
+fn availability_comment(available_in: &[String], config: &Config) -> String {
+    availability(available_in, config)
+        .map(|note| format!(" <span class=\"c\">// {}</span>", note))
+        .unwrap_or_default()
+}
+
+/// The `@since` badges of the members of a namespace, by name, for its listings
+fn since_badges(namespace: &parser::Namespace, config: &Config) -> HashMap<String, String> {
+    let comments = namespace
+        .functions
+        .iter()
+        .map(|f| (&f.name, &f.comment))
+        .chain(namespace.records.iter().map(|r| (&r.name, &r.comment)))
+        .chain(namespace.enums.iter().map(|e| (&e.name, &e.comment)))
+        .chain(namespace.aliases.iter().map(|a| (&a.name, &a.comment)))
+        .chain(namespace.variables.iter().map(|v| (&v.name, &v.comment)))
+        .chain(namespace.concepts.iter().map(|c| (&c.name, &c.comment)))
+        .chain(namespace.namespaces.iter().map(|n| (&n.name, &n.comment)));
+
+    comments
+        .filter_map(|(name, comment)| Some((name.clone(), since_badge(comment, config)?)))
+        .collect()
+}
+
+/// The "See also" list of an entity, from its `@see` entries
+fn see_also_list(comment: &Option<parser::Comment>) -> Option<String> {
+    let entries = &comment.as_ref()?.see_also;
+
+    (!entries.is_empty()).then(|| {
+        let items = entries
+            .iter()
+            .map(|entry| format!("<li>{entry}</li>"))
+            .collect::<String>();
+
+        format!("<ul class=\"see-also\">{items}</ul>")
+    })
+}
+
+/// The "Implements" list of an entity, linking to the entities its `@impl`s name
+fn implements_list(
+    comment: &Option<parser::Comment>,
+    config: &Config,
+    index: &Index,
+) -> Option<String> {
+    let names = comment.as_ref()?.impl_.as_ref().filter(|names| !names.is_empty())?;
+
+    let items = names
+        .iter()
+        .map(|name| format!("<li>{}</li>", entity_link(name, config, index)))
+        .collect::<String>();
+
+    Some(format!("<ul class=\"implements\">{items}</ul>"))
+}
+
+/// The "Implemented by" list of the entity `name`, linking to the entities naming it in their
+/// `@impl`
+fn implemented_by_list(
+    name: &str,
+    config: &Config,
+    index: &Index,
+    site: &Site,
+) -> Option<String> {
+    let names = site.implementers.get(name)?;
+
+    let items = names
+        .iter()
+        .map(|name| format!("<li>{}</li>", entity_link(name, config, index)))
+        .collect::<String>();
+
+    Some(format!("<ul class=\"implemented-by\">{items}</ul>"))
+}
+
+/// The Template parameters table of a function or record, `None` when none of its template
+/// parameters is documented
+fn template_parameters_table(template: &Option<parser::Template>) -> Option<String> {
+    let rows = template
+        .iter()
+        .flat_map(|t| &t.parameters)
+        .filter_map(|parameter| {
+            let comment = parameter.comment.as_ref()?;
+            let pack = if parameter.is_pack { "..." } else { "" };
+
+            Some(format!(
+                "<tr><td><code>{}{pack}</code></td><td>{}</td></tr>",
+                render::escape_html(&parameter.name),
+                comment.description
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    (!rows.is_empty()).then(|| {
+        format!(
+            "<table class=\"template-parameters\"><thead><tr><th>Template parameter</th>\
+             <th>Description</th></tr></thead><tbody>{}</tbody></table>",
+            rows.concat()
+        )
+    })
+}
+
+/// The Parameters table of a function, `None` when none of its parameters is documented
+fn parameters_table(function: &parser::Function) -> Option<String> {
+    let rows = function
+        .parameters
+        .iter()
+        .filter_map(|parameter| {
+            let comment = parameter.comment.as_ref()?;
+
+            Some(format!(
+                "<tr><td><code>{}</code></td><td>{}</td></tr>",
+                render::escape_html(&parameter.name),
+                comment.description
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    (!rows.is_empty()).then(|| {
+        format!(
+            "<table class=\"parameters\"><thead><tr><th>Parameter</th><th>Description</th></tr>\
+             </thead><tbody>{}</tbody></table>",
+            rows.concat()
+        )
+    })
+}
+
+/// The Returns section of a function: its `@return` and a table of its `@retval`s
+fn returns_section(function: &parser::Function) -> Option<String> {
+    let comment = function.comment.as_ref()?;
+    let mut section = comment.returns.clone().unwrap_or_default();
+
+    if !comment.retvals.is_empty() {
+        let rows = comment
+            .retvals
+            .iter()
+            .map(|(value, description)| {
+                format!("<tr><td>{value}</td><td>{description}</td></tr>")
+            })
+            .collect::<String>();
+
+        section.push_str(&format!(
+            "<table class=\"retvals\"><thead><tr><th>Value</th><th>Meaning</th></tr></thead>\
+             <tbody>{rows}</tbody></table>"
+        ));
+    }
+
+    (!section.is_empty()).then_some(section)
+}
+
+/// The Exceptions section of a function, a table of its `@throws` with the types linked
+fn exceptions_section(
+    function: &parser::Function,
+    config: &Config,
+    index: &Index,
+) -> Option<String> {
+    let comment = function.comment.as_ref()?;
+
+    if comment.throws.is_empty() {
+        return None;
+    }
+
+    let namespace = function.namespace.as_deref().unwrap_or_default();
+    let rows = comment
+        .throws
+        .iter()
+        .map(|(type_, description)| {
+            let type_ = linked_type(type_, namespace, config, index);
+
+            format!("<tr><td><code>{type_}</code></td><td>{description}</td></tr>")
+        })
+        .collect::<String>();
+
+    Some(format!(
+        "<table class=\"exceptions\"><thead><tr><th>Exception</th><th>Description</th></tr></thead>\
+         <tbody>{rows}</tbody></table>"
+    ))
+}
+
 pub fn output_function(
     function: &parser::Function,
     pages: &crate::Pages,
     config: &Config,
+    index: &Index,
+    site: &Site,
     tera: &Tera,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let path = match function.namespace {
@@ -257,7 +1020,70 @@ pub fn output_function(
 
     let mut context = tera::Context::new();
 
-    context.insert("function", function);
+    // Operators are shown with their name escaped, `operator&lt;&lt;`, and literal operators
+    // with the usual space before their suffix, `operator&quot;&quot; _kb`
+    let mut displayed = function.clone();
+    displayed.name = match &function.udl_suffix {
+        Some(suffix) => format!("operator&quot;&quot; {}", render::escape_html(suffix)),
+        None => render::escape_html(&function.name),
+    };
+
+    context.insert("function", &displayed);
+    context.insert("defined_in", &defined_in(&function.location));
+    context.insert("source_link", &source_link(&function.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(function.namespace.as_deref(), &function.name),
+            site,
+        ),
+    );
+    context.insert("include_line", &include_line(&function.include_path));
+    context.insert("availability", &availability(&function.available_in, config));
+    context.insert("see_also", &see_also_list(&function.comment));
+    context.insert("deprecation", &deprecation(&function.deprecated, &function.comment));
+    context.insert("since_badge", &since_badge(&function.comment, config));
+    context.insert("implements", &implements_list(&function.comment, config, index));
+    context.insert("template_parameters", &template_parameters_table(&function.template));
+    context.insert(
+        "overload_template_parameters",
+        &function
+            .overloads
+            .iter()
+            .flatten()
+            .map(|overload| template_parameters_table(&overload.template))
+            .collect::<Vec<_>>(),
+    );
+    context.insert("parameters", &parameters_table(function));
+    context.insert(
+        "overload_parameters",
+        &function
+            .overloads
+            .iter()
+            .flatten()
+            .map(parameters_table)
+            .collect::<Vec<_>>(),
+    );
+    context.insert("exceptions", &exceptions_section(function, config, index));
+    context.insert(
+        "overload_exceptions",
+        &function
+            .overloads
+            .iter()
+            .flatten()
+            .map(|overload| exceptions_section(overload, config, index))
+            .collect::<Vec<_>>(),
+    );
+    context.insert("returns", &returns_section(function));
+    context.insert(
+        "overload_returns",
+        &function
+            .overloads
+            .iter()
+            .flatten()
+            .map(returns_section)
+            .collect::<Vec<_>>(),
+    );
     context.insert("pages", &pages);
     context.insert("project", &config.project);
     context.insert("config", &config);
@@ -266,12 +1092,18 @@ pub fn output_function(
         "{}/{}/function.{}.html",
         config.output.path,
         path,
-        function.name.replace("/", "slash")
+        render::sanitize_symbol_filename(&function.name)
     );
 
-    let output = tera.render("function", &context)?;
+    let output = render(
+        tera,
+        "function",
+        &context,
+        &qualified_name(function.namespace.as_deref(), &function.name),
+        site,
+    )?;
 
-    std::fs::write(&path, output)?;
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
 
     Ok(())
 }
@@ -280,12 +1112,19 @@ fn get_link_for_type(
     name: &str,
     curr_namespace: &str,
     config: &Config,
-    index: &HashMap<String, String>,
+    index: &Index,
 ) -> Option<String> {
+    // Function types like `void (*)(int)` can't be resolved to a single page
+    if name.contains('(') {
+        return None;
+    }
+
+    // The trailing `...` of pack expansions (`Args &&...`) is part of the suffix
     let cleaned_name = name.trim_start_matches("const ");
-    let name_without_suffix = name.trim_matches(|c| c == '&' || c == ' ' || c == '*');
+    let name_without_suffix = name.trim_matches(|c| c == '&' || c == ' ' || c == '*' || c == '.');
     let suffix = name.trim_start_matches(name_without_suffix).trim();
-    let cleaned_name = cleaned_name.trim_matches(|c| c == '&' || c == ' ' || c == '*');
+    let cleaned_name =
+        cleaned_name.trim_matches(|c| c == '&' || c == ' ' || c == '*' || c == '.');
 
     if name.contains('<') {
         let mut type_name = name.split('<').next().unwrap();
@@ -320,16 +1159,7 @@ fn get_link_for_type(
         suffix = suffix.trim();
 
         for (i, param) in type_params.iter().enumerate() {
-            let param = param.trim();
-            let link = get_link_for_type(param, curr_namespace, config, index);
-
-            if let Some(link) = link {
-                ret.push_str(&link);
-            } else {
-                ret.push_str("<span class=\"kt\">");
-                ret.push_str(cleanup_type(param).as_str());
-                ret.push_str("</span>");
-            }
+            ret.push_str(&linked_type(param, curr_namespace, config, index));
 
             if i < type_params.len() - 1 {
                 ret.push_str(", ");
@@ -338,15 +1168,22 @@ fn get_link_for_type(
 
         return Some(format!(
             "{}&lt;{}&gt;{}",
-            get_link_for_type(type_name, curr_namespace, config, index).unwrap_or_else(|| format!(
-                "<span class=\"kt\">{}</span>",
-                cleanup_type(type_name)
-            )),
+            linked_type(type_name, curr_namespace, config, index),
             ret,
-            suffix
+            render::escape_html(suffix)
         ));
     }
 
+    // Types written through a namespace alias are looked up in the aliased namespace
+    if let Some(expanded) = expand_namespace_alias(cleaned_name, curr_namespace, index) {
+        if let Some(ret) = render::get_path_for_name(&expanded, index) {
+            return Some(format!(
+                "<a href=\"{}/{}.html\"><span class=\"kt\">{}</span></a>{}",
+                config.output.base_url, ret, name_without_suffix, suffix
+            ));
+        }
+    }
+
     // if name starts with '::', then we must use the global namespace
     if cleaned_name.starts_with("::") {
         let cleaned_name = cleaned_name.trim_start_matches("::");
@@ -360,8 +1197,10 @@ fn get_link_for_type(
         }
     }
 
-    // First try name in current namespace
-    let ret = render::get_path_for_name(&format!("{}::{}", curr_namespace, cleaned_name), index);
+    // Then in the current namespace and its parents, innermost first, down to the global one
+    let ret = index
+        .resolve(cleaned_name, curr_namespace)
+        .and_then(|name| render::get_path_for_name(&name, index));
 
     if let Some(ret) = ret {
         return Some(format!(
@@ -370,37 +1209,10 @@ fn get_link_for_type(
         ));
     }
 
-    // Then try name in global namespace
-    let ret = render::get_path_for_name(cleaned_name, index);
+    None
+}
 
-    if let Some(ret) = ret {
-        return Some(format!(
-            "<a href=\"{}/{}.html\"><span class=\"kt\">{}</span></a>{}",
-            config.output.base_url, ret, name_without_suffix, suffix
-        ));
-    }
-
-    // If still not found, then try in all parents namespaces
-    let mut parts = curr_namespace.split("::").collect::<Vec<_>>();
-
-    while !parts.is_empty() {
-        let ret =
-            render::get_path_for_name(&format!("{}::{}", parts.join("::"), cleaned_name), index);
-
-        if let Some(ret) = ret {
-            return Some(format!(
-                "<a href=\"{}/{}.html\"><span class=\"kt\">{}</span></a>{}",
-                config.output.base_url, ret, name_without_suffix, suffix
-            ));
-        }
-
-        parts.pop();
-    }
-
-    None
-}
-
-fn tera_get_url_for(index: HashMap<String, String>, config: Config) -> impl tera::Function {
+fn tera_get_url_for(index: Index, config: Config) -> impl tera::Function {
     Box::new(
         move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
             let the_type = args.get("type").unwrap();
@@ -435,28 +1247,66 @@ fn tera_get_url_for(index: HashMap<String, String>, config: Config) -> impl tera
                 }
             }
 
-            match get_link_for_type(
+            Ok(tera::to_value(linked_type(
                 the_type.as_str().unwrap(),
                 namespace.as_str().unwrap(),
                 &config,
                 &index,
-            ) {
-                Some(x) => Ok(tera::to_value(x).unwrap()),
-                None => Ok(tera::to_value(format!(
-                    "<span class=\"kt\">{}</span>",
-                    cleanup_type(the_type.as_str().unwrap())
-                ))
-                .unwrap()),
-            }
+            ))
+            .unwrap())
         },
     )
 }
 
+/// `template <typename T, int N = 3> ` before a declaration, with its `requires` clause
+fn template_prefix(
+    templ: &parser::Template,
+    namespace: &str,
+    config: &Config,
+    index: &Index,
+) -> String {
+    let mut prefix = String::new();
+    prefix.push_str("<span class=\"k\">template</span> &lt;");
+
+    let params_length = templ.parameters.len();
+
+    for (i, param) in templ.parameters.iter().enumerate() {
+        prefix.push_str(&format!(
+            "{}{} {}{}",
+            linked_type(&param.type_, namespace, config, index),
+            if param.is_pack { "..." } else { "" },
+            param.name,
+            template_default(
+                &param.type_,
+                param.default_value.as_deref(),
+                namespace,
+                config,
+                index
+            )
+        ));
+
+        if i < params_length - 1 {
+            prefix.push_str(", ");
+        }
+    }
+
+    match templ.requires {
+        Some(ref requires) => prefix.push_str(&format!(
+            "&gt;\n<span class=\"k\">requires</span> {}\n",
+            render::escape_html(requires)
+        )),
+        None => prefix.push_str("&gt; "),
+    }
+
+    prefix
+}
+
 pub fn output_record(
     record: &parser::Record,
     pages: &crate::Pages,
     config: &Config,
-    index: &HashMap<String, String>,
+    index: &Index,
+    site: &Site,
     tera: &Tera,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut context = tera::Context::new();
@@ -468,66 +1318,144 @@ pub fn output_record(
     }
 
     if let Some(templ) = &record.template {
-        prefix.push_str("<span class=\"k\">template</span> &lt;");
-
-        let params_length = templ.parameters.len();
-
-        for (i, param) in templ.parameters.iter().enumerate() {
-            prefix.push_str(&format!(
-                "{} {}",
-                get_link_for_type(
-                    &param.type_,
-                    record.namespace.clone().unwrap_or_default().as_str(),
-                    config,
-                    index
-                )
-                .unwrap_or(format!(
-                    "<span class=\"kt\">{}</span>",
-                    cleanup_type(&param.type_)
-                )),
-                param.name
-            ));
+        prefix.push_str(&template_prefix(
+            templ,
+            record.namespace.clone().unwrap_or_default().as_str(),
+            config,
+            index,
+        ));
+    }
 
-            if i < params_length - 1 {
-                prefix.push_str(", ");
-            }
+    let ns_name = record.namespace.clone().unwrap_or_default();
+
+    let mut bases = String::new();
+
+    for (i, base) in record.bases.iter().enumerate() {
+        bases.push_str(if i == 0 { " : " } else { ", " });
+        bases.push_str(&format!("<span class=\"k\">{}</span> ", base.access));
+
+        if base.virtual_ {
+            bases.push_str("<span class=\"k\">virtual</span> ");
         }
 
-        prefix.push_str("&gt; ");
+        bases.push_str(&linked_type(&base.name, &ns_name, config, index));
     }
 
+    // Attributes of a record go between the class key and the name
+    let attributes = record
+        .attributes
+        .iter()
+        .map(|a| {
+            format!("<span class=\"k\">[[{}]]</span> ", render::escape_html(a))
+        })
+        .collect::<String>();
+
     let mut listing = format!(
-        "{}<span class=\"k\">{}</span> {} {{",
-        prefix, record.kind, record.name
+        "{}<span class=\"k\">{}</span> {}{}{}{} {{",
+        prefix,
+        record.kind,
+        attributes,
+        record.name,
+        if record.final_ {
+            " <span class=\"k\">final</span>"
+        } else {
+            ""
+        },
+        bases
     );
-    let ns_name = record.namespace.clone().unwrap_or_default();
 
-    if !record.fields.is_empty() {
+    let has_members = !record.fields.is_empty()
+        || !record.static_fields.is_empty()
+        || !record.using_declarations.is_empty()
+        || record.inherited_ctors.is_some();
+
+    if has_members {
         listing.push('\n');
     }
 
-    for field in &record.fields {
+    // `using Base::Base;` and `using Base::value_type;`, linked to the base class
+    let inherited_ctors = record.inherited_ctors.as_ref().map(|base| {
+        let base_name = base.split('<').next().unwrap_or_default();
+        let base_name = base_name.rsplit("::").next().unwrap_or_default();
+
+        format!(
+            "{}::{}",
+            get_link_for_type(base, &ns_name, config, index)
+                .unwrap_or(format!("<span class=\"kt\">{}</span>", render::escape_html(base))),
+            base_name
+        )
+    });
+
+    if let Some(inherited_ctors) = &inherited_ctors {
+        listing.push_str(&format!("  <span class=\"k\">using</span> {};\n", inherited_ctors));
+    }
+
+    let mut member_aliases = Vec::new();
+
+    for using in &record.using_declarations {
+        let (base, member) = using.type_.rsplit_once("::").unwrap_or_default();
+
+        let link = get_link_for_type(&using.type_, &ns_name, config, index).unwrap_or(format!(
+            "{}::{}",
+            get_link_for_type(base, &ns_name, config, index)
+                .unwrap_or(format!("<span class=\"kt\">{}</span>", render::escape_html(base))),
+            render::escape_html(member)
+        ));
+
+        listing.push_str(&format!(
+            "  <span class=\"k\">using</span> {};{}\n",
+            link,
+            availability_comment(&using.available_in, config)
+        ));
+        member_aliases.push(serde_json::json!({
+            "name": using.name,
+            "link": link,
+            "comment": using.comment,
+        }));
+    }
+
+    for variable in &record.static_fields {
+        listing.push_str(&format!(
+            "  {};{}\n",
+            variable_declaration(variable, ns_name.as_str(), config, index),
+            availability_comment(&variable.available_in, config)
+        ));
+    }
+
+    // Protected fields (with `output.include_protected`) come last, in their own section
+    let (public_fields, protected_fields): (Vec<_>, Vec<_>) = record
+        .fields
+        .iter()
+        .partition(|f| f.access.as_deref() != Some("protected"));
+
+    for (i, field) in public_fields.iter().chain(&protected_fields).enumerate() {
+        if i == public_fields.len() {
+            listing.push_str("<span class=\"k\">protected</span>:\n");
+        }
+
         if let Some(nested) = &field.struct_ {
             if let parser::NestedField::Record(struct_) = nested {
                 listing.push_str("  <span class=\"k\">struct</span> {\n");
                 for struct_field in struct_.fields.iter() {
                     listing.push_str("  ");
                     listing.push_str(&format!(
-                        "  {} {};\n",
-                        get_link_for_type(
-                            struct_field.type_.as_str(),
-                            ns_name.as_str(),
+                        "  {}{}{};\n",
+                        declaration(
+                            &struct_field.type_,
+                            &struct_field.name,
+                            &ns_name,
                             config,
                             index
-                        )
-                        .unwrap_or(format!(
-                            "<span class=\"kt\">{}</span>",
-                            cleanup_type(&struct_field.type_)
-                        )),
-                        struct_field.name
+                        ),
+                        bit_width_suffix(struct_field.bit_width.map(u64::from)),
+                        field_initializer(struct_field.default_value.as_deref(), config)
                     ));
                 }
-                listing.push_str(&format!("  }} {};\n", field.name));
+                listing.push_str(&format!(
+                    "  }} {};{}\n",
+                    field.name,
+                    availability_comment(&field.available_in, config)
+                ));
                 continue;
             } else if let parser::NestedField::Enum(enm) = nested {
                 listing.push_str("  <span class=\"k\">enum</span> {\n");
@@ -535,19 +1463,46 @@ pub fn output_record(
                     listing.push_str("  ");
                     listing.push_str(&format!("  {};\n", enum_field.name));
                 }
-                listing.push_str(&format!("  }} {};\n", field.name));
+                listing.push_str(&format!(
+                    "  }} {};{}\n",
+                    field.name,
+                    availability_comment(&field.available_in, config)
+                ));
                 continue;
             }
         }
         listing.push_str(&format!(
-            "  {} {};\n",
-            get_link_for_type(field.type_.as_str(), ns_name.as_str(), config, index).unwrap_or(
-                format!("<span class=\"kt\">{}</span>", cleanup_type(&field.type_))
-            ),
-            field.name
+            "  {}{}{};{}\n",
+            declaration(&field.type_, &field.name, &ns_name, config, index),
+            bit_width_suffix(field.bit_width.map(u64::from)),
+            field_initializer(field.default_value.as_deref(), config),
+            availability_comment(&field.available_in, config)
         ));
     }
 
+    if !record.friends.is_empty() {
+        let path = format!(
+            "{}/{}/{}",
+            config.output.path,
+            render::get_namespace_path(&ns_name),
+            record.name
+        );
+
+        std::fs::create_dir_all(&path)?;
+
+        for friend in &record.friends {
+            let mut friend = friend.clone();
+
+            friend.namespace = if ns_name.is_empty() {
+                Some(record.name.clone())
+            } else {
+                Some(format!("{}::{}", ns_name, record.name))
+            };
+
+            output_function(&friend, pages, config, index, site, tera)?;
+        }
+    }
+
     if let Some(nested) = &record.nested {
         if !nested.is_empty() {
             // Create a directory to represent nested types
@@ -571,7 +1526,7 @@ pub fn output_record(
                     Some(format!("{}::{}", ns_name, record.name))
                 };
 
-                output_record(&rec, pages, config, index, tera)?;
+                output_record(&rec, pages, config, index, site, tera)?;
             } else if let parser::NestedField::Enum(enm) = nested_field {
                 let mut enm = enm.clone();
                 enm.namespace = if ns_name.is_empty() {
@@ -580,13 +1535,13 @@ pub fn output_record(
                     Some(format!("{}::{}", ns_name, record.name))
                 };
 
-                output_enum(&enm, pages, config, tera)?;
+                output_enum(&enm, pages, config, index, site, tera)?;
             }
         }
     }
 
     listing.push_str("<span class=\"c\">  /* Full declaration omitted */ </span>");
-    if !record.fields.is_empty() {
+    if has_members {
         listing.push('\n');
     }
     listing.push('}');
@@ -607,20 +1562,140 @@ pub fn output_record(
         None => "".to_string(),
     };
 
+    // `Object ← Widget`, one breadcrumb per path to a root base class
+    let qualified = qualified_name(record.namespace.as_deref(), &record.name);
+    let inheritance = inheritance_paths(&qualified, &site.base_classes)
+        .iter()
+        .map(|path| {
+            path.iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let short = render::escape_html(name.rsplit("::").next().unwrap_or_default());
+
+                    match render::get_path_for_name(name, index) {
+                        Some(link) if i < path.len() - 1 => format!(
+                            "<a href=\"{}/{}.html\"><span class=\"kt\">{}</span></a>",
+                            config.output.base_url, link, short
+                        ),
+                        _ => format!("<span class=\"kt\">{}</span>", short),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ← ")
+        })
+        .collect::<Vec<_>>();
+
+    // "Inherited from Base" sections, linking to the methods on the base class pages
+    let inherited = record
+        .inherited
+        .iter()
+        .map(|(base, methods)| {
+            let link = render::get_path_for_name(base, index)
+                .map(|path| format!("{}/{}.html", config.output.base_url, path));
+
+            let methods = methods
+                .iter()
+                .map(|method| {
+                    serde_json::json!({
+                        "name": render::escape_html(&method.name),
+                        "link": link.as_ref().map(|link| {
+                            format!("{}#{}", link, method.anchor.as_deref().unwrap_or_default())
+                        }),
+                        "brief": method.comment.as_ref().map(|c| c.brief.clone()),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            serde_json::json!({
+                "base": render::escape_html(base),
+                "link": link,
+                "methods": methods,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // "Implements" lists of the methods, by anchor
+    let method_implements = record
+        .methods
+        .iter()
+        .flat_map(|f| std::iter::once(f).chain(f.overloads.iter().flatten()))
+        .filter_map(|f| Some((f.anchor.clone()?, implements_list(&f.comment, config, index)?)))
+        .collect::<HashMap<_, _>>();
+
+    // Parameters tables of the methods and constructors, by anchor
+    let parameters = record
+        .methods
+        .iter()
+        .chain(&record.ctor)
+        .flat_map(|f| std::iter::once(f).chain(f.overloads.iter().flatten()))
+        .filter_map(|f| Some((f.anchor.clone()?, parameters_table(f)?)))
+        .collect::<HashMap<_, _>>();
+
+    let mut badges = Vec::new();
+
+    if record.abstract_ {
+        badges.push("abstract");
+    }
+
+    match (record.copyable, record.movable) {
+        (false, true) => badges.push("move-only"),
+        (false, false) => badges.push("non-copyable, non-movable"),
+        (true, false) => badges.push("non-movable"),
+        (true, true) => {}
+    }
+
     context.insert("record", record);
+    context.insert("defined_in", &defined_in(&record.location));
+    context.insert("source_link", &source_link(&record.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(record.namespace.as_deref(), &record.name),
+            site,
+        ),
+    );
+    context.insert("include_line", &include_line(&record.include_path));
+    context.insert("availability", &availability(&record.available_in, config));
+    context.insert("see_also", &see_also_list(&record.comment));
+    context.insert("deprecation", &deprecation(&record.deprecated, &record.comment));
+    context.insert("since_badge", &since_badge(&record.comment, config));
+    context.insert("implements", &implements_list(&record.comment, config, index));
+    context.insert(
+        "implemented_by",
+        &implemented_by_list(
+            &qualified_name(record.namespace.as_deref(), &record.name),
+            config,
+            index,
+            site,
+        ),
+    );
     context.insert("pages", &pages);
     context.insert("config", &config);
     context.insert("project", &config.project);
     context.insert("listing", &listing);
-
-    let output = tera.render("record", &context)?;
+    context.insert("badges", &badges);
+    context.insert("inheritance", &inheritance);
+    context.insert("inherited_ctors", &inherited_ctors);
+    context.insert("member_aliases", &member_aliases);
+    context.insert("inherited", &inherited);
+    context.insert("template_parameters", &template_parameters_table(&record.template));
+    context.insert("parameters", &parameters);
+    context.insert("method_implements", &method_implements);
+
+    let output = render(
+        tera,
+        "record",
+        &context,
+        &qualified_name(record.namespace.as_deref(), &record.name),
+        site,
+    )?;
 
     let path = format!(
         "{}/{}/record.{}.html",
         config.output.path, path, record.name
     );
 
-    std::fs::write(&path, output)?;
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
 
     Ok(())
 }
@@ -629,7 +1704,8 @@ fn output_alias(
     alias: &parser::Alias,
     pages: &crate::Pages,
     config: &Config,
-    index: &HashMap<String, String>,
+    index: &Index,
+    site: &Site,
     tera: &Tera,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut context = tera::Context::new();
@@ -641,7 +1717,7 @@ fn output_alias(
         alias.name,
         get_link_for_type(alias.type_.as_str(), &ns_name, config, index).unwrap_or(format!(
             "<span class=\"kt\">{}</span>",
-            cleanup_type(&alias.type_)
+            render::escape_html(&alias.type_)
         )),
     );
 
@@ -662,16 +1738,221 @@ fn output_alias(
     };
 
     context.insert("alias", alias);
+    context.insert("defined_in", &defined_in(&alias.location));
+    context.insert("source_link", &source_link(&alias.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(alias.namespace.as_deref(), &alias.name),
+            site,
+        ),
+    );
+    context.insert("include_line", &include_line(&alias.include_path));
+    context.insert("availability", &availability(&alias.available_in, config));
+    context.insert("see_also", &see_also_list(&alias.comment));
+    context.insert("deprecation", &deprecation(&alias.deprecated, &alias.comment));
+    context.insert("since_badge", &since_badge(&alias.comment, config));
     context.insert("pages", &pages);
     context.insert("config", &config);
     context.insert("project", &config.project);
     context.insert("listing", &listing);
 
-    let output = tera.render("alias", &context)?;
+    let output = render(
+        tera,
+        "alias",
+        &context,
+        &qualified_name(alias.namespace.as_deref(), &alias.name),
+        site,
+    )?;
 
     let path = format!("{}/{}/alias.{}.html", config.output.path, path, alias.name);
 
-    std::fs::write(&path, output)?;
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
+
+    Ok(())
+}
+
+fn output_concept(
+    concept: &parser::Concept,
+    pages: &crate::Pages,
+    config: &Config,
+    index: &Index,
+    site: &Site,
+    tera: &Tera,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut context = tera::Context::new();
+
+    let ns_name = concept.namespace.clone().unwrap_or_default();
+
+    let listing = format!(
+        "{}<span class=\"k\">concept</span> {} = {};",
+        template_prefix(&concept.template, &ns_name, config, index),
+        concept.name,
+        render::escape_html(&concept.constraint)
+    );
+
+    let listing = format!(
+        "<div class=\"code highlight\"><pre><code>{}</code></pre></div>",
+        listing
+    );
+
+    let path = match concept.namespace {
+        Some(ref ns) => {
+            if ns.is_empty() {
+                "".to_string()
+            } else {
+                format!("/{}", render::get_namespace_path(ns))
+            }
+        }
+        None => "".to_string(),
+    };
+
+    context.insert("concept", concept);
+    context.insert("defined_in", &defined_in(&concept.location));
+    context.insert("source_link", &source_link(&concept.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(concept.namespace.as_deref(), &concept.name),
+            site,
+        ),
+    );
+    context.insert("include_line", &include_line(&concept.include_path));
+    context.insert("availability", &availability(&concept.available_in, config));
+    context.insert("see_also", &see_also_list(&concept.comment));
+    context.insert("deprecation", &deprecation(&concept.deprecated, &concept.comment));
+    context.insert("since_badge", &since_badge(&concept.comment, config));
+    context.insert("pages", &pages);
+    context.insert("config", &config);
+    context.insert("project", &config.project);
+    context.insert("listing", &listing);
+
+    let output = render(
+        tera,
+        "concept",
+        &context,
+        &qualified_name(concept.namespace.as_deref(), &concept.name),
+        site,
+    )?;
+
+    let path = format!("{}/{}/concept.{}.html", config.output.path, path, concept.name);
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
+
+    Ok(())
+}
+
+/// Whether an initializer is a plain literal (number, string, character, boolean or null
+/// pointer), the only kind worth showing in a listing
+fn is_simple_literal(initializer: &str) -> bool {
+    let value = initializer.trim_start_matches('-').trim();
+
+    matches!(value, "true" | "false" | "nullptr")
+        || value.starts_with(|c: char| c.is_ascii_digit())
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '\'')
+        || (value.starts_with('"') || value.starts_with('\''))
+            && !value.contains(['(', '{'])
+}
+
+/// `static constexpr int name = 3`, with the initializer only when it is a simple literal
+fn variable_declaration(
+    variable: &parser::Variable,
+    ns_name: &str,
+    config: &Config,
+    index: &Index,
+) -> String {
+    let mut listing = String::new();
+
+    if let Some(ref templ) = variable.template {
+        listing.push_str(&template_prefix(templ, ns_name, config, index));
+    }
+
+    for (specifier, enabled) in [
+        ("static", variable.static_),
+        ("inline", variable.inline_),
+        ("constexpr", variable.constexpr_),
+    ] {
+        if enabled {
+            listing.push_str(&format!("<span class=\"k\">{}</span> ", specifier));
+        }
+    }
+
+    listing.push_str(&format!(
+        "{} {}",
+        linked_type(&variable.type_, ns_name, config, index),
+        variable.name
+    ));
+
+    if let Some(ref initializer) = variable.initializer {
+        if is_simple_literal(initializer) {
+            listing.push_str(&format!(" = {}", render::escape_html(initializer)));
+        }
+    }
+
+    listing
+}
+
+fn output_variable(
+    variable: &parser::Variable,
+    pages: &crate::Pages,
+    config: &Config,
+    index: &Index,
+    site: &Site,
+    tera: &Tera,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut context = tera::Context::new();
+
+    let ns_name = variable.namespace.clone().unwrap_or_default();
+
+    let listing = format!(
+        "<div class=\"code highlight\"><pre><code>{};</code></pre></div>",
+        variable_declaration(variable, &ns_name, config, index)
+    );
+
+    let path = match variable.namespace {
+        Some(ref ns) => {
+            if ns.is_empty() {
+                "".to_string()
+            } else {
+                format!("/{}", render::get_namespace_path(ns))
+            }
+        }
+        None => "".to_string(),
+    };
+
+    context.insert("variable", variable);
+    context.insert("defined_in", &defined_in(&variable.location));
+    context.insert("source_link", &source_link(&variable.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(variable.namespace.as_deref(), &variable.name),
+            site,
+        ),
+    );
+    context.insert("include_line", &include_line(&variable.include_path));
+    context.insert("availability", &availability(&variable.available_in, config));
+    context.insert("see_also", &see_also_list(&variable.comment));
+    context.insert("deprecation", &deprecation(&variable.deprecated, &variable.comment));
+    context.insert("since_badge", &since_badge(&variable.comment, config));
+    context.insert("pages", &pages);
+    context.insert("config", &config);
+    context.insert("project", &config.project);
+    context.insert("listing", &listing);
+
+    let output = render(
+        tera,
+        "variable",
+        &context,
+        &qualified_name(variable.namespace.as_deref(), &variable.name),
+        site,
+    )?;
+
+    let path = format!(
+        "{}/{}/variable.{}.html",
+        config.output.path, path, variable.name
+    );
+
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
 
     Ok(())
 }
@@ -680,6 +1961,8 @@ fn output_enum(
     enum_: &parser::Enum,
     pages: &crate::Pages,
     config: &Config,
+    index: &Index,
+    site: &Site,
     tera: &Tera,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if enum_.name.starts_with("(unnamed enum") {
@@ -688,7 +1971,22 @@ fn output_enum(
 
     let mut context = tera::Context::new();
 
-    let mut listing = format!("<span class=\"k\">enum</span> {} {{", enum_.name);
+    let mut listing = if enum_.scoped {
+        format!("<span class=\"k\">enum class</span> {}", enum_.name)
+    } else {
+        format!("<span class=\"k\">enum</span> {}", enum_.name)
+    };
+
+    if let Some(ref underlying_type) = enum_.underlying_type {
+        let ns_name = enum_.namespace.clone().unwrap_or_default();
+
+        listing.push_str(&format!(
+            " : {}",
+            linked_type(underlying_type, &ns_name, config, index)
+        ));
+    }
+
+    listing.push_str(" {");
 
     let value_cnt = enum_.values.len();
 
@@ -696,10 +1994,18 @@ fn output_enum(
         listing.push('\n');
     }
 
+    let show_all_values = config.output.show_all_enum_values.unwrap_or(false);
+
     for (i, value) in enum_.values.iter().enumerate() {
         listing.push_str("  ");
         listing.push_str(&value.name);
 
+        if let Some(ref number) = value.value {
+            if value.explicit || show_all_values {
+                listing.push_str(&format!(" = <span class=\"m\">{}</span>", number));
+            }
+        }
+
         if i < value_cnt - 1 {
             listing.push_str(",\n");
         }
@@ -726,15 +2032,36 @@ fn output_enum(
     };
 
     context.insert("enum", enum_);
+    context.insert("defined_in", &defined_in(&enum_.location));
+    context.insert("source_link", &source_link(&enum_.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(enum_.namespace.as_deref(), &enum_.name),
+            site,
+        ),
+    );
+    context.insert("include_line", &include_line(&enum_.include_path));
+    context.insert("availability", &availability(&enum_.available_in, config));
+    context.insert("see_also", &see_also_list(&enum_.comment));
+    context.insert("deprecation", &deprecation(&enum_.deprecated, &enum_.comment));
+    context.insert("since_badge", &since_badge(&enum_.comment, config));
+    context.insert("show_all_values", &show_all_values);
     context.insert("pages", &pages);
     context.insert("config", &config);
     context.insert("project", &config.project);
     context.insert("listing", &listing);
 
-    let output = tera.render("enum", &context)?;
+    let output = render(
+        tera,
+        "enum",
+        &context,
+        &qualified_name(enum_.namespace.as_deref(), &enum_.name),
+        site,
+    )?;
 
     let path = format!("{}/{}/enum.{}.html", config.output.path, path, enum_.name);
-    std::fs::write(&path, output)?;
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
 
     Ok(())
 }
@@ -743,7 +2070,8 @@ pub fn output_namespace(
     namespace: &parser::Namespace,
     pages: &crate::Pages,
     config: &Config,
-    index: &HashMap<String, String>,
+    index: &Index,
+    site: &Site,
     tera: &Tera,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut context = tera::Context::new();
@@ -759,7 +2087,41 @@ pub fn output_namespace(
         None => "".to_string(),
     };
 
+    // Literal operators get their own "User-defined literals" section
+    let (user_defined_literals, functions): (Vec<_>, Vec<_>) = namespace
+        .functions
+        .iter()
+        .partition(|f| f.udl_suffix.is_some());
+
     context.insert("namespace", namespace);
+    context.insert("defined_in", &defined_in(&namespace.location));
+    context.insert("source_link", &source_link(&namespace.location, config, site));
+    context.insert(
+        "directory",
+        &directory_link(
+            &qualified_name(namespace.namespace.as_deref(), &namespace.name),
+            site,
+        ),
+    );
+    context.insert("functions", &functions);
+    context.insert("user_defined_literals", &user_defined_literals);
+    context.insert("since_badges", &since_badges(namespace, config));
+    context.insert(
+        "namespace_aliases",
+        &namespace
+            .namespace_aliases
+            .iter()
+            .map(|alias| {
+                serde_json::json!({
+                    "name": alias.name,
+                    "target": alias.target,
+                    "comment": alias.comment,
+                    "link": render::get_path_for_name(&alias.target, index)
+                        .map(|path| format!("{}/{}/index.html", config.output.base_url, path)),
+                })
+            })
+            .collect::<Vec<_>>(),
+    );
     context.insert("config", &config);
     context.insert("project", &config.project);
     context.insert("pages", &pages);
@@ -769,10 +2131,17 @@ pub fn output_namespace(
 
     if is_root {
         context.insert("content", &pages.index.content);
+        context.insert("groups", &groups_section(config, site));
         path = "".to_string();
     }
 
-    let output = tera.render(if is_root { "index" } else { "namespace" }, &context)?;
+    let output = render(
+        tera,
+        if is_root { "index" } else { "namespace" },
+        &context,
+        &qualified_name(namespace.namespace.as_deref(), &namespace.name),
+        site,
+    )?;
 
     let path = format!("{}/{}", config.output.path, path);
 
@@ -788,27 +2157,515 @@ pub fn output_namespace(
         }
     );
 
-    std::fs::write(&path, output)?;
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
 
     for record in &namespace.records {
-        output_record(record, pages, config, index, tera)?;
+        output_record(record, pages, config, index, site, tera)?;
+    }
+
+    for function in &namespace.functions {
+        output_function(function, pages, config, index, site, tera)?;
+    }
+
+    for enm in &namespace.enums {
+        output_enum(enm, pages, config, index, site, tera)?;
+    }
+
+    for alias in &namespace.aliases {
+        output_alias(alias, pages, config, index, site, tera)?;
+    }
+
+    for variable in &namespace.variables {
+        output_variable(variable, pages, config, index, site, tera)?;
+    }
+
+    for concept in &namespace.concepts {
+        output_concept(concept, pages, config, index, site, tera)?;
+    }
+
+    for ns in &namespace.namespaces {
+        output_namespace(ns, pages, config, index, site, tera)?;
+    }
+
+    Ok(())
+}
+
+/// The "Topics" section of the root index page, linking to the pages of the groups
+fn groups_section(config: &Config, site: &Site) -> Option<String> {
+    if site.groups.is_empty() {
+        return None;
+    }
+
+    let items = site
+        .groups
+        .iter()
+        .map(|group| {
+            format!(
+                "<li><a href=\"{}/group.{}.html\">{}</a>{}</li>",
+                config.output.base_url,
+                group.name,
+                render::escape_html(group_title(group)),
+                group.comment.as_ref().map(|c| c.brief.as_str()).unwrap_or_default()
+            )
+        })
+        .collect::<String>();
+
+    Some(format!("<h2>Topics</h2><ul class=\"groups\">{items}</ul>"))
+}
+
+/// Briefs of the entities of `namespace` (whose qualified name is `scope`) and of its
+/// namespaces, by qualified name, for the member lists of the groups
+fn collect_briefs(namespace: &parser::Namespace, scope: &str, out: &mut HashMap<String, String>) {
+    fn add(
+        out: &mut HashMap<String, String>,
+        name: String,
+        comment: &Option<parser::Comment>,
+    ) {
+        if let Some(comment) = comment {
+            out.insert(name, comment.brief.clone());
+        }
+    }
+
+    fn walk_record(record: &parser::Record, scope: &str, out: &mut HashMap<String, String>) {
+        let name = qualified_name(scope, &record.name);
+
+        for nested in record.nested.iter().flatten() {
+            match nested {
+                parser::NestedField::Record(r) => walk_record(r, &name, out),
+                parser::NestedField::Enum(e) => {
+                    add(out, qualified_name(name.as_str(), &e.name), &e.comment)
+                }
+            }
+        }
+
+        add(out, name, &record.comment);
     }
 
     for function in &namespace.functions {
-        output_function(function, pages, config, tera)?;
+        add(out, qualified_name(scope, &function.name), &function.comment);
+    }
+
+    for record in &namespace.records {
+        walk_record(record, scope, out);
     }
 
     for enm in &namespace.enums {
-        output_enum(enm, pages, config, tera)?;
+        add(out, qualified_name(scope, &enm.name), &enm.comment);
     }
 
     for alias in &namespace.aliases {
-        output_alias(alias, pages, config, index, tera)?;
+        add(out, qualified_name(scope, &alias.name), &alias.comment);
+    }
+
+    for variable in &namespace.variables {
+        add(out, qualified_name(scope, &variable.name), &variable.comment);
+    }
+
+    for concept in &namespace.concepts {
+        add(out, qualified_name(scope, &concept.name), &concept.comment);
     }
 
     for ns in &namespace.namespaces {
-        output_namespace(ns, pages, config, index, tera)?;
+        let name = qualified_name(scope, &ns.name);
+        collect_briefs(ns, &name, out);
+        add(out, name, &ns.comment);
+    }
+}
+
+/// The name of an entity in `<code>`, linking to its page when it has one
+fn entity_link(name: &str, config: &Config, index: &Index) -> String {
+    let code = format!("<code>{}</code>", render::escape_html(name));
+
+    match render::page_url(name, index, config) {
+        Some(link) => format!("<a href=\"{link}\">{code}</a>"),
+        None => code,
+    }
+}
+
+/// Write the `group.<name>.html` page of every group, listing its members with their brief
+pub fn output_groups(
+    root: &parser::Namespace,
+    pages: &crate::Pages,
+    config: &Config,
+    index: &Index,
+    site: &Site,
+    tera: &Tera,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut briefs = HashMap::new();
+    let scope = qualified_name(root.namespace.as_deref(), &root.name);
+    collect_briefs(root, &scope, &mut briefs);
+
+    for group in &site.groups {
+        let rows = group
+            .members
+            .iter()
+            .map(|name| {
+                format!(
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    entity_link(name, config, index),
+                    briefs.get(name).map(String::as_str).unwrap_or_default()
+                )
+            })
+            .collect::<String>();
+
+        let mut content = String::new();
+
+        if let Some(ref comment) = group.comment {
+            if !comment.auto_brief {
+                content.push_str(&comment.brief);
+            }
+
+            content.push_str(&comment.description);
+        }
+
+        if !rows.is_empty() {
+            content.push_str(&format!(
+                "<table class=\"group-members\"><tbody>{rows}</tbody></table>"
+            ));
+        }
+
+        let mut context = tera::Context::new();
+        context.insert("group", group);
+        context.insert("pages", &pages);
+        context.insert("config", &config);
+        context.insert("project", &config.project);
+        context.insert("title", group_title(group));
+        context.insert("content", &content);
+
+        let output = render(tera, "docpage", &context, &group.name, site)?;
+
+        let path = format!("{}/group.{}.html", config.output.path, group.name);
+        manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
+    }
+
+    Ok(())
+}
+
+/// Write the `examples.html` page listing the files shown by `@example`, with their code and
+/// the entities showing them
+pub fn output_examples(
+    examples: &[parser::ExampleFile],
+    pages: &crate::Pages,
+    config: &Config,
+    index: &Index,
+    site: &Site,
+    tera: &Tera,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if examples.is_empty() {
+        return Ok(());
     }
 
+    let content = examples
+        .iter()
+        .map(|example| {
+            let used_by = example
+                .used_by
+                .iter()
+                .map(|name| format!("<li>{}</li>", entity_link(name, config, index)))
+                .collect::<String>();
+
+            format!(
+                "<h2 id=\"{}\">{}</h2><ul class=\"example-users\">{used_by}</ul>{}",
+                render::example_id(&example.file),
+                render::escape_html(&example.file),
+                render::highlight_example(&example.file, &example.code, index, None, config)
+            )
+        })
+        .collect::<String>();
+
+    let mut context = tera::Context::new();
+    context.insert("examples", examples);
+    context.insert("pages", &pages);
+    context.insert("config", &config);
+    context.insert("project", &config.project);
+    context.insert("title", "Examples");
+    context.insert("content", &content);
+
+    let output = render(tera, "docpage", &context, "examples", site)?;
+
+    let path = format!("{}/examples.html", config.output.path);
+    manifest::write(&site.manifest, &path, output, manifest::Category::EntityPage)?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+
+    /// The text of rendered HTML, as read in the browser
+    fn text(html: &str) -> String {
+        let mut ret = String::new();
+        let mut in_tag = false;
+
+        for c in html.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                c if !in_tag => ret.push(c),
+                _ => {}
+            }
+        }
+
+        ret.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+    }
+
+    fn render(type_: &str, name: &str, index: &Index) -> String {
+        declaration(type_, name, "", &test_config(""), index)
+    }
+
+    #[test]
+    fn diamonds_give_one_inheritance_path_per_base() {
+        let mut bases = HashMap::new();
+        bases.insert("Object".to_string(), vec![]);
+        bases.insert("Left".to_string(), vec!["Object".to_string()]);
+        bases.insert("Right".to_string(), vec!["Object".to_string()]);
+        bases.insert(
+            "Widget".to_string(),
+            vec!["Left".to_string(), "Right".to_string()],
+        );
+
+        assert_eq!(
+            inheritance_paths("Widget", &bases),
+            [["Object", "Left", "Widget"], ["Object", "Right", "Widget"]]
+        );
+        assert!(inheritance_paths("Object", &bases).is_empty());
+
+        // Broken (cyclic) hierarchies stop instead of looping
+        bases.insert("Object".to_string(), vec!["Widget".to_string()]);
+        assert_eq!(
+            inheritance_paths("Left", &bases),
+            [["Right", "Widget", "Object", "Left"]]
+        );
+    }
+
+    #[test]
+    fn exception_specifications_link_their_types() {
+        let mut index = Index::default();
+        index.insert("io".to_string(), "namespace", None);
+        index.insert("io::error".to_string(), "record", None);
+
+        let html = exception_spec("throw(io::error, std::bad_alloc)", "", &test_config(""), &index);
+        assert_eq!(text(&html), " throw(io::error, std::bad_alloc)");
+        assert!(html.contains("href=\"/io/record.error.html\""));
+
+        let html = exception_spec("noexcept(noexcept(T::swap))", "", &test_config(""), &index);
+        assert_eq!(text(&html), " noexcept(noexcept(T::swap))");
+        assert!(!html.contains("href"));
+    }
+
+    #[test]
+    fn function_pointers_keep_the_name_inside() {
+        let mut index = Index::default();
+        index.insert("Event".to_string(), "record", None);
+
+        let html = render("void (*)(int, void *)", "callback", &index);
+        assert_eq!(text(&html), "void (*callback)(int, void*)");
+
+        let html = render("bool (*)(const Event &)", "filter", &index);
+        assert_eq!(text(&html), "bool (*filter)(const Event&)");
+        assert!(html.contains("href=\"/record.Event.html\""));
+
+        let html = render("void (*[4])(int)", "handlers", &index);
+        assert_eq!(text(&html), "void (*handlers[4])(int)");
+
+        let html = render("int (*)[3]", "rows", &index);
+        assert_eq!(text(&html), "int (*rows)[3]");
+    }
+
+    #[test]
+    fn arrays_keep_their_size_after_the_name() {
+        let index = Index::default();
+
+        assert_eq!(text(&render("char[256]", "buffer", &index)), "char buffer[256]");
+        assert_eq!(text(&render("int *[4]", "slots", &index)), "int* slots[4]");
+        assert_eq!(text(&render("float[2][3]", "matrix", &index)), "float matrix[2][3]");
+    }
+
+    #[test]
+    fn pointers_to_member_functions_link_their_class() {
+        let mut index = Index::default();
+        index.insert("Widget".to_string(), "record", None);
+
+        let html = render("int (Widget::*)() const", "getter", &index);
+
+        assert_eq!(text(&html), "int (Widget::*getter)() const");
+        assert!(html.contains("href=\"/record.Widget.html\""));
+    }
+
+    #[test]
+    fn other_parentheses_are_left_alone() {
+        let index = Index::default();
+
+        assert_eq!(
+            text(&render("std::function<void (int)>", "on_change", &index)),
+            "std::function<void (int)> on_change"
+        );
+        assert_eq!(text(&render("void (*)(", "broken", &index)), "void (*broken)(");
+        assert_eq!(text(&render("void (*", "broken", &index)), "void (* broken");
+    }
+
+    #[test]
+    fn function_templates_keep_their_requires_clause() {
+        use tera::Function;
+
+        let output_template = tera_output_template(Index::default(), test_config(""));
+        let mut args = HashMap::new();
+        args.insert("namespace".to_string(), tera::Value::from(""));
+        args.insert(
+            "template".to_string(),
+            serde_json::json!({
+                "parameters": [{"name": "T", "type": "typename", "default_value": null}],
+                "requires": "std::integral<T>",
+            }),
+        );
+
+        let html = output_template.call(&args).unwrap();
+        assert_eq!(
+            text(html.as_str().unwrap()),
+            "template <typename T>\nrequires std::integral<T>\n"
+        );
+
+        args.get_mut("template").unwrap()["requires"] = tera::Value::Null;
+        let html = output_template.call(&args).unwrap();
+        assert_eq!(text(html.as_str().unwrap()), "template <typename T> ");
+    }
+
+    #[test]
+    fn thrown_types_are_linked_and_escaped() {
+        let dump: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/basic/expected/output.json"))
+                .unwrap();
+        let mut function: parser::Function =
+            serde_json::from_value(dump["root"]["functions"][0].clone()).unwrap();
+
+        function.comment.as_mut().unwrap().throws = vec![
+            ("io::error".to_string(), "When reading fails".to_string()),
+            ("std::system_error<T&>".to_string(), "Never".to_string()),
+        ];
+
+        let mut index = Index::default();
+        index.insert("io".to_string(), "namespace", None);
+        index.insert("io::error".to_string(), "record", None);
+
+        let html = exceptions_section(&function, &test_config(""), &index).unwrap();
+        assert!(html.contains("href=\"/io/record.error.html\""));
+        assert!(html.contains("&lt;<span class=\"kt\">T&amp;</span>&gt;"));
+        assert!(!html.contains("T&<") && !html.contains("<T"));
+    }
+
+    #[test]
+    fn render_errors_list_their_causes() {
+        let mut tera = Tera::default();
+        tera.add_raw_template("broken", "{{ missing }}").unwrap();
+
+        let error = tera.render("broken", &tera::Context::new()).unwrap_err();
+        let message = render_error("broken", "mylib::Widget", &error);
+
+        assert!(message.starts_with("Error rendering template “broken” for “mylib::Widget”: "));
+        assert!(message.contains("\n  caused by: Variable `missing` not found"));
+    }
+
+    #[test]
+    fn debug_context_is_dumped_for_its_entity_only() {
+        let mut tera = Tera::default();
+        tera.add_raw_template("page", "{{ title }}").unwrap();
+
+        let path = std::env::temp_dir().join(format!("cppdoc-context-{}.json", std::process::id()));
+        let site = Site {
+            debug_context: Some(("mylib::Widget".to_string(), Some(path.display().to_string()))),
+            ..Default::default()
+        };
+
+        let mut context = tera::Context::new();
+        context.insert("title", "Buffer");
+        let html = super::render(&tera, "page", &context, "mylib::Buffer", &site).unwrap();
+        assert_eq!(html, "Buffer");
+        assert!(!path.exists());
+
+        context.insert("title", "Widget");
+        let html = super::render(&tera, "page", &context, "mylib::Widget", &site).unwrap();
+        assert_eq!(html, "Widget");
+
+        let dumped: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(dumped, serde_json::json!({"title": "Widget"}));
+    }
+
+    #[test]
+    fn concept_pages_show_where_concepts_are_defined() {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            ("page", "{% block content %}{% endblock content %}"),
+            ("concept", CONCEPT_TEMPLATE),
+        ])
+        .unwrap();
+
+        let context = tera::Context::from_value(serde_json::json!({
+            "concept": {"name": "Addable", "comment": null},
+            "listing": "<pre>concept Addable</pre>",
+            "defined_in": "include/tpl.hpp:33",
+            "source_link": "/src/include/tpl.hpp.html#L33",
+            "include_line": "#include <include/tpl.hpp>",
+            "deprecation": "use <code>Summable</code>",
+        }))
+        .unwrap();
+
+        let html = tera.render("concept", &context).unwrap();
+        assert!(html.contains("<pre>concept Addable</pre>"));
+        assert!(html.contains("<code>#include &lt;include&#x2F;tpl.hpp&gt;</code>"));
+        assert!(html.contains("<strong>Deprecated:</strong> use <code>Summable</code>"));
+        assert!(html.contains(
+            "<a href=\"/src/include/tpl.hpp.html#L33\"><code>include&#x2F;tpl.hpp:33</code></a>"
+        ));
+    }
+
+    #[test]
+    fn variable_pages_show_where_variables_are_defined() {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            ("page", "{% block content %}{% endblock content %}"),
+            ("variable", VARIABLE_TEMPLATE),
+        ])
+        .unwrap();
+
+        let context = tera::Context::from_value(serde_json::json!({
+            "variable": {"name": "kVersion", "comment": null},
+            "listing": "<pre>constexpr const char *kVersion</pre>",
+            "defined_in": "include/lib.hpp:4",
+            "source_link": "/src/include/lib.hpp.html#L4",
+            "include_line": "#include <include/lib.hpp>",
+            "deprecation": "use <code>kApiVersion</code>",
+        }))
+        .unwrap();
+
+        let html = tera.render("variable", &context).unwrap();
+        assert!(html.contains("Variable <code>kVersion</code>"));
+        assert!(html.contains("<pre>constexpr const char *kVersion</pre>"));
+        assert!(html.contains("<code>#include &lt;include&#x2F;lib.hpp&gt;</code>"));
+        assert!(html.contains("<strong>Deprecated:</strong> use <code>kApiVersion</code>"));
+        assert!(html.contains(
+            "<a href=\"/src/include/lib.hpp.html#L4\"><code>include&#x2F;lib.hpp:4</code></a>"
+        ));
+    }
+
+    #[test]
+    fn katex_is_only_included_with_output_math() {
+        let mut tera = Tera::default();
+        tera.add_raw_template("math", MATH_TEMPLATE).unwrap();
+
+        let mut config = test_config("");
+        let mut context = tera::Context::new();
+        context.insert("config", &config);
+        assert_eq!(tera.render("math", &context).unwrap().trim(), "");
+
+        config.output.math = Some("katex".to_string());
+        context.insert("config", &config);
+        let html = tera.render("math", &context).unwrap();
+        assert!(html.trim_start().starts_with("<link rel=\"stylesheet\""));
+        assert!(html.contains("katex.min.js"));
+    }
+}
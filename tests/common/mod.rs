@@ -0,0 +1,328 @@
+//! Shared harness for the end-to-end tests.
+//!
+//! Each fixture under `tests/fixtures/<name>` is a small project with its own `cppdoc.toml`.
+//! It is copied to a scratch directory, along with the `static/` directory shared by all of them
+//! from `tests/common/static`, built with the real `cppdoc` binary and compared against the
+//! golden files in `tests/fixtures/<name>/expected`:
+//!
+//! - `expected/output.json` is the `--dump-json` output of the parser
+//! - `expected/html/` is the generated site
+//!
+//! When libclang cannot be loaded, the parse half is skipped and the site is rendered from
+//! `expected/output.json` through `--from-json`, so the render half is exercised everywhere.
+//! Tests that can't do without libclang are `#[ignore = "needs libclang"]`, run them with
+//! `cargo test -- --include-ignored` where it is installed.
+//!
+//! Run with `UPDATE_GOLDEN=1` to regenerate the golden files after an intended change.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Fixture {
+    pub name: String,
+    pub source: PathBuf,
+    pub dir: PathBuf,
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        let dest = to.join(entry.file_name());
+
+        if path.is_dir() {
+            copy_dir(&path, &dest);
+        } else {
+            std::fs::copy(&path, &dest).unwrap();
+        }
+    }
+}
+
+/// Collect every file below `root` as a sorted list of paths relative to it
+fn list_files(root: &Path) -> Vec<PathBuf> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.is_dir() {
+                walk(root, &path, out);
+            } else {
+                out.push(path.strip_prefix(root).unwrap().to_path_buf());
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+
+    if root.exists() {
+        walk(root, root, &mut files);
+    }
+
+    files.sort();
+    files
+}
+
+/// Replace everything that legitimately differs between two builds: the scratch directory
+/// the fixture was built in and ISO-8601 timestamps (`2024-01-31T12:00:00`).
+pub fn normalize(text: &str, dir: &Path) -> String {
+    let text = text.replace(&dir.display().to_string(), "$FIXTURE");
+
+    let pattern = "dddd-dd-ddTdd:dd:dd";
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let is_timestamp = i + pattern.len() <= chars.len()
+            && pattern.chars().enumerate().all(|(j, p)| match p {
+                'd' => chars[i + j].is_ascii_digit(),
+                _ => chars[i + j] == p,
+            });
+
+        if is_timestamp {
+            out.push_str("$TIMESTAMP");
+            i += pattern.len();
+
+            // Swallow fractional seconds and timezone suffixes
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || matches!(chars[i], '.' | 'Z' | '+' | ':'))
+            {
+                i += 1;
+            }
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+
+    out
+}
+
+pub fn update_golden() -> bool {
+    std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1")
+}
+
+impl Fixture {
+    pub fn new(name: &str) -> Self {
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+
+        let dir = std::env::temp_dir().join(format!(
+            "cppdoc-test-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        // A fixture's own static files win over the shared ones
+        copy_dir(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/common/static"),
+            &dir.join("static"),
+        );
+        copy_dir(&source, &dir);
+
+        Fixture {
+            name: name.to_string(),
+            source,
+            dir,
+        }
+    }
+
+    pub fn expected(&self) -> PathBuf {
+        self.source.join("expected")
+    }
+
+    pub fn output_dir(&self) -> PathBuf {
+        self.dir.join("docs")
+    }
+
+    /// Replace `from` by `to` in the fixture's `cppdoc.toml`, like `"[output]"` by
+    /// `"[output]\nemit_source = true"` to set an option
+    pub fn edit_config(&self, from: &str, to: &str) {
+        let path = self.dir.join("cppdoc.toml");
+        let config = std::fs::read_to_string(&path).unwrap();
+
+        assert!(config.contains(from), "[{}] cppdoc.toml has no “{}”", self.name, from);
+
+        std::fs::write(&path, config.replace(from, to)).unwrap();
+    }
+
+    /// Run `cppdoc build` in the fixture, as of 2023-11-14 so that `{year}` is the same everywhere
+    pub fn cppdoc(&self, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_cppdoc"))
+            .current_dir(&self.dir)
+            .env("SOURCE_DATE_EPOCH", "1700000000")
+            .arg("build")
+            .args(args)
+            .output()
+            .expect("failed to run cppdoc")
+    }
+
+    /// Run the parser and return its JSON dump, or `None` when libclang is not available
+    pub fn dump(&self) -> Option<serde_json::Value> {
+        let out = self.cppdoc(&["--dump-json"]);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+
+        if !out.status.success() {
+            if stderr.contains("libclang") {
+                eprintln!("[{}] libclang is not available, skipping the parser", self.name);
+                return None;
+            }
+
+            panic!("[{}] cppdoc --dump-json failed:\n{}", self.name, stderr);
+        }
+
+        Some(serde_json::from_slice(&out.stdout).expect("--dump-json produced invalid JSON"))
+    }
+
+    /// Build the fixture's site, falling back to the checked-in dump without libclang
+    pub fn build(&self) -> PathBuf {
+        self.build_with(&[]);
+        self.output_dir()
+    }
+
+    /// Build the fixture's site with the extra arguments `args` like `build`, returning the
+    /// output of cppdoc
+    pub fn build_with(&self, args: &[&str]) -> std::process::Output {
+        let out = self.run(args);
+
+        assert!(
+            out.status.success(),
+            "[{}] cppdoc build failed:\n{}",
+            self.name,
+            String::from_utf8_lossy(&out.stderr)
+        );
+
+        out
+    }
+
+    /// Run `cppdoc build` with the extra arguments `args`, on the checked-in dump without
+    /// libclang, whether it succeeds or not
+    pub fn run(&self, args: &[&str]) -> std::process::Output {
+        match self.dump() {
+            Some(_) => self.cppdoc(args),
+            None => {
+                let dump = self.expected().join("output.json");
+
+                assert!(
+                    dump.exists(),
+                    "[{}] needs libclang or {}, mark the test #[ignore = \"needs libclang\"]",
+                    self.name,
+                    dump.display()
+                );
+
+                let mut from_json = vec!["--from-json", dump.to_str().unwrap()];
+                from_json.extend_from_slice(args);
+                self.cppdoc(&from_json)
+            }
+        }
+    }
+
+    /// `dump` for the tests of the parser itself, which are `#[ignore]`d as they can't run
+    /// without libclang
+    pub fn require_dump(&self) -> serde_json::Value {
+        self.dump()
+            .unwrap_or_else(|| panic!("[{}] libclang is needed by this test", self.name))
+    }
+
+    pub fn read_json(&self, path: &str) -> serde_json::Value {
+        serde_json::from_str(&self.read_output(path)).unwrap()
+    }
+
+    pub fn read_output(&self, path: &str) -> String {
+        let path = self.output_dir().join(path);
+        let content = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("[{}] cannot read {}: {e}", self.name, path.display()));
+
+        normalize(&content, &self.dir)
+    }
+
+    pub fn assert_golden_dump(&self, dump: &serde_json::Value) {
+        let golden = self.expected().join("output.json");
+
+        if update_golden() {
+            std::fs::create_dir_all(self.expected()).unwrap();
+            std::fs::write(&golden, serde_json::to_string_pretty(dump).unwrap() + "\n").unwrap();
+            return;
+        }
+
+        let expected: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(&golden).unwrap_or_else(|_| {
+                panic!("[{}] missing {}, run with UPDATE_GOLDEN=1", self.name, golden.display())
+            }),
+        )
+        .unwrap();
+
+        // Compared as values so that the (unordered) index map doesn't cause spurious diffs
+        assert!(
+            *dump == expected,
+            "[{}] parser output differs from {}, run with UPDATE_GOLDEN=1 if this is intended\n{}",
+            self.name,
+            golden.display(),
+            serde_json::to_string_pretty(dump).unwrap()
+        );
+    }
+
+    pub fn assert_golden_html(&self) {
+        let golden = self.expected().join("html");
+        let actual = list_files(&self.output_dir());
+
+        if update_golden() {
+            if golden.exists() {
+                std::fs::remove_dir_all(&golden).unwrap();
+            }
+
+            for file in &actual {
+                let dest = golden.join(file);
+                std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+                std::fs::write(dest, self.read_output(file.to_str().unwrap())).unwrap();
+            }
+
+            return;
+        }
+
+        assert!(
+            golden.exists(),
+            "[{}] missing {}, run with UPDATE_GOLDEN=1",
+            self.name,
+            golden.display()
+        );
+
+        assert_eq!(
+            actual,
+            list_files(&golden),
+            "[{}] generated files differ from {}",
+            self.name,
+            golden.display()
+        );
+
+        for file in &actual {
+            let expected = std::fs::read_to_string(golden.join(file)).unwrap();
+
+            assert!(
+                self.read_output(file.to_str().unwrap()) == expected,
+                "[{}] {} differs from its golden copy, run with UPDATE_GOLDEN=1 if this is intended",
+                self.name,
+                file.display()
+            );
+        }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
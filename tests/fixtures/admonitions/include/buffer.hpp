@@ -0,0 +1,23 @@
+#pragma once
+
+namespace mylib {
+
+/// A growable byte buffer
+///
+/// @attention Owns its memory
+class Buffer {
+public:
+    /// Write the buffer to `fd`
+    ///
+    /// @warning Not thread-safe:
+    ///   - lock the buffer first
+    ///   - or use `flush_sync`
+    ///
+    /// Returns once every byte is written.
+    ///
+    /// @note Cheap when the buffer is empty
+    /// @bug Loses the last byte on short writes
+    void flush(int fd);
+};
+
+} // namespace mylib
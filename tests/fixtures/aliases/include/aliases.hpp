@@ -0,0 +1,13 @@
+#pragma once
+
+template <typename T> struct List {};
+template <typename K, typename V> struct Map {};
+template <typename F> struct Function {};
+
+struct Widget {};
+
+using Widgets = List<Widget>;
+using Index = Map<int, List<Widget *>>;
+using Callback = Function<void(int, const Widget &)>;
+using Handler = void (*)(int);
+using WidgetPtr = Widget *;
@@ -0,0 +1,23 @@
+#pragma once
+
+namespace mylib {
+
+/// A connected socket
+///
+/// Made by ::mylib::connect() and closed with mylib::Socket::close(), see also
+/// `mylib::Socket` and [mylib::Socket docs](https://example.com/socket).
+/// Not mylib::Sockets though.
+///
+/// ```cpp
+/// mylib::Socket socket = mylib::connect("localhost");
+/// ```
+class Socket {
+public:
+    /// Close the connection
+    void close();
+};
+
+/// Connect to `host`
+Socket connect(const char *host);
+
+} // namespace mylib
@@ -0,0 +1,94 @@
+#pragma once
+
+/// A plain aggregate
+struct Point {
+  /// Horizontal position
+  int x;
+  int y; ///< Vertical position
+
+  /// Number of coordinates
+  static constexpr int dimensions = 2;
+};
+
+/** A record with nested types */
+class Shape {
+public:
+  /// How the shape is drawn
+  enum Style { Filled, Outline };
+
+  struct Bounds {
+    Point min;
+    Point max;
+  };
+
+  /// Creates an empty shape
+  Shape();
+
+  Shape(const Shape &) = delete;
+
+  /// Releases the shape
+  virtual ~Shape();
+
+  /// Returns the area of the shape
+  [[nodiscard]] float area() const noexcept;
+
+  static Shape unit();
+
+  bool operator==(const Shape &other) const;
+
+  /// Whether the shape is non-empty
+  explicit operator bool() const;
+
+  /// Prints the shape to the standard output
+  friend void print(const Shape &shape);
+};
+
+/// A round shape
+class Circle : public Shape {
+public:
+  using Shape::Shape;
+  using Shape::Style;
+};
+
+/*!
+ * Supported colors
+ */
+enum Color { Red, Green = 4, Blue };
+
+/// Result of an operation
+enum class Status : unsigned char { Ok, Failed };
+
+/// Alias for a list of points
+using Polygon = Point *;
+
+//! Computes the distance between two points
+[[deprecated("compare squared lengths instead")]]
+float distance(const Point &a, const Point &b);
+
+/// Scales a point
+Point scale(const Point &p, float factor = 2.0f);
+
+/// Scales a point along both axes
+Point scale(const Point &p, float fx, float fy);
+
+/// Packed hardware flags
+struct Flags {
+  unsigned int enabled : 1;
+  unsigned int mode : 3;
+};
+
+/// Connection settings
+struct Options {
+  int retries = 3;
+  const char *host{"localhost"};
+};
+
+extern "C" {
+
+/// Number of points allocated, for C callers
+int point_count(void);
+
+}
+
+/// Releases the global state, for C callers
+extern "C" void shutdown(void);
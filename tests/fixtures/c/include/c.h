@@ -0,0 +1,19 @@
+#pragma once
+
+/**
+ * @brief A 2D vector
+ *
+ * Plain C struct, named through its typedef.
+ */
+typedef struct {
+  float x;
+  float y;
+} vec2_t;
+
+/** @brief Blend modes */
+typedef enum { BLEND_NONE, BLEND_ADD } blend_t;
+
+/**
+ * @brief Adds two vectors
+ */
+vec2_t vec2_add(vec2_t a, vec2_t b);
@@ -0,0 +1,8 @@
+#pragma once
+
+namespace app {
+
+/// Version of the library, `major * 100 + minor`
+constexpr int version = 203;
+
+} // namespace app
@@ -0,0 +1,23 @@
+#pragma once
+
+/// File status
+struct stat {
+  /// Size in bytes
+  long size;
+};
+
+/// Gets the status of `path`
+int stat(const char *path, struct stat *buf);
+
+/// A global item
+struct Item {};
+
+namespace store {
+
+/// An item of the store
+struct Item {};
+
+/// Sells an item of the store
+void sell(Item item);
+
+} // namespace store
@@ -0,0 +1,11 @@
+#pragma once
+
+namespace jobs {
+
+/// A queue of jobs
+///
+/// @added{1.4}
+/// @threadsafe
+class Queue {};
+
+} // namespace jobs
@@ -0,0 +1,10 @@
+#pragma once
+
+namespace net {
+
+/// Returns a vector<int> of the <b>open</b> ports, see <https://example.com/ports>.
+///
+/// <script>alert(1)</script> <i onclick="steal()">Scanned</i> with `probe<int>`.
+int ports();
+
+} // namespace net
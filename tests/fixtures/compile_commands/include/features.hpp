@@ -0,0 +1,9 @@
+#pragma once
+
+#ifdef WITH_EXTRAS
+/// Only declared when the build defines WITH_EXTRAS
+void extras();
+#endif
+
+/// Always declared
+void core();
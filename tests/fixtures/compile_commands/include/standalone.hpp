@@ -0,0 +1,9 @@
+#pragma once
+
+#ifdef WITH_EXTRAS
+/// Never declared, this header isn't in the compilation database
+void standalone_extras();
+#endif
+
+/// Parsed with input.compiler_arguments
+void standalone();
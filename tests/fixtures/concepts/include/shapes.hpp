@@ -0,0 +1,17 @@
+#pragma once
+
+namespace geo {
+
+/// Types with an area
+template <typename T>
+concept Shape = requires(const T &shape) { shape.area(); };
+
+/// Shapes that can also be scaled by `Factor`
+template <typename T, typename Factor>
+concept Scalable = Shape<T> && requires(T &shape, Factor factor) { shape.scale(factor); };
+
+/// Whether `T` is a shape, a variable template next to the concepts
+template <typename T>
+constexpr bool is_shape_v = Shape<T>;
+
+} // namespace geo
@@ -0,0 +1,15 @@
+#pragma once
+
+#include <cstddef>
+
+namespace mylib {
+
+/// Copy `size` bytes into `buffer`
+///
+/// @pre buffer != nullptr && size < capacity
+/// @pre `std::vector<int>` or std::vector<int> values
+///   are sorted
+/// @post buffer is **filled**
+void fill(char *buffer, std::size_t size, std::size_t capacity);
+
+} // namespace mylib
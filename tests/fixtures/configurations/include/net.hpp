@@ -0,0 +1,40 @@
+#pragma once
+
+namespace net {
+
+/// A connection to a server
+class Connection {
+public:
+    /// Send `size` bytes
+    void send(const char *data, int size);
+
+#ifdef NET_ENABLE_TLS
+    /// The certificate of a server
+    struct Certificate {
+        /// The name the certificate was issued to
+        const char *subject;
+    };
+
+    /// Check the certificate of the server
+    bool verify_certificate();
+
+    /// Whether the connection is encrypted
+    bool encrypted = true;
+#endif
+};
+
+/// Open a connection to `host`
+Connection connect(const char *host);
+
+#ifdef NET_ENABLE_TLS
+/// Versions of the TLS protocol
+enum class TlsVersion { v1_2, v1_3 };
+
+/// Open an encrypted connection to `host`
+Connection connect_tls(const char *host, TlsVersion version);
+#else
+/// Open a connection to `host` on another port than the default one
+Connection connect(const char *host, int port);
+#endif
+
+} // namespace net
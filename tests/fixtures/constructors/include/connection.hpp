@@ -0,0 +1,34 @@
+#pragma once
+
+/// A connection, made by a builder
+class Connection {
+public:
+  /// Connects to `host`
+  explicit Connection(const char *host);
+
+  /// Closes the connection
+  ~Connection();
+
+protected:
+  /// Wraps an open socket, for derived connections
+  explicit Connection(int socket);
+
+private:
+  /// Used by the builder only
+  Connection();
+
+  friend class Builder;
+};
+
+/// A handle to a pooled connection
+class Handle {
+public:
+  /// Takes a connection from the pool
+  Handle();
+
+private:
+  /// Only the pool gives the connection back
+  ~Handle();
+
+  friend class Pool;
+};
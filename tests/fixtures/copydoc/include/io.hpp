@@ -0,0 +1,36 @@
+#pragma once
+
+namespace mylib {
+namespace detail {
+
+/// @brief Read from a file
+///
+/// Blocks until `size` bytes are read.
+///
+/// @param fd The file descriptor
+/// @param size How many bytes
+/// @returns The bytes read
+int read_impl(int fd, int size);
+
+} // namespace detail
+
+/// \copydoc detail::read_impl
+int read(int fd, int size);
+
+/// @copydoc read(int, int)
+///
+/// Retries on `EINTR`.
+///
+/// @param size How many bytes, at most 4096
+int read_retrying(int fd, int size);
+
+/// @copydoc ping
+void pong();
+
+/// @copydoc pong
+void ping();
+
+/// @copydoc nowhere
+void lost();
+
+} // namespace mylib
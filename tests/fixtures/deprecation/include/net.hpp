@@ -0,0 +1,34 @@
+#pragma once
+
+namespace net {
+
+/// A connection to a server
+class Connection {
+public:
+    /// Connect to `host`
+    static Connection open(const char *host);
+
+    /// Close the connection
+    void close();
+};
+
+/// Connect to `host`
+///
+/// @deprecated Use Connection::open() instead,
+///   it reports errors.
+Connection connect(const char *host);
+
+/// Close `connection`
+///
+/// @deprecated Destroying a net::Connection closes it
+[[deprecated]] void close(Connection &connection);
+
+/// Wait for the server
+[[deprecated("poll the connection instead")]] void wait(Connection &connection);
+
+/// Pointer to a connection
+///
+/// @deprecated
+using Handle = Connection *;
+
+} // namespace net
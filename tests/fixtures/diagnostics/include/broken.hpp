@@ -0,0 +1,6 @@
+#pragma once
+
+#include "missing.hpp"
+
+/// Still documented, libclang recovers from the missing include
+void after();
@@ -0,0 +1,16 @@
+#pragma once
+
+/// @dir
+/// Sockets and the streams writing to them
+
+#include <ostream>
+
+namespace net {
+
+/// A connected socket
+class Socket {};
+
+/// Write the address of `socket`
+std::ostream &operator<<(std::ostream &out, const Socket &socket);
+
+} // namespace net
@@ -0,0 +1,4 @@
+#pragma once
+
+/// Look a user up in the @directory of the users
+void lookup();
@@ -0,0 +1,16 @@
+#pragma once
+
+namespace lib {
+
+/// Runs the library
+void run();
+
+namespace checks {
+static_assert(sizeof(int) >= 2, "int is too small");
+} // namespace checks
+
+namespace outer {
+namespace inner {}
+} // namespace outer
+
+} // namespace lib
@@ -0,0 +1,6 @@
+#include "server.hpp"
+
+int main() {
+    net::Server server = net::listen(7);
+    server.serve();
+}
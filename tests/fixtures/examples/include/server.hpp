@@ -0,0 +1,22 @@
+#pragma once
+
+namespace net {
+
+/// Echo server over TCP
+///
+/// @example tcp_echo.cpp
+class Server {
+public:
+    /// Serve until stopped
+    ///
+    /// @example{doctest} serve_once.cpp
+    /// @example tcp_echo.cpp
+    void serve();
+};
+
+/// Listen on `port`
+///
+/// @example missing.cpp
+Server listen(int port);
+
+} // namespace net
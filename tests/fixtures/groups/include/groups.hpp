@@ -0,0 +1,33 @@
+#pragma once
+
+/// @defgroup net Networking
+///
+/// Talking to other machines
+
+/// @defgroup serialization Serialization
+
+/// @defgroup misc
+
+namespace mylib {
+
+/// @brief A connected socket
+///
+/// @ingroup net serialization
+class Socket {};
+
+/// Connect to `host`
+///
+/// @ingroup net
+Socket connect(const char *host);
+
+/// Wire formats
+///
+/// @ingroup serialization
+enum class Format { Json, Binary };
+
+/// Retry a failed call
+///
+/// @ingroup networking
+void retry();
+
+} // namespace mylib
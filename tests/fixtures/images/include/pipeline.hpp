@@ -0,0 +1,19 @@
+#pragma once
+
+namespace gfx {
+
+/// A render pipeline
+///
+/// @image html pipeline.png "The stages of a frame"
+/// Frames go through the stages in order, see ![the passes](diagrams/passes.png).
+/// @image latex pipeline.eps
+///
+/// ![logo](https://example.com/logo.png)
+class Pipeline {};
+
+/// Draw a frame
+///
+/// @image html missing.png
+void draw();
+
+} // namespace gfx
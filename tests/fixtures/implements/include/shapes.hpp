@@ -0,0 +1,31 @@
+#pragma once
+
+namespace shapes {
+
+/// Something that can be drawn
+class Drawable {
+public:
+    virtual ~Drawable() = default;
+
+    /// Draw on the screen
+    virtual void draw() = 0;
+};
+
+/// A round shape
+///
+/// @impl Drawable
+class Circle : public Drawable {
+public:
+    /// Draw the circle
+    ///
+    /// @impl Drawable::draw
+    void draw() override;
+};
+
+/// Draw by hand
+///
+/// @impl ::shapes::Drawable
+/// @impl Printable
+void sketch();
+
+} // namespace shapes
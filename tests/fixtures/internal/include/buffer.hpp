@@ -0,0 +1,17 @@
+#pragma once
+
+namespace io {
+
+/// A buffer of bytes
+///
+/// @internal
+/// Grows by doubling, see the benchmarks in `bench/`.
+/// @endinternal
+///
+/// Not thread-safe.
+class Buffer {};
+
+/// @internal Only for the tests
+void reset_buffers();
+
+} // namespace io
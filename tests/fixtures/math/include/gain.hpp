@@ -0,0 +1,14 @@
+#pragma once
+
+namespace dsp {
+
+/// Scale a sample by a gain in decibels, $y = x \cdot 10^{g/20}$
+///
+/// $$
+/// G_{dB} = 20 \log_{10} \frac{|y|}{|x|}
+/// $$
+///
+/// Use `$GAIN` to override it.
+float gain(float x, float g);
+
+} // namespace dsp
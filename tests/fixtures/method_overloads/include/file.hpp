@@ -0,0 +1,20 @@
+#pragma once
+
+/// A file on disk, see [opening by descriptor](::File#method-open-2)
+class File {
+public:
+  /// Creates a closed file
+  File();
+
+  /// Creates a file and opens `path`
+  explicit File(const char *path);
+
+  /// Opens the file at `path`
+  bool open(const char *path);
+
+  /// Opens an already open file descriptor
+  bool open(int fd);
+
+  /// Closes the file
+  void close();
+};
@@ -0,0 +1,16 @@
+export module mylib;
+
+/// The answer to everything
+export int answer();
+
+/// Used by answer, not exported
+int helper();
+
+export namespace shapes {
+
+/// A square
+struct Square {
+  int side;
+};
+
+} // namespace shapes
@@ -0,0 +1,20 @@
+#pragma once
+
+namespace mylib {
+
+namespace v2 {
+
+/// Something to draw
+struct Widget {
+  int width;
+};
+
+} // namespace v2
+
+/// The current version of the API
+namespace v = v2;
+
+/// Draws a widget
+void draw(const v::Widget &widget);
+
+} // namespace mylib
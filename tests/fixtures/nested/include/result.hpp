@@ -0,0 +1,15 @@
+#pragma once
+
+/// The outcome of an operation
+class Result {
+public:
+  /// Where the operation failed
+  struct Location {
+    int line;
+  };
+
+  /// Why the operation failed
+  enum Error { None, Timeout };
+
+  Error error() const;
+};
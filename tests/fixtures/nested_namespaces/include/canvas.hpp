@@ -0,0 +1,25 @@
+#pragma once
+
+namespace outer {
+namespace inner {
+
+/// A color
+struct Color {
+  int red, green, blue;
+};
+
+/// A drawing surface
+class Canvas {
+public:
+  /// Fills the canvas with `color`
+  void fill(Color color);
+
+  /// The color the canvas was last filled with
+  Color color() const;
+
+  /// Whether two canvases have the same color
+  friend bool operator==(const Canvas &a, const Canvas &b);
+};
+
+} // namespace inner
+} // namespace outer
@@ -0,0 +1,60 @@
+#pragma once
+
+namespace a {
+
+/// Takes one integer
+void f(int x);
+
+/// Takes one float
+void f(float x);
+
+/// Takes two integers
+void f(int x, int y);
+
+} // namespace a
+
+namespace b {
+
+/// Unrelated function with the same name
+void f(int x);
+
+} // namespace b
+
+namespace a {
+
+/// Declared in a reopened namespace
+void g();
+
+} // namespace a
+
+namespace a {
+inline namespace v2 {
+
+/// Declared in an inline namespace
+void h();
+
+} // namespace v2
+} // namespace a
+
+namespace a {
+namespace detail {
+
+/// Implementation detail, not documented
+void hidden();
+
+} // namespace detail
+} // namespace a
+
+namespace {
+
+/// Internal linkage, not documented
+void internal();
+
+} // namespace
+
+namespace a {
+
+/// Kilobytes literal
+unsigned long long operator""_kb(unsigned long long n);
+
+} // namespace a
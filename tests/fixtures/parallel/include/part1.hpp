@@ -0,0 +1,13 @@
+#pragma once
+
+namespace geo {
+
+/// Part 1 of the geometry types
+struct Shape1 {
+  int size;
+};
+
+/// Scales a Shape1
+void scale(Shape1 &shape, int factor);
+
+} // namespace geo
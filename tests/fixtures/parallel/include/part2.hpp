@@ -0,0 +1,13 @@
+#pragma once
+
+namespace geo {
+
+/// Part 2 of the geometry types
+struct Shape2 {
+  int size;
+};
+
+/// Scales a Shape2
+void scale(Shape2 &shape, int factor);
+
+} // namespace geo
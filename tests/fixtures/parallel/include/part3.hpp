@@ -0,0 +1,13 @@
+#pragma once
+
+namespace geo {
+
+/// Part 3 of the geometry types
+struct Shape3 {
+  int size;
+};
+
+/// Scales a Shape3
+void scale(Shape3 &shape, int factor);
+
+} // namespace geo
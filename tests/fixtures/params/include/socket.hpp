@@ -0,0 +1,40 @@
+#pragma once
+
+namespace io {
+
+/// Read from a socket
+///
+/// @param[out] buffer Where the bytes go
+/// \param timeout_ms Timeout in milliseconds,
+///   0 to wait forever
+/// @param flags Unused, there is no such parameter
+int read(char *buffer, int timeout_ms);
+
+/// Outcome of an operation
+enum class Status { Ok, Busy };
+
+/// Close a socket
+///
+/// @return Whether it was closed
+/// @retval Status::Ok The socket is closed
+/// @retval Status::Busy A read is still pending
+Status close(int fd);
+
+/// Thrown when the other end doesn't answer in time
+class TimeoutError {};
+
+/// Wait for a connection
+///
+/// @throws TimeoutError When no client connects
+/// @throws TimeoutError or when the handshake takes too long
+void accept(int fd);
+
+/// A connected socket
+class Socket {
+public:
+    /// Send bytes
+    /// @param data Bytes to send
+    void send(const char *data);
+};
+
+} // namespace io
@@ -0,0 +1,64 @@
+#pragma once
+
+namespace weird {
+
+/// Padding with unnamed bit-fields
+struct Packed {
+  unsigned a : 3;
+  unsigned : 0;
+  unsigned : 5;
+  unsigned b : 2;
+};
+
+/// Members of unnamed types
+struct Variant {
+  struct {
+    int x, y;
+  } point;
+
+  union {
+    int i;
+    float f;
+  } value;
+
+  enum { Small, Large } size;
+
+  union {
+    long raw;
+    char bytes[8];
+  };
+};
+
+template <typename T> struct Box {
+  T value;
+
+  template <typename U> Box(U &&value);
+};
+
+template <typename U> Box(U &&) -> Box<U>;
+
+/// Unnamed parameters and a function pointer
+void callback(int, void (*)(int, char *), ...);
+
+using Handler = void (*)(int);
+typedef int Array[4];
+
+struct Ops {
+  template <typename T> operator T() const;
+  auto operator<=>(const Ops &) const = default;
+  friend bool operator==(const Ops &, const Ops &) = default;
+};
+
+inline auto lambda = [](auto x) { return x; };
+
+namespace {
+struct Hidden {};
+} // namespace
+
+} // namespace weird
+
+extern "C" {
+typedef struct {
+  int unused;
+} c_type;
+}
@@ -0,0 +1,26 @@
+#pragma once
+
+namespace io {
+
+/// A file handle
+struct File;
+
+/// Opens a file
+///
+/// Returns a closed handle on failure.
+File open(const char *path, int flags = 0);
+
+void close(File &file);
+void close(File &file, bool flush);
+
+struct File {
+  int fd;
+};
+
+/// How a file is opened
+enum class Mode { Read, Write };
+
+/// A file handle owned by the caller
+using Handle = File *;
+
+} // namespace io
@@ -0,0 +1,32 @@
+#pragma once
+
+#include "file.hpp"
+
+namespace io {
+
+/// Opens the file read-only
+struct ReadOnly {};
+
+/// Options of a handle
+template <typename T> struct Options {
+  /// Default options
+  Options();
+
+  int flags;
+};
+
+template <> struct Options<File> {
+  /// Options opening the file with `mode`
+  explicit Options(int mode);
+
+  ~Options();
+
+  int mode;
+  static const int default_mode = 0644;
+
+  struct Lock {
+    bool shared;
+  };
+};
+
+} // namespace io
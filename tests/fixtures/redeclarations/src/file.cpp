@@ -0,0 +1,17 @@
+#include "file.hpp"
+
+namespace io {
+
+struct ReadOnly;
+
+File open(const char *path, int flags) { return File{-1}; }
+
+/// Closes the file
+void close(File &file) {}
+
+/// Closes the file, after flushing it when asked to
+///
+/// @since 2.0
+void close(File &file, bool flush) {}
+
+} // namespace io
@@ -0,0 +1,19 @@
+#pragma once
+
+namespace net {
+
+/// A connected socket
+///
+/// @anchor threading-model
+/// ## Threading model
+///
+/// A socket may be used by one thread at a time.
+class Socket {};
+
+/// Open a connection
+///
+/// Follows @ref threading-model "the threading model" of @ref Socket, see also
+/// @ref retry-policy and @ref Reconnector.
+Socket connect();
+
+} // namespace net
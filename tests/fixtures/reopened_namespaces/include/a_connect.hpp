@@ -0,0 +1,8 @@
+#pragma once
+
+namespace net {
+
+/// Connects to `host`
+void connect(const char *host);
+
+} // namespace net
@@ -0,0 +1,9 @@
+#pragma once
+
+/// Networking utilities
+namespace net {
+
+/// Closes the connection
+void close();
+
+} // namespace net
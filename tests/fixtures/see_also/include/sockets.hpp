@@ -0,0 +1,38 @@
+#pragma once
+
+namespace mylib {
+
+/// A connected socket
+///
+/// @see close
+/// @sa ::mylib::connect()
+class Socket {
+public:
+    /// Open a socket, unconnected
+    Socket();
+
+    /// Close the connection
+    ///
+    /// @see Socket
+    void close();
+};
+
+/// Connect to `host`
+///
+/// @see mylib::Socket::close
+/// @see Socket::Socket
+/// @see [the protocol](https://example.com/protocol)
+/// @see missing_function
+Socket connect(const char *host);
+
+/// Connection states
+///
+/// @see connect
+enum class State { Open, Closed };
+
+/// Handle of a socket
+///
+/// @see Socket
+using Handle = Socket *;
+
+} // namespace mylib
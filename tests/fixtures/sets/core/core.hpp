@@ -0,0 +1,10 @@
+#pragma once
+
+/// Version of the core library
+struct Version {
+  int major;
+  int minor;
+};
+
+/// Starts the core
+void start();
@@ -0,0 +1,12 @@
+#pragma once
+
+#if PLUGIN_API
+/// Something a plugin can load
+template <typename T>
+concept Loadable = requires(T t) { t.load(); };
+#endif
+
+/// Version of the plugin API
+struct Version {
+  int major;
+};
@@ -0,0 +1,23 @@
+#pragma once
+
+namespace mylib {
+
+/// Compress `size` bytes of `data`
+///
+/// @since 2.0
+int compress(const char *data, int size);
+
+/// A stream of compressed data
+///
+/// @since 2.3
+class Stream {};
+
+/// Compression levels
+///
+/// @since 2.4
+enum class Level { Fast, Best };
+
+/// Undocumented version
+int decompress(const char *data, int size);
+
+} // namespace mylib
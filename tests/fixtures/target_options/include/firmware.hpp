@@ -0,0 +1,14 @@
+#pragma once
+
+#include <board.h>
+
+#if __cplusplus < 201703L
+#error "firmware.hpp needs C++17"
+#endif
+
+namespace firmware {
+
+/// Size of a machine word on the board, in bytes
+constexpr board_word word_size() { return sizeof(board_word); }
+
+} // namespace firmware
@@ -0,0 +1,47 @@
+#pragma once
+
+namespace tpl {
+
+/// A fixed-size buffer
+template <typename T, int N>
+class Buffer {
+public:
+  /// Returns the element at `index`
+  T &at(int index);
+
+  /// Number of elements
+  int size() const &;
+};
+
+/// Returns the larger of two values
+template <typename T>
+constexpr T max(T a, T b);
+
+/// Builds a value from its arguments
+template <typename T, typename... Args>
+T make(Args &&...args);
+
+/// A pair of values
+template <typename A, typename B = A>
+struct Pair {
+  A first;
+  B second;
+};
+
+/// Types that can be added together
+template <typename T>
+concept Addable = requires(T a, T b) { a + b; };
+
+/// Sums two values
+template <typename T>
+  requires Addable<T>
+T sum(T a, T b);
+
+/// Whether values of `T` fit in a register
+template <typename T>
+constexpr bool is_small_v = sizeof(T) <= 8;
+
+/// Buffer of bytes
+using Bytes = Buffer<unsigned char, 64>;
+
+} // namespace tpl
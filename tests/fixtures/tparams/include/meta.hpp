@@ -0,0 +1,23 @@
+#pragma once
+
+namespace meta {
+
+/// A fixed-size buffer
+///
+/// @tparam T Element type
+/// @tparam N Number of elements
+template <typename T, int N>
+struct Buffer {
+    T data[N];
+};
+
+/// Call `f` with `args`
+///
+/// @tparam F Callable type
+/// @tparam Args... Types of the
+///   arguments
+/// @tparam R Not a template parameter
+template <typename F, typename... Args>
+void invoke(F f, Args... args);
+
+} // namespace meta
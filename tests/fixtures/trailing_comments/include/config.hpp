@@ -0,0 +1,37 @@
+#pragma once
+
+namespace net { //! Networking primitives
+//! shared by the clients and the servers
+
+/// Where to connect
+struct Endpoint {
+    int port;         ///< TCP port
+    const char *host; //!< Host name
+    int timeout;      /**< Timeout in seconds */
+    int retries;      /*!< Attempts before giving up */
+};
+
+/// State of a connection
+enum class State {
+    Open,   ///< Accepting data
+    Closed, //!< Shut down
+    Broken, /**< Failed, see the logs */
+    Idle,   /*!< Waiting for data */
+};
+
+} // namespace net
+
+namespace storage {
+/*! Persistent storage */
+
+/// Open the store
+void open();
+
+} // namespace storage
+
+namespace util {
+//! Helpers, clang gives this to `trim` too
+
+void trim();
+
+} // namespace util
@@ -0,0 +1,13 @@
+#pragma once
+
+/// An open file, named through its typedef
+typedef struct {
+  /// Where the file is read from
+  struct position {
+    /// Offset from the start, in bytes
+    long offset;
+  };
+
+  /// Opens the file at `path`
+  int open(const char *path);
+} handle_t;
@@ -0,0 +1,8 @@
+#pragma once
+
+namespace mylib {
+
+/// Adds two integers
+int add(int a, int b);
+
+} // namespace mylib
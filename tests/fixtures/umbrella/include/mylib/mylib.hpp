@@ -0,0 +1,11 @@
+#pragma once
+
+#include "mylib/math.hpp"
+#include "mylib/shapes.hpp"
+
+namespace mylib {
+
+/// Version of the library
+int version();
+
+} // namespace mylib
@@ -0,0 +1,15 @@
+#pragma once
+
+#include "mylib/math.hpp"
+
+namespace mylib {
+
+/// A square
+struct Square {
+  int side;
+};
+
+/// Adds two squares
+Square add(Square a, Square b);
+
+} // namespace mylib
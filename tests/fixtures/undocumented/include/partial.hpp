@@ -0,0 +1,24 @@
+#pragma once
+
+/// Documented
+void documented();
+
+void undocumented();
+
+/// Documented overload
+void both(int value);
+
+void both(double value);
+
+struct Bare {
+  /// A documented member
+  int value;
+};
+
+struct Empty {
+  int value;
+};
+
+enum Plain { A, B };
+
+using Number = int;
@@ -0,0 +1,20 @@
+#pragma once
+
+namespace mylib {
+
+/// Version of the library
+inline constexpr const char *kVersion = "1.2.3";
+
+/// Largest buffer, in bytes, of [this version](::mylib::kVersion)
+inline constexpr int kMaxBuffer = 4096;
+
+/// Settings of a session
+struct Settings {
+    /// Number of live sessions
+    static inline int instances = 0;
+
+    /// Default timeout, in seconds
+    static constexpr double kTimeout = 2.5;
+};
+
+} // namespace mylib
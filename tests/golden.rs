@@ -0,0 +1,55 @@
+mod common;
+
+use common::Fixture;
+
+fn golden(name: &str) {
+    let fixture = Fixture::new(name);
+
+    if let Some(dump) = fixture.dump() {
+        fixture.assert_golden_dump(&dump);
+    }
+
+    fixture.build();
+    fixture.assert_golden_html();
+}
+
+/// The parser output of every fixture, against its `expected/output.json`
+#[test]
+#[ignore = "needs libclang"]
+fn parser_output() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut names = std::fs::read_dir(fixtures)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+
+    for name in names {
+        let fixture = Fixture::new(&name);
+        fixture.assert_golden_dump(&fixture.require_dump());
+    }
+}
+
+#[test]
+#[ignore = "expected/html isn't checked in, generate it with UPDATE_GOLDEN=1"]
+fn basic() {
+    golden("basic");
+}
+
+#[test]
+#[ignore = "expected/html isn't checked in, generate it with UPDATE_GOLDEN=1"]
+fn templates() {
+    golden("templates");
+}
+
+#[test]
+#[ignore = "expected/html isn't checked in, generate it with UPDATE_GOLDEN=1"]
+fn c() {
+    golden("c");
+}
+
+#[test]
+#[ignore = "expected/html isn't checked in, generate it with UPDATE_GOLDEN=1"]
+fn modules() {
+    golden("modules");
+}
@@ -0,0 +1,2309 @@
+mod common;
+
+use common::Fixture;
+use serde_json::{json, Value};
+
+fn find_namespace<'a>(ns: &'a Value, name: &str) -> &'a Value {
+    ns["namespaces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["name"] == name)
+        .unwrap_or_else(|| panic!("namespace {name} not found"))
+}
+
+fn search_entries(fixture: &Fixture) -> Vec<(String, String, Option<String>)> {
+    fixture
+        .read_json("search_index.json")
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["name"].as_str().unwrap().to_string(),
+                e["link"].as_str().unwrap().to_string(),
+                e["anchor"].as_str().map(str::to_string),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn overloads_keep_distinct_entries() {
+    let fixture = Fixture::new("overloads");
+
+    if let Some(dump) = fixture.dump() {
+        assert_eq!(dump["index"]["a::f"][0]["kind"], "function");
+        assert_eq!(dump["index"]["a::g"][0]["kind"], "function");
+        assert_eq!(dump["index"]["b::f"][0]["kind"], "function");
+
+        let a = find_namespace(&dump["root"], "a");
+        let b = find_namespace(&dump["root"], "b");
+
+        let overloads = a["functions"][0]["overloads"].as_array().unwrap();
+        let anchors = overloads
+            .iter()
+            .map(|o| o["anchor"].as_str().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(a["functions"][0]["anchor"], "overload-1");
+        assert_eq!(anchors, ["overload-2", "overload-3"]);
+        assert!(overloads.iter().all(|o| o["namespace"] == "a"));
+        assert!(b["functions"][0]["overloads"].is_null());
+        assert!(b["functions"][0]["anchor"].is_null());
+    }
+
+    // Every signature on the page of `a::f` has its anchor, the first one included
+    let out = fixture.build_with(&["--debug-context", "a::f"]);
+
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let function = &context["function"];
+    assert_eq!(function["anchor"], "overload-1");
+    assert_eq!(function["overloads"][0]["anchor"], "overload-2");
+    assert_eq!(function["overloads"][1]["anchor"], "overload-3");
+
+    let entries = search_entries(&fixture);
+
+    for expected in [
+        ("a::f", "a/function.f", None),
+        ("a::f", "a/function.f", Some("overload-1")),
+        ("a::f", "a/function.f", Some("overload-2")),
+        ("a::f", "a/function.f", Some("overload-3")),
+        ("b::f", "b/function.f", None),
+    ] {
+        assert!(
+            entries.iter().any(|(name, link, anchor)| {
+                name == expected.0 && link == expected.1 && anchor.as_deref() == expected.2
+            }),
+            "missing search entry {expected:?} in {entries:?}"
+        );
+    }
+
+    // `b::f` alone needs no anchor
+    assert_eq!(entries.iter().filter(|(name, _, _)| name == "b::f").count(), 1);
+}
+
+#[test]
+fn friend_functions_get_a_page_under_their_record() {
+    let fixture = Fixture::new("basic");
+
+    if let Some(dump) = fixture.dump() {
+        // `print` is a function of the global namespace, not a member of `Shape`
+        assert!(dump["index"]["Shape::print"].is_null());
+
+        let shape = dump["root"]["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["name"] == "Shape")
+            .unwrap();
+
+        assert_eq!(shape["friends"][0]["name"], "print");
+    }
+
+    let output = fixture.build();
+
+    assert!(output.join("Shape/function.print.html").is_file());
+    assert!(search_entries(&fixture)
+        .iter()
+        .any(|(name, link, _)| name == "print" && link == "Shape/function.print"));
+    assert!(!search_entries(&fixture)
+        .iter()
+        .any(|(name, _, _)| name == "Shape::print"));
+}
+
+#[test]
+fn deprecated_entities_are_flagged_in_search() {
+    let fixture = Fixture::new("basic");
+
+    if let Some(dump) = fixture.dump() {
+        let distance = dump["root"]["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == "distance")
+            .unwrap();
+
+        assert_eq!(distance["deprecated"], "compare squared lengths instead");
+    }
+
+    fixture.build();
+
+    let index = fixture.read_json("search_index.json");
+    let deprecated = index
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["deprecated"] == true)
+        .map(|e| e["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(deprecated, ["distance"]);
+}
+
+#[test]
+fn inline_namespaces_are_collapsed_into_their_parent() {
+    let fixture = Fixture::new("overloads");
+
+    if let Some(dump) = fixture.dump() {
+        assert_eq!(dump["index"]["a::h"][0]["kind"], "function");
+        assert_eq!(dump["index"]["a::v2::h"][0]["kind"], "function");
+
+        let a = find_namespace(&dump["root"], "a");
+
+        assert!(a["namespaces"].as_array().unwrap().is_empty());
+        assert!(a["functions"].as_array().unwrap().iter().any(|f| f["name"] == "h"));
+    }
+
+    fixture.build();
+
+    let entries = search_entries(&fixture);
+
+    assert!(entries
+        .iter()
+        .any(|(name, link, _)| name == "a::h" && link == "a/function.h"));
+    assert!(!entries.iter().any(|(name, _, _)| name.starts_with("a::v2")));
+}
+
+#[test]
+fn internal_namespaces_are_not_documented() {
+    let fixture = Fixture::new("overloads");
+
+    if let Some(dump) = fixture.dump() {
+        let index = dump["index"].as_object().unwrap();
+
+        assert!(!index.contains_key("a::detail"));
+        assert!(!index.contains_key("a::detail::hidden"));
+        assert!(!index.contains_key("internal"));
+        assert!(find_namespace(&dump["root"], "a")["namespaces"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+
+    let output = fixture.build();
+
+    assert!(!output.join("a/detail").exists());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn extern_c_functions_are_documented() {
+    let fixture = Fixture::new("basic");
+
+    let dump = fixture.require_dump();
+
+    let functions = dump["root"]["functions"].as_array().unwrap();
+
+    for name in ["point_count", "shutdown"] {
+        let function = functions.iter().find(|f| f["name"] == name).unwrap();
+
+        assert_eq!(function["extern_c"], true);
+        assert_eq!(dump["index"][name][0]["kind"], "function");
+    }
+
+    assert!(functions
+        .iter()
+        .filter(|f| f["name"] == "distance")
+        .all(|f| f["extern_c"] == false));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn fields_keep_their_default_member_initializer() {
+    let fixture = Fixture::new("basic");
+
+    let dump = fixture.require_dump();
+
+    let options = dump["root"]["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["name"] == "Options")
+        .unwrap();
+
+    assert_eq!(options["fields"][0]["default_value"], "3");
+    assert_eq!(options["fields"][1]["default_value"], "{\"localhost\"}");
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn using_declarations_are_kept_on_records() {
+    let fixture = Fixture::new("basic");
+
+    let dump = fixture.require_dump();
+
+    let circle = dump["root"]["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["name"] == "Circle")
+        .unwrap();
+
+    assert_eq!(circle["inherited_ctors"], "Shape");
+    assert_eq!(circle["using_declarations"][0]["name"], "Style");
+    assert_eq!(circle["using_declarations"][0]["type"], "Shape::Style");
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn module_exports_are_flagged() {
+    let fixture = Fixture::new("modules");
+
+    let dump = fixture.require_dump();
+
+    assert_eq!(dump["root"]["modules"][0], "mylib");
+
+    let functions = dump["root"]["functions"].as_array().unwrap();
+    let exported = |name: &str| {
+        functions.iter().find(|f| f["name"] == name).unwrap()["exported"].clone()
+    };
+
+    assert_eq!(exported("answer"), true);
+    assert_eq!(exported("helper"), false);
+
+    let shapes = find_namespace(&dump["root"], "shapes");
+    assert_eq!(shapes["records"][0]["exported"], true);
+}
+
+#[test]
+fn literal_operators_get_a_readable_page() {
+    let fixture = Fixture::new("overloads");
+
+    if let Some(dump) = fixture.dump() {
+        let a = find_namespace(&dump["root"], "a");
+        let kb = a["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["udl_suffix"] == "_kb")
+            .unwrap();
+
+        assert_eq!(kb["name"], "operator\"\"_kb");
+    }
+
+    let output = fixture.build();
+
+    assert!(output.join("a/function.operator-udl-_kb.html").is_file());
+    assert!(search_entries(&fixture)
+        .iter()
+        .any(|(name, link, _)| name == "a::operator&quot;&quot;_kb"
+            && link == "a/function.operator-udl-_kb"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn deleted_copies_make_records_non_copyable() {
+    let fixture = Fixture::new("basic");
+
+    let dump = fixture.require_dump();
+
+    let records = dump["root"]["records"].as_array().unwrap();
+    let record = |name: &str| records.iter().find(|r| r["name"] == name).unwrap();
+
+    assert_eq!(record("Shape")["copyable"], false);
+    assert_eq!(record("Shape")["movable"], false);
+    assert_eq!(record("Point")["copyable"], true);
+    assert_eq!(record("Point")["movable"], true);
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn compile_commands_give_per_file_arguments() {
+    let fixture = Fixture::new("compile_commands");
+
+    let dump = fixture.require_dump();
+
+    for name in ["extras", "core", "standalone"] {
+        assert_eq!(dump["index"][name][0]["kind"], "function", "{name} is missing");
+    }
+
+    // The define only comes from the database entry of features.hpp
+    assert!(dump["index"].get("standalone_extras").is_none());
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert!(stderr.contains("standalone.hpp"));
+    assert!(!stderr.contains("features.hpp"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn parallel_parsing_matches_sequential_parsing() {
+    let fixture = Fixture::new("parallel");
+
+    fixture.require_dump();
+
+    let dump = |jobs: &str| -> Value {
+        let out = fixture.cppdoc(&["--dump-json", "--jobs", jobs]);
+        assert!(out.status.success());
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let sequential = dump("1");
+
+    for _ in 0..3 {
+        assert_eq!(dump("3"), sequential);
+    }
+
+    let geo = find_namespace(&sequential["root"], "geo");
+    let records = geo["records"].as_array().unwrap();
+    let scale = &geo["functions"][0];
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(geo["functions"].as_array().unwrap().len(), 1);
+    assert_eq!(scale["anchor"], "overload-1");
+    assert_eq!(scale["overloads"][0]["anchor"], "overload-2");
+    assert_eq!(scale["overloads"][1]["anchor"], "overload-3");
+    assert_eq!(sequential["files"]["include/part2.hpp"][0], "geo::Shape2");
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn unchanged_files_are_read_from_the_parse_cache() {
+    let fixture = Fixture::new("parallel");
+
+    let first = fixture.require_dump();
+
+    assert!(fixture.dir.join(".cppdoc-cache").is_dir());
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    let cached: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    assert_eq!(cached, first);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cached parse of 3 unchanged files"));
+
+    // An edited file is parsed again, the others still come from the cache
+    let header = fixture.dir.join("include/part3.hpp");
+    let source = std::fs::read_to_string(&header).unwrap();
+    std::fs::write(&header, source.replace("struct Shape3", "struct Triangle")).unwrap();
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    let edited: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    assert_eq!(edited["index"]["geo::Triangle"][0]["kind"], "record");
+    assert!(edited["index"].get("geo::Shape3").is_none());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("cached parse of 2 unchanged files"));
+
+    for flag in ["--no-cache", "--fresh"] {
+        let out = fixture.cppdoc(&["--dump-json", flag]);
+        assert!(!String::from_utf8_lossy(&out.stderr).contains("cached parse"));
+    }
+
+    // Entries are named after the SHA-256 of what the parse depends on
+    for entry in std::fs::read_dir(fixture.dir.join(".cppdoc-cache")).unwrap() {
+        let name = entry.unwrap().file_name().into_string().unwrap();
+        let hash = name.strip_suffix(".json").unwrap();
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    // A configuration change invalidates them all
+    fixture.edit_config("[output]", "[output]\nemit_source = true");
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("cached parse"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn parse_errors_are_reported_and_fail_strict_builds() {
+    let fixture = Fixture::new("diagnostics");
+
+    let dump = fixture.require_dump();
+
+    assert_eq!(dump["index"]["after"][0]["kind"], "function");
+
+    let out = fixture.cppdoc(&["--dump-json", "--no-cache"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert!(stderr.contains("broken.hpp:3:10: 'missing.hpp' file not found"));
+    assert!(stderr.contains("1 errors, 0 warnings while parsing"));
+    assert!(!stderr.contains("pragma once"));
+
+    let out = fixture.cppdoc(&["--dump-json", "--strict"]);
+    assert!(!out.status.success());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn input_globs_are_deduplicated_and_filtered() {
+    let fixture = Fixture::new("parallel");
+    fixture.edit_config(
+        "glob = \"include/*.hpp\"",
+        "globs = [\"include/part1.hpp\", \"include/*.hpp\"]\nexclude = [\"**/part3.hpp\"]",
+    );
+
+    let dump = fixture.require_dump();
+
+    let files = dump["files"].as_object().unwrap().keys().collect::<Vec<_>>();
+
+    assert_eq!(files, ["include/part1.hpp", "include/part2.hpp"]);
+    assert!(dump["index"].get("geo::Shape3").is_none());
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Found 2 input files"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn included_headers_are_documented_once() {
+    let fixture = Fixture::new("umbrella");
+
+    let dump = fixture.require_dump();
+
+    let mylib = find_namespace(&dump["root"], "mylib");
+    let count = |kind: &str, name: &str| {
+        mylib[kind]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["name"] == name)
+            .count()
+    };
+
+    assert_eq!(count("records", "Square"), 1);
+    assert_eq!(count("functions", "version"), 1);
+
+    // Both overloads, once each, although math.hpp is included three times
+    let add = &mylib["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "add")
+        .unwrap();
+    assert_eq!(count("functions", "add"), 1);
+    assert_eq!(add["overloads"].as_array().unwrap().len(), 1);
+
+    assert_eq!(dump["files"]["include/mylib/shapes.hpp"][0], "mylib::Square");
+    assert!(dump.get("headers").is_none());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn input_sets_have_their_own_arguments() {
+    let fixture = Fixture::new("sets");
+
+    let dump = fixture.require_dump();
+
+    assert_eq!(dump["index"]["start"][0]["kind"], "function");
+    assert_eq!(dump["index"]["Loadable"][0]["kind"], "concept");
+
+    // Both sets define `Version`, the first definition is kept
+    let versions = dump["root"]["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r["name"] == "Version")
+        .collect::<Vec<_>>();
+
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0]["fields"].as_array().unwrap().len(), 2);
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("“Version” is defined by the files of input sets 1 and 2"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn excluded_symbols_are_not_documented() {
+    let fixture = Fixture::new("parallel");
+    fixture.edit_config("[output]", "[output]\nexclude_symbols = [\"^geo::Shape2$\"]");
+
+    let dump = fixture.require_dump();
+
+    let geo = find_namespace(&dump["root"], "geo");
+
+    assert!(dump["index"].get("geo::Shape2").is_none());
+    assert_eq!(dump["index"]["geo::Shape3"][0]["kind"], "record");
+    assert!(geo["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["name"] != "Shape2"));
+
+    // Only the exact name matches, the overload taking a `Shape2` stays
+    assert_eq!(geo["functions"][0]["overloads"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn undocumented_symbols_are_hidden_on_request() {
+    let fixture = Fixture::new("undocumented");
+
+    let dump = fixture.require_dump();
+
+    for name in ["documented", "both", "Bare"] {
+        assert!(dump["index"].get(name).is_some(), "{name} is missing");
+    }
+
+    for name in ["undocumented", "Empty", "Plain", "Number"] {
+        assert!(dump["index"].get(name).is_none(), "{name} isn't hidden");
+    }
+
+    let both = dump["root"]["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "both")
+        .unwrap();
+    assert!(both["overloads"].is_null());
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("5 undocumented symbols are hidden by output.require_comment"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn locations_are_relative_to_the_source_root() {
+    let fixture = Fixture::new("parallel");
+    fixture.edit_config("[input]", "[input]\nsource_root = \"include\"");
+
+    let dump = fixture.require_dump();
+
+    let geo = find_namespace(&dump["root"], "geo");
+    let scale = &geo["functions"][0];
+
+    assert_eq!(geo["location"]["file"], "part1.hpp");
+    assert_eq!(geo["location"]["line"], 3);
+    assert_eq!(scale["location"]["line"], 11);
+    assert_eq!(scale["overloads"][1]["location"]["file"], "part3.hpp");
+}
+
+#[test]
+fn source_pages_have_line_anchors() {
+    let fixture = Fixture::new("parallel");
+    fixture.edit_config("[output]", "[output]\nemit_source = true\nmax_source_size = 200");
+
+    // part3.hpp goes over the size limit
+    let header = fixture.dir.join("include/part3.hpp");
+    let padding = format!("{}\n", "// padding ".repeat(20));
+    std::fs::write(&header, std::fs::read_to_string(&header).unwrap() + &padding).unwrap();
+
+    fixture.build();
+
+    let page = fixture.read_output("src/include/part1.hpp.html");
+    assert!(page.contains("id=\"L11\""));
+    assert!(!fixture
+        .output_dir()
+        .join("src/include/part3.hpp.html")
+        .exists());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn source_pages_stay_in_the_output_directory() {
+    let fixture = Fixture::new("parallel");
+
+    // vendor/extra.hpp is outside the source root, and is globbed by its absolute path
+    let header = fixture.dir.join("vendor/extra.hpp");
+    std::fs::create_dir_all(header.parent().unwrap()).unwrap();
+    std::fs::write(&header, "namespace vendor {\n/// Extra\nint extra();\n}\n").unwrap();
+
+    fixture.edit_config(
+        "glob = \"include/*.hpp\"",
+        &format!(
+            "globs = [\"include/*.hpp\", \"{}\"]\nsource_root = \"include\"",
+            header.display()
+        ),
+    );
+    fixture.edit_config("[output]", "[output]\nemit_source = true");
+
+    fixture.require_dump();
+
+    fixture.build();
+
+    assert!(fixture.read_output("src/part1.hpp.html").contains("id=\"L1\""));
+    assert!(!fixture.dir.join("vendor/extra.hpp.html").exists());
+    assert!(!fixture.output_dir().join("src/vendor").exists());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn include_paths_prefer_the_definition() {
+    let fixture = Fixture::new("umbrella");
+    fixture.edit_config("[output]", "[output]\ninclude_prefix_strip = \"include/\"");
+
+    // math.hpp is read first and only has a forward declaration of Square
+    let math = fixture.dir.join("include/mylib/math.hpp");
+    let header = std::fs::read_to_string(&math).unwrap();
+    let header = header.replace(
+        "namespace mylib {\n",
+        "namespace mylib {\n\nstruct Square;\n",
+    );
+    std::fs::write(&math, header).unwrap();
+
+    let dump = fixture.require_dump();
+
+    let mylib = find_namespace(&dump["root"], "mylib");
+    let square = &mylib["records"][0];
+    let version = mylib["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "version")
+        .unwrap();
+
+    assert_eq!(square["name"], "Square");
+    assert_eq!(square["include_path"], "mylib/shapes.hpp");
+    assert_eq!(version["include_path"], "mylib/mylib.hpp");
+}
+
+#[test]
+fn builds_are_reproducible() {
+    let fixture = Fixture::new("basic");
+
+    fixture.build();
+    let first = fixture.read_output("search_index.json");
+
+    fixture.build();
+    let second = fixture.read_output("search_index.json");
+
+    assert_eq!(first, second);
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn redeclarations_are_merged() {
+    let fixture = Fixture::new("redeclarations");
+
+    let dump = fixture.require_dump();
+
+    let io = find_namespace(&dump["root"], "io");
+    let function = |name: &str| {
+        io["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == name)
+            .unwrap()
+    };
+
+    // The definition of the .cpp doesn't repeat the default argument
+    let open = function("open");
+    assert!(open["overloads"].is_null());
+    assert_eq!(open["parameters"][1]["default_value"], "0");
+    assert_eq!(
+        open["comment"]["description"],
+        "Opens a file\n\nReturns a closed handle on failure."
+    );
+
+    // The comments of the definitions are kept, each overload is listed once
+    let close = function("close");
+    assert_eq!(close["comment"]["description"], "Closes the file");
+    assert_eq!(close["overloads"].as_array().unwrap().len(), 1);
+    assert_eq!(close["overloads"][0]["comment"]["since"], "2.0");
+
+    let records = io["records"].as_array().unwrap();
+    let record = |name: &str| records.iter().find(|r| r["name"] == name).unwrap();
+    assert_eq!(records.len(), 3);
+
+    // The forward declaration doesn't hide the fields of the definition
+    let file = record("File");
+    assert_eq!(file["fields"][0]["name"], "fd");
+    assert_eq!(file["comment"]["description"], "A file handle");
+
+    // An empty tag struct is a definition, the forward declaration of file.cpp doesn't replace it
+    let read_only = record("ReadOnly");
+    assert_eq!(read_only["comment"]["description"], "Opens the file read-only");
+    assert!(read_only["forward_declaration"].is_null());
+
+    // The specialization adds the members the primary template doesn't have
+    let options = record("Options");
+    let names = |list: &str| -> Vec<String> {
+        options[list]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(names("fields"), ["flags", "mode"]);
+    assert_eq!(names("static_fields"), ["default_mode"]);
+    assert_eq!(options["ctor"].as_array().unwrap().len(), 1);
+    assert_eq!(options["ctor"][0]["overloads"].as_array().unwrap().len(), 1);
+    assert_eq!(options["nested"][0]["Record"]["name"], "Lock");
+    assert!(options["dtor"].is_object());
+
+    // file.hpp is parsed with options.hpp and file.cpp too, its entities are listed once
+    assert_eq!(io["enums"].as_array().unwrap().len(), 1);
+    assert_eq!(io["aliases"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn single_nested_types_are_kept() {
+    let fixture = Fixture::new("nested");
+
+    let dump = fixture.require_dump();
+
+    let result = &dump["root"]["records"][0];
+    let nested = result["nested"].as_array().unwrap();
+
+    assert_eq!(nested.len(), 2);
+    assert_eq!(nested[0]["Record"]["name"], "Location");
+    assert_eq!(nested[1]["Enum"]["name"], "Error");
+    assert_eq!(dump["index"]["Result::Location"][0]["kind"], "record");
+    assert_eq!(dump["index"]["Result::Error"][0]["kind"], "enum");
+}
+
+#[test]
+fn unknown_root_namespaces_suggest_the_closest_one() {
+    let fixture = Fixture::new("parallel");
+
+    fixture.edit_config("[output]", "[output]\nroot_namespace = \"goe\"");
+
+    let out = fixture.run(&[]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert!(!out.status.success());
+    assert!(stderr.contains("Root namespace “goe”"));
+    assert!(stderr.contains("Did you mean “geo”?"));
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn unusual_declarations_are_parsed() {
+    let fixture = Fixture::new("pathological");
+
+    let dump = fixture.require_dump();
+
+    let weird = find_namespace(&dump["root"], "weird");
+    let record = |name: &str| {
+        weird["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["name"] == name)
+            .unwrap_or_else(|| panic!("record {name} not found"))
+    };
+
+    let packed = record("Packed")["fields"].as_array().unwrap();
+    assert!(packed.iter().any(|f| f["name"] == "a"));
+    assert!(packed.iter().any(|f| f["name"] == "b"));
+
+    assert_eq!(record("Variant")["fields"][0]["type"], "struct");
+    assert_eq!(record("Variant")["fields"][2]["type"], "enum");
+    assert_eq!(dump["index"]["weird::callback"][0]["kind"], "function");
+    assert_eq!(dump["index"]["weird::Handler"][0]["kind"], "alias");
+}
+
+#[test]
+fn namespace_aliases_point_to_their_target() {
+    let fixture = Fixture::new("namespace_aliases");
+
+    if let Some(dump) = fixture.dump() {
+        let mylib = find_namespace(&dump["root"], "mylib");
+        let alias = &mylib["namespace_aliases"][0];
+
+        assert_eq!(alias["name"], "v");
+        assert_eq!(alias["target"], "mylib::v2");
+        assert_eq!(alias["comment"]["description"], "The current version of the API");
+        assert_eq!(dump["index"]["mylib::v"][0]["kind"], "namespace alias");
+    }
+
+    fixture.build();
+
+    let entries = search_entries(&fixture);
+    let (_, link, _) = entries.iter().find(|(name, _, _)| name == "mylib::v").unwrap();
+
+    assert_eq!(link, "mylib/v2/index");
+}
+
+#[test]
+fn aliases_keep_the_aliased_type() {
+    let fixture = Fixture::new("aliases");
+
+    if let Some(dump) = fixture.dump() {
+        let type_of = |name: &str| {
+            dump["root"]["aliases"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|a| a["name"] == name)
+                .unwrap()["type"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(type_of("Widgets"), "List<Widget>");
+        assert_eq!(type_of("Index"), "Map<int, List<Widget *>>");
+        assert_eq!(type_of("Callback"), "Function<void (int, const Widget &)>");
+        assert_eq!(type_of("Handler"), "void (*)(int)");
+        assert_eq!(type_of("WidgetPtr"), "Widget *");
+    }
+
+    fixture.build();
+
+    let page = fixture.read_output("alias.Callback.html");
+    assert!(page.contains("Function&lt;void (int, const Widget &amp;)&gt;"));
+}
+
+#[test]
+fn method_overloads_get_distinct_anchors() {
+    let fixture = Fixture::new("method_overloads");
+
+    if let Some(dump) = fixture.dump() {
+        let file = &dump["root"]["records"][0];
+        let methods = file["methods"].as_array().unwrap();
+
+        assert_eq!(methods.len(), 2);
+
+        let open = methods.iter().find(|m| m["name"] == "open").unwrap();
+        let overloads = open["overloads"].as_array().unwrap();
+
+        assert_eq!(open["anchor"], "method-open-1");
+        assert_eq!(overloads.len(), 1);
+        assert_eq!(overloads[0]["anchor"], "method-open-2");
+        assert_eq!(
+            overloads[0]["comment"]["description"],
+            "Opens an already open file descriptor"
+        );
+
+        let close = methods.iter().find(|m| m["name"] == "close").unwrap();
+        assert_eq!(close["anchor"], "method-close");
+
+        let ctors = file["ctor"].as_array().unwrap();
+        assert_eq!(ctors.len(), 1);
+        assert_eq!(ctors[0]["anchor"], "ctor-1");
+        assert_eq!(ctors[0]["overloads"][0]["anchor"], "ctor-2");
+    }
+
+    fixture.build();
+
+    let entries = search_entries(&fixture);
+
+    for expected in [
+        ("File::open", "record.File", Some("method-open-1")),
+        ("File::open", "record.File", Some("method-open-2")),
+        ("File::close", "record.File", Some("method-close")),
+        ("File::File", "record.File", Some("ctor-2")),
+    ] {
+        assert!(
+            entries.iter().any(|(name, link, anchor)| {
+                name == expected.0 && link == expected.1 && anchor.as_deref() == expected.2
+            }),
+            "missing search entry {expected:?} in {entries:?}"
+        );
+    }
+
+    let page = fixture.read_output("record.File.html");
+    assert!(page.contains("record.File.html#method-open-2"));
+}
+
+#[test]
+fn colliding_names_keep_every_entity() {
+    let fixture = Fixture::new("collisions");
+
+    if let Some(dump) = fixture.dump() {
+        let kinds = dump["index"]["stat"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["kind"].as_str().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds.len(), 2);
+        assert!(kinds.contains(&"record") && kinds.contains(&"function"));
+        assert_eq!(dump["index"]["store::Item"][0]["namespace"], "store");
+
+        // Collisions are reported by the parser
+        let out = fixture.cppdoc(&["--dump-json"]);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+
+        assert!(stderr.contains("“stat” names several entities"));
+        assert!(stderr.contains("include/collisions.hpp:4"));
+        assert!(stderr.contains("include/collisions.hpp:10"));
+        assert!(!stderr.contains("“Item”"));
+    }
+
+    fixture.build();
+
+    let entries = search_entries(&fixture);
+
+    for expected in [("stat", "record.stat"), ("stat", "function.stat")] {
+        assert!(
+            entries.iter().any(|(name, link, _)| name == expected.0 && link == expected.1),
+            "missing search entry {expected:?} in {entries:?}"
+        );
+    }
+
+    // Types resolve to the closest namespace first
+    let page = fixture.read_output("store/function.sell.html");
+    assert!(page.contains("store/record.Item.html"));
+}
+
+#[test]
+fn method_links_resolve_in_nested_namespaces() {
+    let fixture = Fixture::new("nested_namespaces");
+
+    if let Some(dump) = fixture.dump() {
+        let inner = find_namespace(find_namespace(&dump["root"], "outer"), "inner");
+        let canvas = &inner["records"][0];
+
+        for method in canvas["methods"].as_array().unwrap() {
+            assert_eq!(method["namespace"], "outer::inner::Canvas");
+        }
+    }
+
+    let output = fixture.build();
+
+    // Links to entity pages, without their anchor
+    let links = |page: &str| {
+        fixture
+            .read_output(page)
+            .split("href=\"/")
+            .skip(1)
+            .filter_map(|rest| rest.split(['"', '#']).next())
+            .filter(|link| link.contains("/record.") || link.contains("/function."))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    let record_links = links("outer/inner/record.Canvas.html");
+    let friend_links = links("outer/inner/Canvas/function.operator-eq-eq.html");
+
+    assert!(record_links.iter().any(|l| l == "outer/inner/record.Color.html"));
+    assert!(friend_links.iter().any(|l| l == "outer/inner/record.Canvas.html"));
+
+    for link in record_links.iter().chain(&friend_links) {
+        assert!(output.join(link).is_file(), "link to missing page {link}");
+    }
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn only_accessible_constructors_are_documented() {
+    let fixture = Fixture::new("constructors");
+
+    let dump = fixture.require_dump();
+
+    let parameter_types = |dump: &Value| {
+        let ctor = &dump["root"]["records"][0]["ctor"][0];
+
+        std::iter::once(ctor)
+            .chain(ctor["overloads"].as_array().into_iter().flatten())
+            .map(|c| {
+                (
+                    c["parameters"][0]["type"].as_str().unwrap().to_string(),
+                    c["access"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        parameter_types(&dump),
+        [("const char *".to_string(), "public".to_string())]
+    );
+
+    // Destructors follow the same rule, a private one isn't part of the interface
+    assert_eq!(dump["root"]["records"][0]["dtor"]["name"], "~Connection");
+    assert_eq!(dump["root"]["records"][1]["name"], "Handle");
+    assert!(dump["root"]["records"][1]["dtor"].is_null());
+
+    fixture.edit_config("[output]", "[output]\ninclude_protected = true");
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    let dump: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    assert_eq!(
+        parameter_types(&dump),
+        [
+            ("const char *".to_string(), "public".to_string()),
+            ("int".to_string(), "protected".to_string())
+        ]
+    );
+}
+
+#[test]
+fn empty_namespaces_are_pruned() {
+    let fixture = Fixture::new("empty_namespaces");
+
+    let output = fixture.build();
+
+    assert!(output.join("lib/index.html").is_file());
+
+    for name in ["lib/checks", "lib/outer", "lib/outer/inner"] {
+        assert!(!output.join(name).join("index.html").exists(), "{name} isn't pruned");
+    }
+
+    let entries = search_entries(&fixture);
+    assert!(entries.iter().any(|(name, _, _)| name == "lib"));
+    assert!(!entries.iter().any(|(name, _, _)| name.starts_with("lib::outer")));
+    assert!(!entries.iter().any(|(name, _, _)| name == "lib::checks"));
+
+    fixture.edit_config("[output]", "[output]\nkeep_empty_namespaces = true");
+
+    fixture.build();
+
+    assert!(output.join("lib/outer/inner/index.html").is_file());
+    assert!(search_entries(&fixture).iter().any(|(name, _, _)| name == "lib::checks"));
+}
+
+#[test]
+fn reopened_namespaces_keep_their_comment() {
+    let fixture = Fixture::new("reopened_namespaces");
+
+    if let Some(dump) = fixture.dump() {
+        let net = find_namespace(&dump["root"], "net");
+        assert_eq!(net["comment"]["description"], "Networking utilities");
+        assert_eq!(net["functions"].as_array().unwrap().len(), 2);
+    }
+
+    // The comment is the description of the index page when `net` is the root namespace
+    fixture.edit_config("[output]", "[output]\nroot_namespace = \"net\"");
+
+    fixture.build();
+
+    assert!(fixture.read_output("index.html").contains("Networking utilities"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn target_options_become_clang_arguments() {
+    let fixture = Fixture::new("target_options");
+
+    let dump = fixture.require_dump();
+
+    // input.std comes after compiler_arguments, so it wins over their -std=c++14
+    assert_eq!(dump["index"]["firmware::word_size"][0]["kind"], "function");
+
+    let out = fixture.cppdoc(&["--dump-json", "--no-cache", "--print-clang-args"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert!(stderr.contains(
+        "include/firmware.hpp: -Wno-pragma-once-outside-header -Iinclude -std=c++14 -xc++ \
+         -std=c++17 --target=armv7em-none-eabi -isystem sysroot/board"
+    ));
+    assert!(!stderr.contains("needs C++17"));
+
+    fixture.edit_config("armv7em-none-eabi", "not-a-target");
+
+    let out = fixture.cppdoc(&["--dump-json", "--no-cache", "--strict"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert!(!out.status.success());
+    assert!(stderr.contains("Unable to parse “include/firmware.hpp”"));
+}
+
+#[test]
+fn configurations_are_merged_and_tagged() {
+    let fixture = Fixture::new("configurations");
+
+    if let Some(dump) = fixture.dump() {
+        let net = find_namespace(&dump["root"], "net");
+        let functions = net["functions"].as_array().unwrap();
+
+        // Declared by both configurations, documented once
+        assert_eq!(functions.iter().filter(|f| f["name"] == "connect").count(), 1);
+
+        let connect = functions.iter().find(|f| f["name"] == "connect").unwrap();
+        assert_eq!(connect["available_in"], json!([]));
+        assert_eq!(connect["overloads"][0]["available_in"], json!(["default"]));
+
+        let connect_tls = functions.iter().find(|f| f["name"] == "connect_tls").unwrap();
+        assert_eq!(connect_tls["available_in"], json!(["tls"]));
+        assert_eq!(net["enums"][0]["available_in"], json!(["tls"]));
+
+        let connection = &net["records"][0];
+        assert_eq!(connection["available_in"], json!([]));
+
+        let methods = connection["methods"].as_array().unwrap();
+        assert_eq!(methods.len(), 2);
+        assert_eq!(methods[0]["available_in"], json!([]));
+        assert_eq!(methods[1]["available_in"], json!(["tls"]));
+
+        let fields = connection["fields"].as_array().unwrap();
+        assert_eq!(fields[0]["name"], "encrypted");
+        assert_eq!(fields[0]["available_in"], json!(["tls"]));
+
+        let certificate = &connection["nested"][0]["Record"];
+        assert_eq!(certificate["name"], "Certificate");
+        assert_eq!(certificate["available_in"], json!(["tls"]));
+        assert_eq!(certificate["fields"][0]["available_in"], json!(["tls"]));
+        assert!(dump["index"]["net::Connection::Certificate"].is_array());
+    }
+
+    fixture.build();
+
+    let page = fixture.read_output("net/record.Connection.html");
+    assert!(page.contains("encrypted = true; <span class=\"c\">// Only available when"));
+    assert!(page.contains("NET_ENABLE_TLS"));
+}
+
+#[test]
+fn inline_and_constexpr_variables_are_documented() {
+    let fixture = Fixture::new("variables");
+
+    if let Some(dump) = fixture.dump() {
+        let mylib = find_namespace(&dump["root"], "mylib");
+        let version = mylib["variables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|v| v["name"] == "kVersion")
+            .unwrap();
+
+        assert_eq!(version["type"], "const char *");
+        assert_eq!(version["initializer"], "\"1.2.3\"");
+        assert_eq!(version["inline"], true);
+        assert_eq!(version["constexpr"], true);
+        assert_eq!(dump["index"]["mylib::kVersion"][0]["kind"], "variable");
+
+        let fields = mylib["records"][0]["static_fields"].as_array().unwrap();
+        assert_eq!(fields[0]["name"], "instances");
+        assert_eq!(fields[0]["inline"], true);
+        assert_eq!(fields[0]["initializer"], "0");
+        assert_eq!(fields[1]["type"], "double");
+        assert_eq!(fields[1]["initializer"], "2.5");
+    }
+
+    fixture.build();
+
+    let page = fixture.read_output("mylib/variable.kVersion.html");
+    assert!(page.contains("<span class=\"k\">inline</span> <span class=\"k\">constexpr</span> "));
+    assert!(page.contains("kVersion = &quot;1.2.3&quot;"));
+    assert!(page.contains("class=\"defined-in\""));
+
+    let page = fixture.read_output("mylib/variable.kMaxBuffer.html");
+    assert!(page.contains("href=\"/mylib/variable.kVersion.html\""));
+}
+
+#[test]
+fn param_and_return_commands_are_documented() {
+    let fixture = Fixture::new("params");
+
+    if let Some(dump) = fixture.dump() {
+        let io = find_namespace(&dump["root"], "io");
+        let read = io["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == "read")
+            .unwrap();
+        let params = &read["comment"]["params"];
+
+        assert_eq!(params[0], json!(["buffer", "[out] Where the bytes go"]));
+        assert_eq!(params[1], json!(["timeout_ms", "Timeout in milliseconds, 0 to wait forever"]));
+        assert!(!read["comment"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("timeout_ms"));
+    }
+
+    let out = fixture.build_with(&[]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("“io::read” documents a parameter “flags” it doesn't have"));
+
+    let out = fixture.build_with(&["--debug-context", "io::read"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let table = context["parameters"].as_str().unwrap();
+    assert!(table.contains("<td><code>timeout_ms</code></td>"));
+    assert!(context["function"]["parameters"][0]["comment"]["description"]
+        .as_str()
+        .unwrap()
+        .contains("Where the bytes go"));
+
+    let out = fixture.build_with(&["--debug-context", "io::close"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let returns = context["returns"].as_str().unwrap();
+    assert!(returns.contains("Whether it was closed"));
+    assert!(returns.contains("<a href=\"/io/enum.Status.html\"><code>Status::Busy</code></a>"));
+
+    let out = fixture.build_with(&["--debug-context", "io::accept"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let exceptions = context["exceptions"].as_str().unwrap();
+    assert!(exceptions.contains("href=\"/io/record.TimeoutError.html\""));
+    assert!(exceptions.contains("When no client connects or when the handshake takes too long"));
+    assert_eq!(exceptions.matches("<tr><td>").count(), 1);
+
+    let out = fixture.build_with(&["--debug-context", "io::Socket"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(context["parameters"]["method-send"]
+        .as_str()
+        .unwrap()
+        .contains("<td><code>data</code></td>"));
+}
+
+#[test]
+fn tparam_commands_document_template_parameters() {
+    let fixture = Fixture::new("tparams");
+
+    let out = fixture.build_with(&["--debug-context", "meta::invoke"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("“meta::invoke” documents a template parameter “R” it doesn't have"));
+    assert!(!stderr.contains("“Args...”"));
+
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let table = context["template_parameters"].as_str().unwrap();
+    assert!(table.contains("<code>Args...</code>"));
+    assert!(table.contains("Types of the arguments"));
+
+    let out = fixture.build_with(&["--debug-context", "meta::Buffer"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let table = context["template_parameters"].as_str().unwrap();
+    assert!(table.contains("<code>N</code>"));
+    assert!(table.contains("Number of elements"));
+}
+
+#[test]
+fn see_commands_link_to_their_symbols() {
+    let fixture = Fixture::new("see_also");
+
+    if let Some(dump) = fixture.dump() {
+        let mylib = find_namespace(&dump["root"], "mylib");
+        assert_eq!(
+            mylib["records"][0]["comment"]["see_also"],
+            json!(["close", "::mylib::connect()"])
+        );
+    }
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let see_also = context("mylib::connect")["see_also"].as_str().unwrap().to_string();
+    assert!(see_also.contains(
+        "<a href=\"/mylib/record.Socket.html#method-close\"><code>mylib::Socket::close</code></a>"
+    ));
+    assert!(see_also.contains(
+        "<a href=\"/mylib/record.Socket.html#ctor\"><code>Socket::Socket</code></a>"
+    ));
+    assert!(see_also.contains("<a href=\"https://example.com/protocol\">the protocol</a>"));
+    assert!(see_also.contains("<li><code>missing_function</code></li>"));
+
+    let see_also = context("mylib::Socket")["see_also"].as_str().unwrap().to_string();
+    assert!(see_also.contains("href=\"/mylib/record.Socket.html#method-close\""));
+    assert!(see_also.contains("href=\"/mylib/function.connect.html\""));
+
+    assert!(context("mylib::State")["see_also"]
+        .as_str()
+        .unwrap()
+        .contains("href=\"/mylib/function.connect.html\""));
+    assert!(context("mylib::Handle")["see_also"]
+        .as_str()
+        .unwrap()
+        .contains("href=\"/mylib/record.Socket.html\""));
+
+    // Unresolved entries only warn with output.strict_see_also
+    let out = fixture.build_with(&[]);
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("missing_function"));
+
+    fixture.edit_config("[output]", "[output]\nstrict_see_also = true");
+
+    let out = fixture.build_with(&[]);
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("“@see missing_function” in “mylib” names no documented symbol"));
+}
+
+#[test]
+fn admonitions_become_callouts() {
+    let fixture = Fixture::new("admonitions");
+
+    if let Some(dump) = fixture.dump() {
+        let buffer = &find_namespace(&dump["root"], "mylib")["records"][0];
+        assert_eq!(buffer["comment"]["notes"], json!([["attention", "Owns its memory"]]));
+
+        let flush = &buffer["methods"][0]["comment"];
+        let description = flush["description"].as_str().unwrap();
+        assert!(description.starts_with("Write the buffer to `fd`\n"));
+        assert!(description.ends_with("\nReturns once every byte is written."));
+        assert!(!description.contains("thread-safe"));
+        assert_eq!(
+            flush["notes"][0],
+            json!(["warning", "Not thread-safe:\n- lock the buffer first\n- or use `flush_sync`"])
+        );
+        assert_eq!(flush["notes"][2][0], "bug");
+    }
+
+    let out = fixture.build_with(&["--debug-context", "mylib::Buffer"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    assert!(context["record"]["comment"]["description"]
+        .as_str()
+        .unwrap()
+        .contains(
+            "<div class=\"admonition attention\">\
+             <p class=\"admonition-title\">Attention</p>"
+        ));
+
+    let description = context["record"]["methods"][0]["comment"]["description"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let warning = description.find("<div class=\"admonition warning\">").unwrap();
+    assert!(description[warning..].starts_with(
+        "<div class=\"admonition warning\"><p class=\"admonition-title\">Warning</p>\
+         <p>Not thread-safe:</p>\n<ul>\n<li>lock the buffer first</li>"
+    ));
+    assert!(description.contains("<div class=\"admonition note\">"));
+    assert!(description.contains("<div class=\"admonition bug\">"));
+}
+
+#[test]
+fn deprecated_commands_are_merged_with_the_attribute() {
+    let fixture = Fixture::new("deprecation");
+
+    if let Some(dump) = fixture.dump() {
+        let net = find_namespace(&dump["root"], "net");
+        let function = |name: &str| {
+            net["functions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|f| f["name"] == name)
+                .unwrap()
+                .clone()
+        };
+
+        assert_eq!(
+            function("connect")["comment"]["deprecated"],
+            "Use Connection::open() instead, it reports errors."
+        );
+        assert_eq!(function("connect")["deprecated"], Value::Null);
+        assert_eq!(function("close")["deprecated"], "");
+        assert_eq!(net["aliases"][0]["comment"]["deprecated"], "");
+    }
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    assert_eq!(
+        context("net::connect")["deprecation"],
+        "Use <a href=\"/net/record.Connection.html#method-open\"><code>Connection::open()</code>\
+         </a> instead, it reports errors."
+    );
+    assert_eq!(
+        context("net::close")["deprecation"],
+        "Destroying a <a href=\"/net/record.Connection.html\"><code>net::Connection</code></a> \
+         closes it"
+    );
+    assert_eq!(context("net::wait")["deprecation"], "poll the connection instead");
+    assert_eq!(context("net::Handle")["deprecation"], "");
+    assert_eq!(context("net::Connection")["deprecation"], Value::Null);
+
+    fixture.build();
+
+    let index = fixture.read_json("search_index.json");
+    let mut deprecated = index
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["deprecated"] == true)
+        .map(|e| e["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    deprecated.sort();
+
+    assert_eq!(deprecated, ["net::Handle", "net::close", "net::connect", "net::wait"]);
+}
+
+#[test]
+fn since_commands_become_version_badges() {
+    let fixture = Fixture::new("since");
+
+    if let Some(dump) = fixture.dump() {
+        assert_eq!(dump["current_version"], "2.4");
+    }
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    // Without output.highlight_since, the badges of the current version are new
+    assert_eq!(
+        context("mylib::Level")["since_badge"],
+        "<span class=\"since new\" title=\"Since 2.4\">2.4</span>"
+    );
+    assert_eq!(
+        context("mylib::Stream")["since_badge"],
+        "<span class=\"since\" title=\"Since 2.3\">2.3</span>"
+    );
+    assert_eq!(context("mylib::decompress")["since_badge"], Value::Null);
+
+    let badges = &context("mylib")["since_badges"];
+    assert_eq!(badges["compress"], "<span class=\"since\" title=\"Since 2.0\">2.0</span>");
+    assert!(badges["Level"].as_str().unwrap().contains("since new"));
+    assert_eq!(badges["decompress"], Value::Null);
+
+    fixture.edit_config("[output]", "[output]\nhighlight_since = \"2.2\"");
+
+    assert!(context("mylib::Stream")["since_badge"].as_str().unwrap().contains("since new"));
+    assert!(!context("mylib::compress")["since_badge"].as_str().unwrap().contains("new"));
+}
+
+#[test]
+fn api_version_drops_newer_entities() {
+    let fixture = Fixture::new("since");
+
+    let names = |version: &str| {
+        let out = fixture.build_with(&["--dump-json", "--api-version", version]);
+        let dump: Value = serde_json::from_slice(&out.stdout).unwrap();
+        let mylib = &dump["root"]["namespaces"][0];
+
+        let mut names = ["functions", "records", "enums"]
+            .iter()
+            .flat_map(|kind| mylib[kind].as_array().unwrap().clone())
+            .map(|entity| entity["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        assert_eq!(
+            dump["index"].get("mylib::Level").is_some(),
+            names.iter().any(|n| n == "Level")
+        );
+
+        names
+    };
+
+    assert_eq!(names("2.3"), ["Stream", "compress", "decompress"]);
+    assert_eq!(names("1.0"), ["decompress"]);
+    assert_eq!(names("2.4"), ["Level", "Stream", "compress", "decompress"]);
+
+    // A prerelease comes before its release
+    assert_eq!(names("2.4.0-rc1"), ["Stream", "compress", "decompress"]);
+}
+
+#[test]
+fn groups_list_their_members() {
+    let fixture = Fixture::new("groups");
+
+    if let Some(dump) = fixture.dump() {
+        let groups = dump["groups"].as_array().unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0]["name"], "net");
+        assert_eq!(groups[0]["title"], "Networking");
+        assert_eq!(groups[0]["comment"]["description"], "Talking to other machines");
+        assert_eq!(groups[0]["members"], json!(["mylib::Socket", "mylib::connect"]));
+        assert_eq!(groups[1]["members"], json!(["mylib::Format", "mylib::Socket"]));
+        assert_eq!(groups[2]["members"], json!([]));
+    }
+
+    let out = fixture.build_with(&["--dump-json"]);
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("“mylib::retry” is in the group “networking” that no @defgroup defines"));
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let net = context("net");
+    assert_eq!(net["title"], "Networking");
+    let content = net["content"].as_str().unwrap();
+    assert!(content.contains(
+        "<tr><td><a href=\"/mylib/record.Socket.html\"><code>mylib::Socket</code></a></td>\
+         <td><p>A connected socket</p>\n</td></tr>"
+    ));
+    assert!(content.contains("<a href=\"/mylib/function.connect.html\">"));
+
+    let topics = context("")["groups"].as_str().unwrap().to_string();
+    assert!(topics.contains("<li><a href=\"/group.net.html\">Networking</a>"));
+    assert!(topics.contains("<li><a href=\"/group.misc.html\">misc</a></li>"));
+
+    fixture.build();
+
+    let index = fixture.read_json("search_index.json");
+    let groups = index
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["kind"] == "group")
+        .map(|e| (e["name"].as_str().unwrap(), e["link"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        groups,
+        [
+            ("Networking", "group.net"),
+            ("Serialization", "group.serialization"),
+            ("misc", "group.misc")
+        ]
+    );
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn copydoc_copies_documentation() {
+    let fixture = Fixture::new("copydoc");
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    let dump = fixture.require_dump();
+
+    let functions = find_namespace(&dump["root"], "mylib")["functions"].clone();
+    let comment = |name: &str| {
+        functions
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == name)
+            .unwrap()["comment"]
+            .clone()
+    };
+
+    let read = comment("read");
+    assert_eq!(read["brief"], "Read from a file");
+    assert_eq!(read["description"], "Blocks until `size` bytes are read.");
+    assert_eq!(read["params"], json!([["fd", "The file descriptor"], ["size", "How many bytes"]]));
+    assert_eq!(read["returns"], "The bytes read");
+    assert!(read.get("copydoc").is_none());
+
+    let retrying = comment("read_retrying");
+    assert_eq!(
+        retrying["description"],
+        "Blocks until `size` bytes are read.\n\nRetries on `EINTR`."
+    );
+    assert_eq!(
+        retrying["params"],
+        json!([["size", "How many bytes, at most 4096"], ["fd", "The file descriptor"]])
+    );
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "“mylib::lost” copies the documentation of “nowhere” with @copydoc, which isn't found"
+    ));
+    assert!(stderr.contains("which copies it back with @copydoc, ignored"));
+}
+
+#[test]
+fn autolink_links_qualified_names_in_comments() {
+    let fixture = Fixture::new("autolink");
+
+    let description = |fixture: &Fixture| -> String {
+        let out = fixture.build_with(&["--debug-context", "mylib::Socket"]);
+        let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+        context["record"]["comment"]["description"].as_str().unwrap().to_string()
+    };
+
+    let linked = description(&fixture);
+    assert!(linked.contains("<a href=\"/mylib/function.connect.html\">::mylib::connect()</a>"));
+    assert!(linked.contains(
+        "<a href=\"/mylib/record.Socket.html#method-close\">mylib::Socket::close()</a>"
+    ));
+    assert!(linked
+        .contains("<a href=\"/mylib/record.Socket.html\"><code>mylib::Socket</code></a>"));
+
+    // Link texts, code blocks and names that only start like a symbol are left alone
+    assert!(linked.contains("<a href=\"https://example.com/socket\">mylib::Socket docs</a>"));
+    assert_eq!(linked.matches("href=\"/mylib/record.Socket.html\"").count(), 1);
+    assert_eq!(linked.matches("href=\"/mylib/function.connect.html\"").count(), 1);
+    assert!(linked.contains("Not mylib::Sockets though."));
+
+    fixture.edit_config("autolink = true", "");
+
+    let plain = description(&fixture);
+    assert!(!plain.contains("href=\"/mylib/"));
+    assert!(plain.contains("<code>mylib::Socket</code>"));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn trailing_and_exclamation_comments_are_attached() {
+    let fixture = Fixture::new("trailing_comments");
+
+    let dump = fixture.require_dump();
+
+    let descriptions = |entries: &Value| -> Vec<String> {
+        entries
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["comment"]["description"].as_str().unwrap_or_default().to_string())
+            .collect()
+    };
+
+    let net = find_namespace(&dump["root"], "net");
+    assert_eq!(
+        net["comment"]["description"],
+        "Networking primitives\nshared by the clients and the servers"
+    );
+
+    assert_eq!(
+        descriptions(&net["records"][0]["fields"]),
+        ["TCP port", "Host name", "Timeout in seconds", "Attempts before giving up"]
+    );
+    assert_eq!(
+        descriptions(&net["enums"][0]["values"]),
+        ["Accepting data", "Shut down", "Failed, see the logs", "Waiting for data"]
+    );
+
+    // The header is the namespace's, not the one of its first declaration
+    assert_eq!(net["records"][0]["comment"]["description"], "Where to connect");
+
+    let storage = find_namespace(&dump["root"], "storage");
+    assert_eq!(storage["comment"]["description"], "Persistent storage");
+    assert_eq!(storage["functions"][0]["comment"]["description"], "Open the store");
+
+    let util = find_namespace(&dump["root"], "util");
+    assert_eq!(util["comment"]["description"], "Helpers, clang gives this to `trim` too");
+    assert_eq!(util["functions"][0]["comment"], Value::Null);
+}
+
+#[test]
+fn examples_are_embedded_and_indexed() {
+    let fixture = Fixture::new("examples");
+
+    if let Some(dump) = fixture.dump() {
+        let net = find_namespace(&dump["root"], "net");
+        let serve = &net["records"][0]["methods"][0]["comment"]["examples"];
+        assert_eq!(serve[0]["file"], "serve_once.cpp");
+        assert_eq!(serve[0]["doctest"], true);
+        assert_eq!(serve[1]["file"], "tcp_echo.cpp");
+        assert!(serve[1].get("doctest").is_none());
+        assert!(serve[1]["code"].as_str().unwrap().contains("net::listen(7)"));
+
+        assert_eq!(dump["examples"][1]["file"], "tcp_echo.cpp");
+        assert_eq!(dump["examples"][1]["used_by"], json!(["net::Server", "net::Server::serve"]));
+    }
+
+    let out = fixture.build_with(&["--dump-json"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "“net::listen” shows the example “missing.cpp”, which isn't in doctests.example_dirs"
+    ));
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let description = context("net::Server")["record"]["comment"]["description"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(description.contains("<h2 id=\"examples\">Examples</h2>"));
+    assert!(description.contains(
+        "<a href=\"/examples.html#example-tcp_echo-cpp\"><code>tcp_echo.cpp</code></a>"
+    ));
+
+    let index = context("examples");
+    assert_eq!(index["title"], "Examples");
+    let content = index["content"].as_str().unwrap();
+    assert!(content.contains("<h2 id=\"example-serve_once-cpp\">serve_once.cpp</h2>"));
+    assert!(content.contains("<a href=\"/net/record.Server.html\"><code>net::Server</code></a>"));
+}
+
+#[test]
+fn conditions_are_listed_before_the_description() {
+    let fixture = Fixture::new("conditions");
+
+    if let Some(dump) = fixture.dump() {
+        let fill = &find_namespace(&dump["root"], "mylib")["functions"][0]["comment"];
+        assert_eq!(
+            fill["preconditions"],
+            json!([
+                "buffer != nullptr && size < capacity",
+                "`std::vector<int>` or std::vector<int> values are sorted"
+            ])
+        );
+        assert_eq!(fill["postconditions"], json!(["buffer is **filled**"]));
+    }
+
+    let out = fixture.build_with(&["--debug-context", "mylib::fill"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let description = context["function"]["comment"]["description"].as_str().unwrap();
+
+    assert!(description.starts_with(
+        "<div class=\"preconditions\"><h3>Preconditions</h3><ul>\
+         <li>buffer != nullptr &amp;&amp; size &lt; capacity</li>\
+         <li><code>std::vector&lt;int&gt;</code> or std::vector&lt;int&gt; values are sorted</li>\
+         </ul></div>\
+         <div class=\"postconditions\"><h3>Postconditions</h3><ul>\
+         <li>buffer is <strong>filled</strong></li></ul></div>"
+    ));
+    assert!(description.contains("Copy <code>size</code> bytes"));
+}
+
+#[test]
+fn impl_commands_link_both_ways() {
+    let fixture = Fixture::new("implements");
+
+    if let Some(dump) = fixture.dump() {
+        let shapes = find_namespace(&dump["root"], "shapes");
+        let circle = shapes["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["name"] == "Circle")
+            .unwrap();
+        assert_eq!(circle["comment"]["impl"], json!(["shapes::Drawable"]));
+        assert_eq!(circle["methods"][0]["comment"]["impl"], json!(["shapes::Drawable::draw"]));
+        assert_eq!(
+            shapes["functions"][0]["comment"]["impl"],
+            json!(["shapes::Drawable", "Printable"])
+        );
+        assert_eq!(
+            dump["implementers"]["shapes::Drawable"],
+            json!(["shapes::sketch", "shapes::Circle"])
+        );
+    }
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let drawable = "<a href=\"/shapes/record.Drawable.html\"><code>shapes::Drawable</code></a>";
+    assert_eq!(
+        context("shapes::Circle")["implements"],
+        format!("<ul class=\"implements\"><li>{drawable}</li></ul>")
+    );
+    assert_eq!(
+        context("shapes::sketch")["implements"],
+        format!("<ul class=\"implements\"><li>{drawable}</li><li><code>Printable</code></li></ul>")
+    );
+
+    let implemented_by = context("shapes::Drawable")["implemented_by"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(implemented_by.contains("<a href=\"/shapes/record.Circle.html\">"));
+    assert!(implemented_by.contains("<a href=\"/shapes/function.sketch.html\">"));
+
+    // Names of nothing documented only warn in strict mode
+    let warning =
+        "“shapes::sketch” implements “Printable” with @impl, which names nothing documented";
+    let out = fixture.run(&["--dump-json"]);
+    assert!(!String::from_utf8_lossy(&out.stderr).contains(warning));
+    let out = fixture.run(&["--dump-json", "--strict"]);
+    assert!(String::from_utf8_lossy(&out.stderr).contains(warning));
+}
+
+#[test]
+fn internal_sections_are_hidden_unless_shown() {
+    let fixture = Fixture::new("internal");
+
+    if let Some(dump) = fixture.dump() {
+        let io = find_namespace(&dump["root"], "io");
+        assert_eq!(io["functions"][0]["comment"]["internal"], true);
+    }
+
+    let description = |fixture: &Fixture| -> String {
+        let out = fixture.build_with(&["--debug-context", "io::Buffer"]);
+        let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+        context["record"]["comment"]["description"].as_str().unwrap().to_string()
+    };
+
+    let hidden = description(&fixture);
+    assert!(hidden.contains("Not thread-safe."));
+    assert!(!hidden.contains("benchmarks"));
+
+    fixture.edit_config("[output]", "[output]\nshow_internal = true");
+
+    assert!(description(&fixture).contains(
+        "<div class=\"admonition internal\"><p class=\"admonition-title\">Internal</p>\
+         <p>Grows by doubling, see the benchmarks in <code>bench/</code>.</p>"
+    ));
+
+    // Entities documented only with @internal count as undocumented
+    fixture.edit_config("[output]", "[output]\nrequire_comment = true");
+
+    let out = fixture.build_with(&["--dump-json"]);
+    let dump: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let io = find_namespace(&dump["root"], "io");
+    assert_eq!(io["functions"], json!([]));
+    assert_eq!(io["records"][0]["name"], "Buffer");
+}
+
+#[test]
+fn refs_link_to_symbols_and_anchors() {
+    let fixture = Fixture::new("refs");
+
+    let out = fixture.build_with(&["--debug-context", "net::connect"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    let description = context["function"]["comment"]["description"].as_str().unwrap();
+    assert!(description.contains(
+        "Follows <a href=\"/net/record.Socket.html#threading-model\">the threading model</a> \
+         of <a href=\"/net/record.Socket.html\"><code>Socket</code></a>"
+    ));
+    assert!(description.contains("<a href=\"/guide.md.html#retry-policy\">retry-policy</a>"));
+    assert!(description.contains("<code>Reconnector</code>"));
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "“net::connect” refers to “Reconnector” with @ref, which is neither a documented symbol \
+         nor an anchor"
+    ));
+    assert!(stderr.contains(
+        "The anchor “threading-model” of “guide.md” is already defined by “net::Socket”"
+    ));
+
+    let context = |name: &str| -> Value {
+        let out = fixture.build_with(&["--debug-context", name]);
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let socket = context("net::Socket");
+    let description = socket["record"]["comment"]["description"].as_str().unwrap();
+    assert!(description.contains("<a id=\"threading-model\"></a>"));
+
+    // Without a title in its metadata, a page is named after its file
+    let guide = context("guide.md");
+    let content = guide["content"].as_str().unwrap();
+    assert!(content.contains("<a id=\"retry-policy\"></a>"));
+    assert!(content.contains(
+        "Connect with <a href=\"/net/function.connect.html\"><code>net::connect</code></a>"
+    ));
+    assert!(content.contains(
+        "as <a href=\"/net/record.Socket.html#threading-model\">threading-model</a> says"
+    ));
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn comment_aliases_are_expanded() {
+    let fixture = Fixture::new("comment_aliases");
+
+    let dump = fixture.require_dump();
+
+    let queue = &find_namespace(&dump["root"], "jobs")["records"][0]["comment"];
+    assert_eq!(queue["since"], "1.4");
+    assert_eq!(queue["notes"], json!([["warning", "Not thread-safe, lock the queue first."]]));
+
+    fixture.edit_config(
+        "[comments.aliases]",
+        "[comments.aliases]\nping = \"@pong\"\npong = \"@ping\"",
+    );
+
+    let out = fixture.cppdoc(&["--dump-json"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains(
+        "the alias “ping” still expands to aliases after 8 levels, it is probably recursive"
+    ));
+}
+
+#[test]
+fn comment_html_is_escaped_sanitized_or_allowed() {
+    let fixture = Fixture::new("comment_html");
+
+    let description = |fixture: &Fixture| -> String {
+        let out = fixture.build_with(&["--debug-context", "net::ports"]);
+        let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+        context["function"]["comment"]["description"].as_str().unwrap().to_string()
+    };
+
+    let escaped = description(&fixture);
+    assert!(escaped.contains("a vector&lt;int&gt; of the &lt;b&gt;open&lt;/b&gt; ports"));
+    assert!(escaped.contains("<a href=\"https://example.com/ports\">"));
+    assert!(escaped.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(escaped.contains("<code>probe&lt;int&gt;</code>"));
+
+    // Extra pages keep their HTML whatever the mode
+    let out = fixture.build_with(&["--debug-context", "notes.md"]);
+    let notes: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(notes["content"]
+        .as_str()
+        .unwrap()
+        .contains("<div class=\"callout\">Ports are scanned every minute.</div>"));
+
+    fixture.edit_config("[output]", "[output]\ncomment_html = \"escape\"");
+
+    let mut mode = "escape";
+    let mut with_mode = |new: &'static str| {
+        fixture.edit_config(
+            &format!("comment_html = \"{mode}\""),
+            &format!("comment_html = \"{new}\""),
+        );
+        mode = new;
+    };
+
+    with_mode("sanitize");
+    let sanitized = description(&fixture);
+    assert!(sanitized.contains("a vector&lt;int&gt; of the <b>open</b> ports"));
+    assert!(sanitized.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(sanitized.contains("&lt;i onclick=\"steal()\"&gt;Scanned</i>"));
+
+    with_mode("allow");
+    assert!(description(&fixture).contains("<script>alert(1)</script>"));
+
+    // An unknown mode is refused when the configuration is read, before anything is parsed
+    with_mode("strip");
+    let out = fixture.cppdoc(&["--debug-context", "net::ports"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains(
+        "output.comment_html is “strip”, it must be “escape”, “allow” or “sanitize”"
+    ));
+}
+
+#[test]
+fn images_are_copied_to_the_assets() {
+    let fixture = Fixture::new("images");
+
+    let out = fixture.build_with(&["--debug-context", "gfx::Pipeline"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let description = context["record"]["comment"]["description"].as_str().unwrap();
+
+    assert!(description.contains(
+        "<figure class=\"image\"><img src=\"/assets/pipeline.png\" alt=\"The stages of a frame\">\
+         <figcaption>The stages of a frame</figcaption></figure>"
+    ));
+    assert!(description.contains("<img src=\"/assets/diagrams/passes.png\" alt=\"the passes\""));
+    assert!(description.contains("<img src=\"https://example.com/logo.png\" alt=\"logo\""));
+    assert!(!description.contains("pipeline.eps"));
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "“gfx::draw” shows the image “missing.png”, which isn't in output.image_dirs"
+    ));
+
+    let output = fixture.build();
+    assert_eq!(std::fs::read_to_string(output.join("assets/pipeline.png")).unwrap(), "pipeline");
+    assert_eq!(
+        std::fs::read_to_string(output.join("assets/diagrams/passes.png")).unwrap(),
+        "passes"
+    );
+}
+
+#[test]
+fn strikethrough_and_task_lists_are_rendered() {
+    let fixture = Fixture::new("changelog");
+
+    let out = fixture.build_with(&["--debug-context", "changelog.md"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let content = context["content"].as_str().unwrap();
+
+    assert!(content.contains("The <del>blocking</del> asynchronous client"));
+    assert!(content.contains("<li>Plain item with <del>struck</del> text</li>"));
+    assert!(content.contains("<del>Legacy transport</del>, dropped"));
+    assert!(content.contains("Removed the <del><code>poll</code></del> backend"));
+
+    // Top-level and nested items alike, the boxes can't be ticked on the page
+    assert_eq!(content.matches("<li><input type=\"checkbox\" class=\"task\" disabled").count(), 4);
+    assert_eq!(content.matches("class=\"task\" disabled checked>").count(), 2);
+    assert!(!content.contains("[x]") && !content.contains("[ ]"));
+}
+
+#[test]
+fn math_is_typeset_with_katex() {
+    let fixture = Fixture::new("math");
+
+    let out = fixture.build_with(&["--debug-context", "dsp::gain"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    let description = context["function"]["comment"]["description"].as_str().unwrap();
+
+    assert!(description.contains("<div class=\"math\">"));
+    assert!(description.contains("G_{dB} = 20 \\log_{10} \\frac{|y|}{|x|}"));
+    assert!(description.contains("<code>$GAIN</code>"));
+
+    let brief = context["function"]["comment"]["brief"].as_str().unwrap();
+    assert!(brief.contains("<span class=\"math\">y = x \\cdot 10^{g/20}</span>"));
+
+    fixture.build();
+    let page = fixture.read_output("dsp/function.gain.html");
+    assert_eq!(page.matches("katex.min.js").count(), 1);
+    assert!(page.find("katex.min.css").unwrap() < page.find("</head>").unwrap());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn concepts_are_parsed_with_any_libclang() {
+    // libclang 18+ reports `ConceptDecl`s, older versions unexposed declarations, both must
+    // give the same concepts
+    let fixture = Fixture::new("concepts");
+
+    let dump = fixture.require_dump();
+
+    let geo = &dump["root"]["namespaces"][0];
+    let concepts = geo["concepts"].as_array().unwrap();
+    assert_eq!(concepts.len(), 2);
+
+    let shape = &concepts[0];
+    assert_eq!(shape["name"], "Shape");
+    assert_eq!(shape["template"]["parameters"][0]["name"], "T");
+    assert_eq!(shape["constraint"], "requires(const T & shape) {shape.area();}");
+    assert_eq!(shape["comment"]["brief"], "Types with an area");
+
+    let scalable = &concepts[1];
+    assert_eq!(scalable["name"], "Scalable");
+    assert_eq!(scalable["template"]["parameters"].as_array().unwrap().len(), 2);
+    assert!(scalable["constraint"].as_str().unwrap().starts_with("Shape<T> &&"));
+
+    assert_eq!(dump["index"]["geo::Shape"][0]["kind"], "concept");
+    assert_eq!(dump["index"]["geo::Scalable"][0]["kind"], "concept");
+
+    // The variable template isn't mistaken for a concept, nor the concepts for variables
+    let variables = geo["variables"].as_array().unwrap();
+    assert_eq!(variables.len(), 1);
+    assert_eq!(variables[0]["name"], "is_shape_v");
+}
+
+#[cfg(not(feature = "scripting"))]
+#[test]
+fn template_scripts_need_the_scripting_feature() {
+    let fixture = Fixture::new("aliases");
+
+    fixture.edit_config("[output]", "[output]\ntemplate_script = \"helpers.rhai\"");
+
+    let out = fixture.cppdoc(&[]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains(
+        "output.template_script is “helpers.rhai”, but cppdoc was built without the “scripting” \
+         feature"
+    ));
+}
+
+#[test]
+fn debug_context_can_be_written_to_a_file() {
+    let fixture = Fixture::new("basic");
+    let dump = fixture.expected().join("output.json");
+
+    let out = fixture.cppdoc(&[
+        "--from-json",
+        dump.to_str().unwrap(),
+        "--debug-context",
+        "Point",
+        "--debug-context-output",
+        "point.json",
+    ]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("\"record\""));
+
+    // Without a file, the context is all there is on stdout
+    let out = fixture.cppdoc(&["--from-json", dump.to_str().unwrap(), "--debug-context", "Point"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(context["record"]["name"], "Point");
+
+    let context: Value =
+        serde_json::from_str(&std::fs::read_to_string(fixture.dir.join("point.json")).unwrap())
+            .unwrap();
+    assert_eq!(context["record"]["name"], "Point");
+    assert_eq!(context["project"]["name"], "Basic");
+}
+
+#[test]
+fn entities_link_to_their_directory_page() {
+    let fixture = Fixture::new("directories");
+
+    let out = fixture.build_with(&["--debug-context", "net::Socket"]);
+
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(
+        context["directory"],
+        "<a href=\"/dir.include-net.html\"><code>include/net/</code></a>"
+    );
+
+    let page = fixture.read_output("dir.include-net.html");
+    assert!(page.contains("Sockets and the streams writing to them"));
+    assert!(page.contains("<code>net::operator&lt;&lt;</code>"));
+
+    // `@directory` in a comment is not a `@dir` command
+    assert!(!fixture.output_dir().join("dir.include.html").exists());
+}
+
+#[test]
+fn footers_credit_the_project_and_link_its_license() {
+    let fixture = Fixture::new("basic");
+
+    fixture.edit_config(
+        "version = \"1.0.0\"",
+        "version = \"1.0.0\"\n\
+         copyright = \"© {year} Basic authors\"\n\
+         license = { name = \"MIT\", url = \"https://opensource.org/licenses/MIT\" }\n\
+         license_file = \"LICENSE.md\"",
+    );
+    std::fs::write(
+        fixture.dir.join("LICENSE.md"),
+        "---\ntitle: MIT License\n---\n\nPermission is hereby granted, free of charge.\n",
+    )
+    .unwrap();
+
+    let out = fixture.build_with(&["--debug-context", "MIT License"]);
+
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(context["project"]["copyright"], "© 2023 Basic authors");
+    assert_eq!(
+        context["project"]["license"],
+        json!({ "name": "MIT", "url": "https://opensource.org/licenses/MIT" })
+    );
+    assert!(context["content"]
+        .as_str()
+        .unwrap()
+        .contains("Permission is hereby granted, free of charge."));
+
+    assert!(fixture.output_dir().join("license.html").is_file());
+    assert!(search_entries(&fixture)
+        .iter()
+        .any(|(name, link, _)| name == "MIT License" && link == "license"));
+
+    // Plain text licenses are shown as they are, under a generic title
+    fixture.edit_config("LICENSE.md", "LICENSE");
+    std::fs::write(fixture.dir.join("LICENSE"), "Copyright <c> 2023 & later\n").unwrap();
+
+    let out = fixture.build_with(&["--debug-context", "License"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(context["content"], "<pre>Copyright &lt;c&gt; 2023 &amp; later\n</pre>");
+}
+
+#[test]
+fn oversized_binary_and_unlisted_extra_pages_are_skipped() {
+    let fixture = Fixture::new("basic");
+
+    let pages = fixture.dir.join("pages");
+    std::fs::create_dir_all(&pages).unwrap();
+    std::fs::write(pages.join("guide.md"), "---\ntitle: Guide\n---\n\nHow to draw shapes.\n")
+        .unwrap();
+    std::fs::write(pages.join("big.md"), "x".repeat(200)).unwrap();
+    std::fs::write(pages.join("binary.md"), b"PK\x03\x04\xff\xfe").unwrap();
+    std::fs::write(pages.join("notes.txt"), "Notes\n").unwrap();
+
+    fixture.edit_config("[pages]", "[pages]\nextra = [\"pages/*\"]\nmax_size = 64");
+
+    let out = fixture.build_with(&[]);
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("file is 200 bytes, over the 64 bytes limit of pages.max_size"));
+    assert!(stderr.contains("not valid UTF-8 (invalid byte at offset 4), is it a binary file?"));
+    assert!(stderr.contains("extension “txt” is not in pages.extensions"));
+
+    let output = fixture.output_dir();
+    assert!(output.join("pages/guide.md.html").is_file());
+    assert!(!output.join("pages/big.md.html").exists());
+    assert!(!output.join("pages/binary.md.html").exists());
+    assert!(!output.join("pages/notes.txt.html").exists());
+
+    let links = search_entries(&fixture)
+        .into_iter()
+        .map(|(_, link, _)| link)
+        .filter(|link| link.starts_with("pages/"))
+        .collect::<Vec<_>>();
+    assert_eq!(links, ["pages/guide.md"]);
+
+    // Listed extensions replace the default markdown ones
+    fixture.edit_config("max_size = 64", "max_size = 64\nextensions = [\"TXT\"]");
+
+    let out = fixture.build_with(&[]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("extension “md” is not in pages.extensions"));
+    assert!(search_entries(&fixture)
+        .iter()
+        .any(|(name, link, _)| name == "notes.txt" && link == "pages/notes.txt"));
+    assert!(!search_entries(&fixture)
+        .iter()
+        .any(|(_, link, _)| link == "pages/guide.md"));
+}
+
+#[test]
+fn manifests_list_written_files_and_prune_the_rest() {
+    use sha2::{Digest, Sha256};
+
+    let fixture = Fixture::new("basic");
+    let output = fixture.output_dir();
+
+    std::fs::create_dir_all(output.join("old")).unwrap();
+    std::fs::write(output.join("stale.html"), "stale").unwrap();
+    std::fs::write(output.join("old/page.html"), "old").unwrap();
+
+    let out = fixture.build_with(&["--prune", "--dry-run"]);
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Would remove stale.html"));
+    assert!(stdout.contains("Would remove old/page.html"));
+    assert!(!stdout.contains("record.Point.html"));
+    assert!(output.join("stale.html").is_file());
+    assert!(output.join("old/page.html").is_file());
+
+    let args = ["--prune", "--debug-context", "Point", "--debug-context-output", "docs/point.json"];
+    let out = fixture.build_with(&args);
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Removed stale.html"));
+    assert!(stdout.contains("Removed old/page.html"));
+    assert!(!output.join("stale.html").exists());
+    assert!(!output.join("old/page.html").exists());
+
+    // The dumped context is kept, but as a debugging artifact it isn't part of the site
+    assert!(output.join("point.json").is_file());
+
+    let manifest = fixture.read_json("manifest.json");
+    let entries = manifest.as_array().unwrap();
+    let entry = |path: &str| {
+        entries
+            .iter()
+            .find(|e| e["path"] == path)
+            .unwrap_or_else(|| panic!("{path} is not in the manifest"))
+    };
+
+    let paths = entries.iter().map(|e| e["path"].as_str().unwrap()).collect::<Vec<_>>();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+    assert!(!paths.contains(&"manifest.json"));
+    assert!(!paths.contains(&"stale.html"));
+    assert!(!paths.contains(&"point.json"));
+
+    assert_eq!(entry("record.Point.html")["category"], "entity-page");
+    assert_eq!(entry("search_index.json")["category"], "search");
+    assert_eq!(entry("style.css")["category"], "static");
+
+    let contents = std::fs::read(output.join("record.Point.html")).unwrap();
+    assert_eq!(entry("record.Point.html")["size"], contents.len());
+    assert_eq!(
+        entry("record.Point.html")["sha256"],
+        format!("{:x}", Sha256::digest(&contents))
+    );
+}
+
+#[test]
+fn concept_pages_have_their_own_template() {
+    let fixture = Fixture::new("templates");
+
+    let out = fixture.build_with(&["--debug-context", "tpl::Addable"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    assert_eq!(context["concept"]["name"], "Addable");
+    assert_eq!(context["defined_in"], "include/templates.hpp:33");
+    assert_eq!(context["include_line"], "#include <include/templates.hpp>");
+    assert!(context["deprecation"].is_null());
+    assert!(context["listing"]
+        .as_str()
+        .unwrap()
+        .contains("<span class=\"k\">concept</span> Addable = requires"));
+    assert!(fixture.output_dir().join("tpl/concept.Addable.html").is_file());
+}
+
+#[test]
+#[ignore = "needs libclang"]
+fn typedef_names_qualify_the_members_of_unnamed_records() {
+    let fixture = Fixture::new("typedefs");
+
+    let dump = fixture.require_dump();
+    let records = dump["root"]["records"].as_array().unwrap();
+    assert_eq!(records.len(), 1);
+
+    let handle = &records[0];
+    assert_eq!(handle["name"], "handle_t");
+    assert_eq!(handle["methods"][0]["name"], "open");
+    assert_eq!(handle["methods"][0]["namespace"], "handle_t");
+    assert_eq!(handle["nested"][0]["Record"]["namespace"], "handle_t");
+
+    assert_eq!(dump["index"]["handle_t"][0]["kind"], "record");
+    assert_eq!(dump["index"]["handle_t::position"][0]["kind"], "record");
+    assert!(!dump.to_string().contains("unnamed struct"));
+}
+
+#[test]
+fn variable_pages_have_their_own_template() {
+    let fixture = Fixture::new("templates");
+
+    let out = fixture.build_with(&["--debug-context", "tpl::is_small_v"]);
+    let context: Value = serde_json::from_slice(&out.stdout).unwrap();
+
+    assert_eq!(context["variable"]["name"], "is_small_v");
+    assert_eq!(context["defined_in"], "include/templates.hpp:42");
+    assert_eq!(context["include_line"], "#include <include/templates.hpp>");
+    assert!(context["deprecation"].is_null());
+    assert!(context["listing"]
+        .as_str()
+        .unwrap()
+        .contains("<span class=\"k\">constexpr</span> "));
+}